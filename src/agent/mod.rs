@@ -0,0 +1,480 @@
+//! Minimal client of the ssh-agent protocol, talking to the agent listening on `SSH_AUTH_SOCK`
+//!
+//! See draft-miller-ssh-agent for the details of the protocol
+
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+use color_eyre::eyre::{eyre, Result};
+use ssh_encoding::{Decode, Encode};
+use ssh_key::{PrivateKey, PublicKey};
+
+use crate::keys::convert;
+
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENT_SUCCESS: u8 = 6;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_ADD_IDENTITY: u8 = 17;
+const SSH_AGENTC_REMOVE_IDENTITY: u8 = 18;
+const SSH_AGENTC_REMOVE_ALL_IDENTITIES: u8 = 19;
+const SSH_AGENTC_ADD_ID_CONSTRAINED: u8 = 25;
+
+const SSH_AGENT_CONSTRAIN_LIFETIME: u8 = 1;
+const SSH_AGENT_CONSTRAIN_CONFIRM: u8 = 2;
+
+/// Agents refuse messages bigger than this
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+#[cfg(unix)]
+type AgentStream = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+type AgentStream = std::fs::File;
+
+/// Constraints of an identity added to the agent
+#[derive(Clone, Copy, Default, Debug)]
+pub struct AgentConstraints {
+    /// Seconds after which the agent forgets the identity
+    pub lifetime: Option<u32>,
+    /// Ask for confirmation (through ssh-askpass) every time the identity is used
+    pub confirm: bool,
+}
+
+pub struct AgentClient {
+    stream: AgentStream,
+}
+
+impl AgentClient {
+    /// Connects to the agent listening on `SSH_AUTH_SOCK`
+    pub fn connect() -> Result<Self> {
+        let socket_path = std::env::var("SSH_AUTH_SOCK")
+            .map_err(|_| eyre!("SSH_AUTH_SOCK is not set. Is ssh-agent running?"))?;
+
+        Self::connect_to(&socket_path)
+    }
+
+    /// Connects to the agent listening on the given socket
+    pub fn connect_to(socket_path: &str) -> Result<Self> {
+        #[cfg(unix)]
+        let stream = AgentStream::connect(socket_path);
+        #[cfg(not(unix))]
+        let stream = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(socket_path);
+
+        let stream = stream
+            .map_err(|e| eyre!("failed to connect to the agent at '{}': {}", socket_path, e))?;
+
+        Ok(Self { stream })
+    }
+
+    /// Checks if there's an agent listening on `SSH_AUTH_SOCK`
+    pub fn is_available() -> bool {
+        Self::connect().is_ok()
+    }
+
+    /// Sends a request and returns the type and the contents of the response
+    fn request(&mut self, msg_type: u8, contents: &[u8]) -> Result<(u8, Vec<u8>)> {
+        self.stream
+            .write_all(&encode_message(msg_type, contents))
+            .map_err(|e| eyre!("failed to write to the agent: {}", e))?;
+
+        let mut len = [0u8; 4];
+        self.stream
+            .read_exact(&mut len)
+            .map_err(|e| eyre!("failed to read from the agent: {}", e))?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            return Err(eyre!("invalid agent response length: {}", len));
+        }
+
+        let mut response = vec![0u8; len];
+        self.stream
+            .read_exact(&mut response)
+            .map_err(|e| eyre!("failed to read from the agent: {}", e))?;
+        let contents = response.split_off(1);
+
+        Ok((response[0], contents))
+    }
+
+    /// Sends a request whose only expected response is either success or failure
+    fn simple_request(&mut self, msg_type: u8, contents: &[u8], action: &str) -> Result<()> {
+        match self.request(msg_type, contents)? {
+            (SSH_AGENT_SUCCESS, _) => Ok(()),
+            (SSH_AGENT_FAILURE, _) => Err(eyre!("the agent refused to {}", action)),
+            (other, _) => Err(eyre!("unexpected agent response: {}", other)),
+        }
+    }
+
+    /// Lists the identities (public keys and their comments) loaded in the agent, like `ssh-add -L`
+    pub fn list_identities(&mut self) -> Result<Vec<PublicKey>> {
+        let (msg_type, contents) = self.request(SSH_AGENTC_REQUEST_IDENTITIES, &[])?;
+        if msg_type != SSH_AGENT_IDENTITIES_ANSWER {
+            return Err(eyre!("the agent refused to list its identities"));
+        }
+
+        decode_identities(&contents)
+    }
+
+    /// Adds a decrypted private key to the agent, like `ssh-add`
+    pub fn add_identity(
+        &mut self,
+        private_key: &PrivateKey,
+        constraints: AgentConstraints,
+    ) -> Result<()> {
+        if private_key.is_encrypted() {
+            return Err(eyre!("the private key must be decrypted first"));
+        }
+
+        let (msg_type, contents) = encode_add_identity(private_key, constraints)?;
+        self.simple_request(msg_type, &contents, "add the identity")
+    }
+
+    /// Removes an identity from the agent, like `ssh-add -d`
+    pub fn remove_identity(&mut self, public_key: &PublicKey) -> Result<()> {
+        let contents = encode_remove_identity(public_key)?;
+        self.simple_request(SSH_AGENTC_REMOVE_IDENTITY, &contents, "remove the identity")
+    }
+
+    /// Removes all the identities from the agent, like `ssh-add -D`
+    pub fn remove_all_identities(&mut self) -> Result<()> {
+        self.simple_request(
+            SSH_AGENTC_REMOVE_ALL_IDENTITIES,
+            &[],
+            "remove all the identities",
+        )
+    }
+}
+
+/// Frames a message of the agent protocol: its length, its type and its contents
+fn encode_message(msg_type: u8, contents: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(contents.len() + 5);
+    msg.extend_from_slice(&(contents.len() as u32 + 1).to_be_bytes());
+    msg.push(msg_type);
+    msg.extend_from_slice(contents);
+
+    msg
+}
+
+/// Reads the identities of an `SSH_AGENT_IDENTITIES_ANSWER`
+fn decode_identities(contents: &[u8]) -> Result<Vec<PublicKey>> {
+    let decode_err = |e: ssh_encoding::Error| eyre!("invalid agent response: {}", e);
+    let reader = &mut &contents[..];
+    let identities_count = u32::decode(reader).map_err(decode_err)?;
+
+    let mut identities = vec![];
+    for _ in 0..identities_count {
+        let key_blob = Vec::<u8>::decode(reader).map_err(decode_err)?;
+        let comment = String::decode(reader).map_err(decode_err)?;
+
+        // Keys the crate can't parse (e.g. certificates) are skipped
+        if let Ok(mut public_key) = PublicKey::from_bytes(&key_blob) {
+            public_key.set_comment(comment);
+            identities.push(public_key);
+        }
+    }
+
+    Ok(identities)
+}
+
+/// Builds the request adding the private key with the given constraints
+///
+/// # Returns
+/// The type and the contents of the request
+fn encode_add_identity(
+    private_key: &PrivateKey,
+    constraints: AgentConstraints,
+) -> Result<(u8, Vec<u8>)> {
+    let encode_err = |e: ssh_encoding::Error| eyre!("failed to encode private key: {}", e);
+    let mut contents = vec![];
+    private_key
+        .key_data()
+        .encode(&mut contents)
+        .map_err(encode_err)?;
+    private_key
+        .comment()
+        .encode(&mut contents)
+        .map_err(encode_err)?;
+
+    let mut msg_type = SSH_AGENTC_ADD_IDENTITY;
+    if let Some(lifetime) = constraints.lifetime {
+        msg_type = SSH_AGENTC_ADD_ID_CONSTRAINED;
+        contents.push(SSH_AGENT_CONSTRAIN_LIFETIME);
+        contents.extend_from_slice(&lifetime.to_be_bytes());
+    }
+    if constraints.confirm {
+        msg_type = SSH_AGENTC_ADD_ID_CONSTRAINED;
+        contents.push(SSH_AGENT_CONSTRAIN_CONFIRM);
+    }
+
+    Ok((msg_type, contents))
+}
+
+/// Builds the contents of the request removing the identity with the given public key
+fn encode_remove_identity(public_key: &PublicKey) -> Result<Vec<u8>> {
+    let key_blob = public_key
+        .to_bytes()
+        .map_err(|e| eyre!("failed to encode public key: {}", e))?;
+
+    let mut contents = vec![];
+    key_blob
+        .encode(&mut contents)
+        .map_err(|e| eyre!("failed to encode public key: {}", e))?;
+
+    Ok(contents)
+}
+
+/// Reads the private key stored at the given path, decrypting it with the passphrase if needed, and
+/// adds it to the agent. Like `ssh-add`, keys without a comment are added with their path as the
+/// comment
+///
+/// # Returns
+/// The public key of the added identity
+pub fn add_key_file(
+    private_key_path: &Path,
+    passphrase: &[u8],
+    constraints: AgentConstraints,
+) -> Result<PublicKey> {
+    let mut private_key = convert::read_private_key(private_key_path, passphrase)?;
+    if private_key.comment().is_empty() {
+        private_key.set_comment(private_key_path.to_string_lossy());
+    }
+
+    AgentClient::connect()?.add_identity(&private_key, constraints)?;
+
+    let mut public_key = private_key.public_key().clone();
+    public_key.set_comment(private_key.comment());
+
+    Ok(public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        process::{Child, Command, Stdio},
+        thread,
+        time::Duration,
+    };
+
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    use super::*;
+    use crate::{commands::ssh_keygen::PublicKeyType, keys::generate::generate_key_pair};
+
+    fn test_private_key(seed: u64, comment: &str) -> PrivateKey {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        generate_key_pair(&mut rng, PublicKeyType::ED25519, comment, b"")
+            .unwrap()
+            .private_key
+    }
+
+    fn encode_identities(public_keys: &[PublicKey]) -> Vec<u8> {
+        let mut contents = vec![];
+        (public_keys.len() as u32).encode(&mut contents).unwrap();
+        for public_key in public_keys {
+            public_key
+                .to_bytes()
+                .unwrap()
+                .encode(&mut contents)
+                .unwrap();
+            public_key.comment().encode(&mut contents).unwrap();
+        }
+
+        contents
+    }
+
+    #[test]
+    fn message_is_framed_with_its_length_and_type() {
+        assert_eq!(
+            encode_message(SSH_AGENTC_REQUEST_IDENTITIES, &[]),
+            [0, 0, 0, 1, SSH_AGENTC_REQUEST_IDENTITIES]
+        );
+        assert_eq!(
+            encode_message(SSH_AGENTC_REMOVE_IDENTITY, &[1, 2, 3]),
+            [0, 0, 0, 4, SSH_AGENTC_REMOVE_IDENTITY, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn identities_are_decoded_with_their_comments() {
+        let first = test_private_key(1, "first@host").public_key().clone();
+        let second = test_private_key(2, "").public_key().clone();
+
+        let identities =
+            decode_identities(&encode_identities(&[first.clone(), second.clone()])).unwrap();
+
+        assert_eq!(identities, [first, second]);
+        assert_eq!(identities[0].comment(), "first@host");
+        assert!(decode_identities(&encode_identities(&[]))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn truncated_identities_are_rejected() {
+        let public_key = test_private_key(1, "user@host").public_key().clone();
+        let contents = encode_identities(&[public_key]);
+
+        assert!(decode_identities(&contents[..contents.len() - 1]).is_err());
+        assert!(decode_identities(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn add_identity_is_constrained_only_when_needed() {
+        let private_key = test_private_key(1, "user@host");
+        let (msg_type, unconstrained) =
+            encode_add_identity(&private_key, AgentConstraints::default()).unwrap();
+        assert_eq!(msg_type, SSH_AGENTC_ADD_IDENTITY);
+
+        let reader = &mut &unconstrained[..];
+        let key_data = ssh_key::private::KeypairData::decode(reader).unwrap();
+        assert_eq!(&key_data, private_key.key_data());
+        assert_eq!(String::decode(reader).unwrap(), "user@host");
+        assert!(reader.is_empty());
+
+        let constraints = AgentConstraints {
+            lifetime: Some(600),
+            confirm: true,
+        };
+        let (msg_type, constrained) = encode_add_identity(&private_key, constraints).unwrap();
+        assert_eq!(msg_type, SSH_AGENTC_ADD_ID_CONSTRAINED);
+        assert_eq!(&constrained[..unconstrained.len()], unconstrained);
+        assert_eq!(
+            &constrained[unconstrained.len()..],
+            [
+                SSH_AGENT_CONSTRAIN_LIFETIME,
+                0,
+                0,
+                2,
+                88,
+                SSH_AGENT_CONSTRAIN_CONFIRM
+            ]
+        );
+
+        let confirm_only = AgentConstraints {
+            lifetime: None,
+            confirm: true,
+        };
+        let (msg_type, contents) = encode_add_identity(&private_key, confirm_only).unwrap();
+        assert_eq!(msg_type, SSH_AGENTC_ADD_ID_CONSTRAINED);
+        assert_eq!(
+            &contents[unconstrained.len()..],
+            [SSH_AGENT_CONSTRAIN_CONFIRM]
+        );
+    }
+
+    #[test]
+    fn remove_identity_holds_the_public_key_blob() {
+        let public_key = test_private_key(1, "user@host").public_key().clone();
+        let contents = encode_remove_identity(&public_key).unwrap();
+
+        let reader = &mut &contents[..];
+        assert_eq!(
+            Vec::<u8>::decode(reader).unwrap(),
+            public_key.to_bytes().unwrap()
+        );
+        assert!(reader.is_empty());
+    }
+
+    /// An ssh-agent running in the foreground, killed when dropped
+    struct TestAgent {
+        process: Child,
+        socket_path: String,
+        _dir: tempfile::TempDir,
+    }
+
+    impl TestAgent {
+        fn start() -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            let socket_path = dir.path().join("agent.sock").to_string_lossy().into_owned();
+            let process = Command::new("ssh-agent")
+                .args(["-D", "-a", &socket_path])
+                .stdout(Stdio::null())
+                .spawn()
+                .expect("failed to start ssh-agent");
+
+            for _ in 0..50 {
+                if Path::new(&socket_path).exists() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+
+            Self {
+                process,
+                socket_path,
+                _dir: dir,
+            }
+        }
+
+        fn connect(&self) -> AgentClient {
+            AgentClient::connect_to(&self.socket_path).unwrap()
+        }
+    }
+
+    impl Drop for TestAgent {
+        fn drop(&mut self) {
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+    }
+
+    #[test]
+    #[ignore = "needs ssh-agent"]
+    fn identities_are_managed_in_a_local_agent() {
+        let agent = TestAgent::start();
+        let mut client = agent.connect();
+        assert!(client.list_identities().unwrap().is_empty());
+
+        let unconstrained = test_private_key(1, "unconstrained");
+        let with_lifetime = test_private_key(2, "with-lifetime");
+        let with_confirm = test_private_key(3, "with-confirm");
+        client
+            .add_identity(&unconstrained, AgentConstraints::default())
+            .unwrap();
+        client
+            .add_identity(
+                &with_lifetime,
+                AgentConstraints {
+                    lifetime: Some(600),
+                    confirm: false,
+                },
+            )
+            .unwrap();
+        client
+            .add_identity(
+                &with_confirm,
+                AgentConstraints {
+                    lifetime: Some(600),
+                    confirm: true,
+                },
+            )
+            .unwrap();
+
+        let identities = client.list_identities().unwrap();
+        let comments: Vec<_> = identities.iter().map(|key| key.comment()).collect();
+        assert_eq!(comments, ["unconstrained", "with-lifetime", "with-confirm"]);
+        assert_eq!(
+            identities[1].key_data(),
+            with_lifetime.public_key().key_data()
+        );
+
+        client.remove_identity(with_lifetime.public_key()).unwrap();
+        let comments: Vec<_> = client
+            .list_identities()
+            .unwrap()
+            .iter()
+            .map(|key| key.comment().to_owned())
+            .collect();
+        assert_eq!(comments, ["unconstrained", "with-confirm"]);
+        assert!(client.remove_identity(with_lifetime.public_key()).is_err());
+
+        // Each request also works on a new connection
+        let mut client = agent.connect();
+        client.remove_all_identities().unwrap();
+        assert!(client.list_identities().unwrap().is_empty());
+    }
+}
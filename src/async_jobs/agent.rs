@@ -0,0 +1,103 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use ssh_key::{HashAlg, PublicKey};
+
+use crate::{
+    agent::{self, AgentClient, AgentConstraints},
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously adds a private key to the agent, creating a blocking thread (the key derivation
+/// of encrypted keys is CPU bound) that communicates via the msg_tx
+pub fn add_to_agent(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    constraints: AgentConstraints,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_add_to_agent(private_key_path, passphrase, constraints, msg_tx)
+    });
+}
+
+fn handle_add_to_agent(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    constraints: AgentConstraints,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match agent::add_key_file(&private_key_path, passphrase.as_bytes(), constraints) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when adding key to the agent"),
+        Ok(public_key) => {
+            let mut text = format!(
+                "Identity added: {} ({})",
+                public_key.fingerprint(HashAlg::Sha256),
+                public_key.comment()
+            );
+            if let Some(lifetime) = constraints.lifetime {
+                text.push_str(&format!("\nLifetime set to {} seconds", lifetime));
+            }
+            if constraints.confirm {
+                text.push_str("\nThe user must confirm each use of the key");
+            }
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after adding key to the agent")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshAgentIdentities)
+        .expect("failed to send agent refresh message after adding key to the agent")
+}
+
+/// Asynchronously removes an identity from the agent
+pub fn remove_identity(public_key: PublicKey, msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_remove_identity(public_key, msg_tx));
+}
+
+fn handle_remove_identity(public_key: PublicKey, msg_tx: mpsc::Sender<Message>) {
+    match AgentClient::connect().and_then(|mut agent| agent.remove_identity(&public_key)) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when removing identity from the agent"),
+        Ok(_) => {
+            let text = format!(
+                "Identity removed: {} ({})",
+                public_key.fingerprint(HashAlg::Sha256),
+                public_key.comment()
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after removing identity from the agent")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshAgentIdentities)
+        .expect("failed to send agent refresh message after removing identity from the agent")
+}
+
+/// Asynchronously removes all the identities from the agent
+pub fn remove_all_identities(msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_remove_all_identities(msg_tx));
+}
+
+fn handle_remove_all_identities(msg_tx: mpsc::Sender<Message>) {
+    match AgentClient::connect().and_then(|mut agent| agent.remove_all_identities()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when removing identities from the agent"),
+        Ok(_) => msg_tx
+            .send(Message::ShowPopup(Popup::WithCfg(
+                String::from("All identities removed"),
+                ColorVariant::Success,
+            )))
+            .expect("failed to send success message after removing identities from the agent"),
+    };
+    msg_tx
+        .send(Message::RefreshAgentIdentities)
+        .expect("failed to send agent refresh message after removing identities from the agent")
+}
@@ -0,0 +1,133 @@
+use crate::{
+    events::messages::Message,
+    model::sections_state::allowed_signers_state::{GitSigningRequest, GIT_NAMESPACE},
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{allowed_signers::AllowedSignersEntry, authorized_keys::KeyOption, files},
+};
+use color_eyre::eyre::{eyre, Context, Result};
+use std::{fs, sync::mpsc};
+
+/// Asynchronously appends an entry to the allowed_signers file, creating a green thread that
+/// communicates via the msg_tx
+pub fn add_entry(entry: AllowedSignersEntry, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(
+        async move { handle_update_result(files::add_allowed_signers_entry(&entry), msg_tx) },
+    );
+}
+
+/// Asynchronously replaces an entry of the allowed_signers file, or removes it if there's no new
+/// entry, creating a green thread that communicates via the msg_tx
+pub fn update_entry(
+    old_entry: AllowedSignersEntry,
+    new_entry: Option<AllowedSignersEntry>,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::spawn(async move {
+        handle_update_result(
+            files::update_allowed_signers_entry(&old_entry, new_entry.as_ref()),
+            msg_tx,
+        )
+    });
+}
+
+/// Goes back to the list of allowed signers once the file is written
+fn handle_update_result(result: Result<()>, msg_tx: mpsc::Sender<Message>) {
+    match result {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when updating allowed signers"),
+        Ok(_) => {
+            msg_tx
+                .send(Message::CleanAllowedSignerInput)
+                .expect("failed to send clean input message after updating allowed signers");
+            msg_tx
+                .send(Message::ShowPopup(Popup::AllowedSigners))
+                .expect("failed to send show popup message after updating allowed signers")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshAllowedSigners)
+        .expect("failed to send allowed signers refresh message after updating allowed signers")
+}
+
+/// Asynchronously makes git sign with the requested key, adding it to the allowed_signers file if
+/// requested, creating a green thread that communicates via the msg_tx
+pub fn setup_git_signing(request: GitSigningRequest, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_setup_git_signing(request, msg_tx));
+}
+
+/// Allows the key to sign git objects as the principal, unless an entry already allows it
+///
+/// # Returns
+/// Whether the entry was added
+fn allow_git_signer(request: &GitSigningRequest, principal: &str) -> Result<bool> {
+    let pub_key_content = fs::read_to_string(&request.public_key_path)
+        .wrap_err("Failed to read public key content")?;
+    let entry = AllowedSignersEntry::from_public_key(
+        principal,
+        vec![KeyOption {
+            name: String::from("namespaces"),
+            value: Some(String::from(GIT_NAMESPACE)),
+        }],
+        &pub_key_content,
+    )
+    .ok_or_else(|| eyre!("the public key is not valid"))?;
+
+    let already_allowed = files::get_allowed_signers_entries()?
+        .iter()
+        .any(|existing| {
+            existing.same_key(&entry)
+                && !existing.is_cert_authority()
+                && existing.matches_principal(principal)
+                && existing.allows_namespace(GIT_NAMESPACE)
+        });
+    if already_allowed {
+        return Ok(false);
+    }
+
+    files::add_allowed_signers_entry(&entry)?;
+
+    Ok(true)
+}
+
+fn setup(request: &GitSigningRequest) -> Result<String> {
+    let allowed_signers_path = files::get_allowed_signers_path()?;
+
+    // The signer is added first, so git isn't left configured with a key it can't verify
+    let signer_summary = match &request.signer_principal {
+        Some(principal) if allow_git_signer(request, principal)? => format!(
+            "\n'{}' was added to {}",
+            principal,
+            allowed_signers_path.to_string_lossy()
+        ),
+        Some(principal) => format!("\n'{}' already was an allowed signer", principal),
+        None => String::new(),
+    };
+
+    let git_config_path =
+        files::configure_git_ssh_signing(&request.public_key_path, &allowed_signers_path)?;
+
+    Ok(format!(
+        "git now signs with {}\ngpg.format, user.signingkey and gpg.ssh.allowedSignersFile were set in {}{}",
+        request.public_key_path.to_string_lossy(),
+        git_config_path.to_string_lossy(),
+        signer_summary
+    ))
+}
+
+async fn handle_setup_git_signing(request: GitSigningRequest, msg_tx: mpsc::Sender<Message>) {
+    match setup(&request) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when setting up git signing"),
+        Ok(summary) => msg_tx
+            .send(Message::ShowPopup(Popup::WithCfg(
+                summary,
+                ColorVariant::Success,
+            )))
+            .expect("failed to send success message after setting up git signing"),
+    };
+    msg_tx
+        .send(Message::RefreshAllowedSigners)
+        .expect("failed to send allowed signers refresh message after setting up git signing")
+}
@@ -0,0 +1,69 @@
+use crate::{
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{authorized_keys::AuthorizedKeysEntry, files},
+};
+use std::sync::mpsc;
+
+/// Asynchronously appends an entry to the authorized_keys file, creating a green thread that
+/// communicates via the msg_tx
+pub fn add_entry(entry: AuthorizedKeysEntry, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_add_entry(entry, msg_tx));
+}
+
+async fn handle_add_entry(entry: AuthorizedKeysEntry, msg_tx: mpsc::Sender<Message>) {
+    match files::add_authorized_keys_entry(&entry) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when authorizing key"),
+        Ok(_) => {
+            let text = format!(
+                "{} ({}) is now authorized",
+                entry.fingerprint().unwrap_or(entry.key_type),
+                entry.comment.as_deref().unwrap_or("no comment")
+            );
+            msg_tx
+                .send(Message::CleanAuthorizedKeyInput)
+                .expect("failed to send clean input message after authorizing key");
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after authorizing key")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshAuthorizedKeys)
+        .expect("failed to send authorized keys refresh message after authorizing key")
+}
+
+/// Asynchronously replaces an entry of the authorized_keys file, or removes it if there's no new
+/// entry, creating a green thread that communicates via the msg_tx
+pub fn update_entry(
+    old_entry: AuthorizedKeysEntry,
+    new_entry: Option<AuthorizedKeysEntry>,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::spawn(handle_update_entry(old_entry, new_entry, msg_tx));
+}
+
+async fn handle_update_entry(
+    old_entry: AuthorizedKeysEntry,
+    new_entry: Option<AuthorizedKeysEntry>,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match files::update_authorized_keys_entry(&old_entry, new_entry.as_ref()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when updating authorized key"),
+        Ok(_) => {
+            msg_tx
+                .send(Message::CleanAuthorizedKeyInput)
+                .expect("failed to send clean input message after updating authorized key");
+            msg_tx
+                .send(Message::HidePopup)
+                .expect("failed to send hide popup message after updating authorized key")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshAuthorizedKeys)
+        .expect("failed to send authorized keys refresh message after updating authorized key")
+}
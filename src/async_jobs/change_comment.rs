@@ -0,0 +1,49 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    events::messages::Message,
+    keys::comment,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously changes the comment of a key pair, creating a blocking thread (the key
+/// derivation of encrypted keys is CPU bound) that communicates via the msg_tx
+pub fn change_comment(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    new_comment: String,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_change_comment(private_key_path, passphrase, new_comment, msg_tx)
+    });
+}
+
+fn handle_change_comment(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    new_comment: String,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match comment::change_comment(&mut OsRng, &private_key_path, passphrase.as_bytes(), &new_comment) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when changing key comment"),
+        Ok(_) => {
+            let text = format!(
+                "Comment of '{}' changed to '{}'",
+                private_key_path.to_string_lossy(),
+                new_comment
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after changing key comment")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after changing key comment")
+}
@@ -0,0 +1,90 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    events::messages::Message,
+    keys::passphrase,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously checks the current passphrase of a private key, creating a blocking thread (the
+/// key derivation is CPU bound) that communicates via the msg_tx
+///
+/// If the passphrase is correct, the user is prompted for the new one
+pub fn verify_passphrase(
+    private_key_path: PathBuf,
+    old_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_verify_passphrase(private_key_path, old_passphrase, msg_tx)
+    });
+}
+
+fn handle_verify_passphrase(
+    private_key_path: PathBuf,
+    old_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match passphrase::verify_passphrase(&private_key_path, old_passphrase.as_bytes()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when verifying passphrase"),
+        Ok(_) => msg_tx
+            .send(Message::ShowPopup(Popup::PromptPassphrase))
+            .expect("failed to send new passphrase prompt after verifying passphrase"),
+    }
+}
+
+/// Asynchronously changes the passphrase of a private key, creating a blocking thread (the key
+/// derivation is CPU bound) that communicates via the msg_tx
+pub fn change_passphrase(
+    private_key_path: PathBuf,
+    old_passphrase: SecretString,
+    new_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_change_passphrase(private_key_path, old_passphrase, new_passphrase, msg_tx)
+    });
+}
+
+fn handle_change_passphrase(
+    private_key_path: PathBuf,
+    old_passphrase: SecretString,
+    new_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    let result = passphrase::change_passphrase(
+        &mut OsRng,
+        &private_key_path,
+        old_passphrase.as_bytes(),
+        new_passphrase.as_bytes(),
+    );
+
+    match result {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when changing passphrase"),
+        Ok(converted) => {
+            let action = if new_passphrase.is_empty() {
+                "removed"
+            } else {
+                "changed"
+            };
+            let mut text = format!(
+                "Passphrase of '{}' {}",
+                private_key_path.to_string_lossy(),
+                action
+            );
+            if converted {
+                text.push_str("\nThe key was converted to the OpenSSH format");
+            }
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after changing passphrase")
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    events::messages::Message,
+    keys::convert::{self, KeyFormat},
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously exports a public key to the given format, creating a green thread that
+/// communicates via the msg_tx
+pub fn export_key(public_key_path: PathBuf, format: KeyFormat, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_export_key(public_key_path, format, msg_tx));
+}
+
+async fn handle_export_key(
+    public_key_path: PathBuf,
+    format: KeyFormat,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match convert::export_key_file(&public_key_path, format) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when exporting key"),
+        Ok(export_path) => {
+            let text = format!(
+                "Public key exported to {} format at '{}'",
+                format,
+                export_path.to_string_lossy()
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after exporting key")
+        }
+    };
+}
+
+/// Asynchronously imports a key into the OpenSSH format, creating a blocking thread (decrypting
+/// and encrypting keys is CPU bound) that communicates via the msg_tx
+pub fn import_key(
+    source_path: PathBuf,
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_import_key(source_path, private_key_path, passphrase, msg_tx)
+    });
+}
+
+fn handle_import_key(
+    source_path: PathBuf,
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match convert::import_key_file(&mut OsRng, &source_path, &private_key_path, passphrase.as_bytes()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when importing key"),
+        Ok(public_key_path) => {
+            let text = format!(
+                "'{}' imported as '{}'",
+                source_path.to_string_lossy(),
+                public_key_path.to_string_lossy()
+            );
+            msg_tx
+                .send(Message::CleanImportKeyInput)
+                .expect("failed to send clean input message after importing key");
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after importing key")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after importing key")
+}
@@ -0,0 +1,44 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use crate::{
+    events::messages::Message,
+    keys::derive,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously regenerates the public key of a private key, creating a blocking thread (the
+/// key derivation of encrypted keys is CPU bound) that communicates via the msg_tx
+pub fn derive_public_key(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || {
+        handle_derive_public_key(private_key_path, passphrase, msg_tx)
+    });
+}
+
+fn handle_derive_public_key(
+    private_key_path: PathBuf,
+    passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match derive::derive_public_key(&private_key_path, passphrase.as_bytes()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when deriving public key"),
+        Ok(public_key_path) => {
+            let text = format!(
+                "Public key written to '{}'",
+                public_key_path.to_string_lossy()
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after deriving public key")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after deriving public key")
+}
@@ -0,0 +1,54 @@
+use std::sync::mpsc;
+
+use crate::{
+    events::messages::Message,
+    keys::file_signature::{self, SignFileRequest, VerifyFileRequest},
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{dates, secret::SecretString},
+};
+
+/// Asynchronously signs a file, creating a blocking thread (decrypting the key and hashing the file
+/// are CPU bound) that communicates via the msg_tx
+pub fn sign_file(request: SignFileRequest, passphrase: SecretString, msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_sign_file(request, passphrase, msg_tx));
+}
+
+fn handle_sign_file(request: SignFileRequest, passphrase: SecretString, msg_tx: mpsc::Sender<Message>) {
+    let msg = match file_signature::sign_file(&request, passphrase.as_bytes()) {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(signature_path) => {
+            let text = format!(
+                "Signature of {} written to {}\nNamespace: \"{}\"",
+                request.file_path.to_string_lossy(),
+                signature_path.to_string_lossy(),
+                request.namespace
+            );
+            Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success))
+        }
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after signing file");
+}
+
+/// Asynchronously verifies the signature of a file, creating a blocking thread (hashing the file is
+/// CPU bound) that communicates via the msg_tx
+pub fn verify_file(request: VerifyFileRequest, msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_verify_file(request, msg_tx));
+}
+
+fn handle_verify_file(request: VerifyFileRequest, msg_tx: mpsc::Sender<Message>) {
+    let msg = match file_signature::verify_file(&request, dates::now()) {
+        Err(err) => Message::PrintError(format!(
+            "Could not verify signature of {}: {}",
+            request.file_path.to_string_lossy(),
+            err
+        )),
+        Ok(description) => Message::ShowPopup(Popup::WithCfg(description, ColorVariant::Success)),
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after verifying file signature");
+}
@@ -0,0 +1,69 @@
+use std::sync::mpsc;
+
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    agent::AgentClient,
+    commands::ssh_keygen::PublicKeyType,
+    events::messages::Message,
+    keys::generate,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{files, secret::SecretString},
+};
+
+/// Parameters of the key pair to generate natively
+pub struct NewKeyPair {
+    pub name: String,
+    pub key_type: PublicKeyType,
+    pub comment: String,
+    pub passphrase: SecretString,
+}
+
+/// Asynchronously generates a key pair, creating a blocking thread (key generation is CPU bound)
+/// that communicates via the msg_tx
+pub fn generate_key_pair(new_key: NewKeyPair, msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_generate_key_pair(new_key, msg_tx));
+}
+
+fn handle_generate_key_pair(new_key: NewKeyPair, msg_tx: mpsc::Sender<Message>) {
+    let result = files::get_user_ssh_dir().and_then(|ssh_dir| {
+        let private_key_path = ssh_dir.join(&new_key.name);
+        let key_pair = generate::generate_key_pair(
+            &mut OsRng,
+            new_key.key_type,
+            &new_key.comment,
+            new_key.passphrase.as_bytes(),
+        )?;
+        generate::write_key_pair(&key_pair, &private_key_path)?;
+
+        Ok((private_key_path, key_pair.fingerprint()))
+    });
+
+    match result {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when generating key pair"),
+        Ok((private_key_path, fingerprint)) => {
+            let text = format!(
+                "Your identification has been saved in {}\nThe key fingerprint is: {}",
+                private_key_path.to_string_lossy(),
+                fingerprint
+            );
+            if AgentClient::is_available() {
+                msg_tx
+                    .send(Message::CleanAddToAgentInput)
+                    .expect("failed to send agent form clean message after generating key pair");
+                msg_tx
+                    .send(Message::ShowPopup(Popup::AddNewKeyToAgent(text)))
+                    .expect("failed to send agent offer message after generating key pair")
+            } else {
+                msg_tx
+                    .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                    .expect("failed to send success message after generating key pair")
+            }
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after generating key pair")
+}
@@ -0,0 +1,72 @@
+use crate::{
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{files, known_hosts::KnownHostsEntry},
+};
+use std::sync::mpsc;
+
+/// Asynchronously trusts a new host CA, creating a green thread that communicates via the msg_tx
+pub fn add_cert_authority(
+    ca_pub_key_path: String,
+    host_pattern: String,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::spawn(handle_add_cert_authority(
+        ca_pub_key_path,
+        host_pattern,
+        msg_tx,
+    ));
+}
+
+async fn handle_add_cert_authority(
+    ca_pub_key_path: String,
+    host_pattern: String,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match files::add_cert_authority(&ca_pub_key_path, &host_pattern) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when adding cert authority"),
+        Ok(_) => {
+            let text = format!(
+                "'{}' is now trusted to sign the host keys of '{}'",
+                ca_pub_key_path, host_pattern
+            );
+            msg_tx
+                .send(Message::CleanNewCertAuthorityInput)
+                .expect("failed to send clean input message after adding cert authority");
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after adding cert authority")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshKnownHostsList)
+        .expect("failed to send known hosts refresh message after adding cert authority")
+}
+
+/// Asynchronously revokes a key of a known host, creating a green thread that communicates via
+/// the msg_tx
+pub fn revoke_host_key(host_key: KnownHostsEntry, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_revoke_host_key(host_key, msg_tx));
+}
+
+async fn handle_revoke_host_key(host_key: KnownHostsEntry, msg_tx: mpsc::Sender<Message>) {
+    match files::revoke_known_host_key(&host_key) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when revoking host key"),
+        Ok(_) => {
+            let text = format!(
+                "The {} key of '{}' was marked as revoked",
+                host_key.key_type, host_key.host_patterns
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after revoking host key")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshKnownHostsList)
+        .expect("failed to send known hosts refresh message after revoking host key")
+}
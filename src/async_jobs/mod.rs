@@ -1,2 +1,17 @@
-pub mod delete_key_pair;
+pub mod agent;
+pub mod allowed_signers;
+pub mod authorized_keys;
+pub mod change_comment;
+pub mod change_passphrase;
+pub mod convert_key;
 pub mod copy_to_clipboard;
+pub mod delete_key_pair;
+pub mod file_signature;
+pub mod derive_public_key;
+pub mod generate_key_pair;
+pub mod known_hosts_markers;
+pub mod permissions;
+pub mod rename_key;
+pub mod rotate_key;
+pub mod sign_certificate;
+pub mod trash;
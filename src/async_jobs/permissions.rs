@@ -0,0 +1,51 @@
+use std::sync::mpsc;
+
+use crate::{
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::permissions::{self, PermissionIssue},
+};
+
+/// Asynchronously audits the permissions of the ssh directory, creating a green thread that
+/// communicates via the msg_tx
+pub fn audit_permissions(msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_audit_permissions(msg_tx));
+}
+
+async fn handle_audit_permissions(msg_tx: mpsc::Sender<Message>) {
+    let msg = match permissions::audit_ssh_dir() {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(issues) if issues.is_empty() => Message::ShowPopup(Popup::WithCfg(
+            String::from("No permission problems found in ~/.ssh"),
+            ColorVariant::Success,
+        )),
+        Ok(issues) => Message::ShowPopup(Popup::PermissionsAudit(issues)),
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after auditing permissions");
+}
+
+/// Asynchronously fixes the given permission problems, creating a green thread that communicates
+/// via the msg_tx
+pub fn fix_permissions(issues: Vec<PermissionIssue>, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_fix_permissions(issues, msg_tx));
+}
+
+async fn handle_fix_permissions(issues: Vec<PermissionIssue>, msg_tx: mpsc::Sender<Message>) {
+    match permissions::fix_permissions(&issues) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when fixing permissions"),
+        Ok(_) => {
+            let text = format!("Fixed the permissions of {} files", issues.len());
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(text, ColorVariant::Success)))
+                .expect("failed to send success message after fixing permissions")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after fixing permissions")
+}
@@ -0,0 +1,78 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use color_eyre::eyre::Result;
+
+use crate::{
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{client_config, files},
+};
+
+/// Parameters of the rename (or move) of a key pair
+pub struct KeyRename {
+    /// Name of the private key being renamed
+    pub old_key_name: String,
+    pub new_private_key_path: PathBuf,
+}
+
+/// Asynchronously renames a key pair and makes the config point at its new files, creating a
+/// green thread that communicates via the msg_tx
+pub fn rename_key(rename: KeyRename, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_rename_key(rename, msg_tx));
+}
+
+/// Makes the IdentityFile and CertificateFile lines that point at the moved files point at their
+/// new paths
+///
+/// # Returns
+/// The number of updated lines
+fn rewrite_references(moved_files: &[(PathBuf, PathBuf)]) -> Result<usize> {
+    let mut rewritten = 0;
+    for (old_path, new_path) in moved_files {
+        rewritten += files::rewrite_client_config_references(
+            &[
+                client_config::IDENTITY_FILE,
+                client_config::CERTIFICATE_FILE,
+            ],
+            old_path,
+            new_path,
+        )?;
+    }
+
+    Ok(rewritten)
+}
+
+async fn handle_rename_key(rename: KeyRename, msg_tx: mpsc::Sender<Message>) {
+    let msg = match files::move_key_pair(&rename.old_key_name, &rename.new_private_key_path) {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(moved_files) => {
+            let summary = format!(
+                "'{}' moved to '{}' ({} files)",
+                rename.old_key_name,
+                rename.new_private_key_path.to_string_lossy(),
+                moved_files.len()
+            );
+
+            match rewrite_references(&moved_files) {
+                Err(err) => Message::PrintError(format!("{}\n{}", summary, err)),
+                Ok(rewritten) => Message::ShowPopup(Popup::WithCfg(
+                    format!(
+                        "{}\n{} IdentityFile and CertificateFile lines updated in ~/.ssh/config",
+                        summary, rewritten
+                    ),
+                    ColorVariant::Success,
+                )),
+            }
+        }
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after renaming key");
+    msg_tx
+        .send(Message::ReloadClientConfig)
+        .expect("failed to send config reload message after renaming key");
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after renaming key")
+}
@@ -0,0 +1,144 @@
+use std::sync::mpsc;
+
+use color_eyre::eyre::{eyre, Result};
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    events::messages::Message,
+    keys::generate,
+    model::sections_state::rotate_key_state::OldKeyAction,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{client_config, files},
+};
+
+use super::generate_key_pair::NewKeyPair;
+
+/// Parameters of a key rotation
+pub struct KeyRotation {
+    /// Name of the private key being replaced
+    pub old_key_name: String,
+    pub new_key: NewKeyPair,
+    pub old_key_action: OldKeyAction,
+}
+
+/// Asynchronously replaces a key pair with a new one, creating a blocking thread (key generation
+/// is CPU bound) that communicates via the msg_tx
+pub fn rotate_key(rotation: KeyRotation, msg_tx: mpsc::Sender<Message>) {
+    tokio::task::spawn_blocking(move || handle_rotate_key(rotation, msg_tx));
+}
+
+/// Generates the new key pair and makes the config point at it
+///
+/// # Returns
+/// A summary of the rotation
+fn replace_key(rotation: &KeyRotation) -> Result<String> {
+    let ssh_dir = files::get_user_ssh_dir()?;
+    let old_private_key_path = ssh_dir.join(&rotation.old_key_name);
+    let new_private_key_path = ssh_dir.join(&rotation.new_key.name);
+
+    if files::key_pair_exists(&rotation.new_key.name)? {
+        return Err(eyre!(
+            "a key named '{}' already exists",
+            rotation.new_key.name
+        ));
+    }
+
+    let key_pair = generate::generate_key_pair(
+        &mut OsRng,
+        rotation.new_key.key_type,
+        &rotation.new_key.comment,
+        rotation.new_key.passphrase.as_bytes(),
+    )?;
+    generate::write_key_pair(&key_pair, &new_private_key_path)?;
+
+    let rewritten = files::rewrite_client_config_references(
+        &[client_config::IDENTITY_FILE],
+        &old_private_key_path,
+        &new_private_key_path,
+    )?;
+
+    Ok(format!(
+        "'{}' replaced by '{}' ({})\n{} IdentityFile lines updated in ~/.ssh/config",
+        rotation.old_key_name,
+        rotation.new_key.name,
+        key_pair.fingerprint(),
+        rewritten
+    ))
+}
+
+/// Archives or deletes the old key pair
+fn dispose_old_key(old_key_name: &str, old_key_action: OldKeyAction) -> Result<String> {
+    match old_key_action {
+        OldKeyAction::KeepUntilConfirmed => Ok(format!("'{}' was kept", old_key_name)),
+        OldKeyAction::Archive => files::archive_key_pair(old_key_name).map(|archived_path| {
+            format!(
+                "'{}' archived at '{}'",
+                old_key_name,
+                archived_path.to_string_lossy()
+            )
+        }),
+        OldKeyAction::Delete => files::trash_key_pair(old_key_name)
+            .map(|_| format!("'{}' moved to the trash", old_key_name)),
+    }
+}
+
+fn handle_rotate_key(rotation: KeyRotation, msg_tx: mpsc::Sender<Message>) {
+    let msg = match replace_key(&rotation) {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(summary) if rotation.old_key_action == OldKeyAction::KeepUntilConfirmed => {
+            Message::ShowPopup(Popup::FinishKeyRotation(summary))
+        }
+        Ok(summary) => match dispose_old_key(&rotation.old_key_name, rotation.old_key_action) {
+            Err(err) => Message::PrintError(format!("{}\n{}", summary, err)),
+            Ok(disposal) => Message::ShowPopup(Popup::WithCfg(
+                format!("{}\n{}", summary, disposal),
+                ColorVariant::Success,
+            )),
+        },
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after rotating key");
+    msg_tx
+        .send(Message::ReloadClientConfig)
+        .expect("failed to send config reload message after rotating key");
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after rotating key")
+}
+
+/// Asynchronously archives or deletes the old key of a finished rotation, creating a green thread
+/// that communicates via the msg_tx
+pub fn finish_key_rotation(
+    old_key_name: String,
+    old_key_action: OldKeyAction,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::spawn(handle_finish_key_rotation(
+        old_key_name,
+        old_key_action,
+        msg_tx,
+    ));
+}
+
+async fn handle_finish_key_rotation(
+    old_key_name: String,
+    old_key_action: OldKeyAction,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match dispose_old_key(&old_key_name, old_key_action) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when finishing key rotation"),
+        Ok(disposal) => msg_tx
+            .send(Message::ShowPopup(Popup::WithCfg(
+                disposal,
+                ColorVariant::Success,
+            )))
+            .expect("failed to send success message after finishing key rotation"),
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after finishing key rotation")
+}
@@ -0,0 +1,50 @@
+use std::sync::mpsc;
+
+use ssh_key::rand_core::OsRng;
+
+use crate::{
+    events::messages::Message,
+    keys::certificate::{self, UserCertRequest},
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::secret::SecretString,
+};
+
+/// Asynchronously issues a user certificate, creating a blocking thread (decrypting the CA key is
+/// CPU bound) that communicates via the msg_tx
+pub fn sign_certificate(
+    request: UserCertRequest,
+    ca_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    tokio::task::spawn_blocking(move || handle_sign_certificate(request, ca_passphrase, msg_tx));
+}
+
+fn handle_sign_certificate(
+    request: UserCertRequest,
+    ca_passphrase: SecretString,
+    msg_tx: mpsc::Sender<Message>,
+) {
+    match certificate::sign_user_key(&mut OsRng, &request, ca_passphrase.as_bytes()) {
+        Err(err) => msg_tx
+            .send(Message::PrintError(err.to_string()))
+            .expect("failed to send error message when signing certificate"),
+        Ok(cert_path) => {
+            let text = format!(
+                "Certificate written to {}\nKey ID: \"{}\" | Serial: {} | Principals: {}",
+                cert_path.to_string_lossy(),
+                request.key_id,
+                request.serial,
+                request.principals.join(",")
+            );
+            msg_tx
+                .send(Message::ShowPopup(Popup::WithCfg(
+                    text,
+                    ColorVariant::Success,
+                )))
+                .expect("failed to send success message after signing certificate")
+        }
+    };
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys refresh message after signing certificate")
+}
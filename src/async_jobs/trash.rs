@@ -0,0 +1,53 @@
+use std::sync::mpsc;
+
+use crate::{
+    events::messages::Message,
+    ui::{color_variants::ColorVariant, components::popups::Popup},
+    utils::{files, trash::TrashedKeyPair},
+};
+
+/// Asynchronously moves a key pair back from the trash, creating a green thread that communicates
+/// via the msg_tx
+pub fn restore_key_pair(trashed_key_pair: TrashedKeyPair, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_restore_key_pair(trashed_key_pair, msg_tx));
+}
+
+/// Asynchronously deletes a key pair from the trash for good, creating a green thread that
+/// communicates via the msg_tx. Goes back to the trash once it's done
+pub fn purge_key_pair(trashed_key_pair: TrashedKeyPair, msg_tx: mpsc::Sender<Message>) {
+    tokio::spawn(handle_purge_key_pair(trashed_key_pair, msg_tx));
+}
+
+async fn handle_restore_key_pair(trashed_key_pair: TrashedKeyPair, msg_tx: mpsc::Sender<Message>) {
+    let msg = match files::restore_trashed_key_pair(&trashed_key_pair) {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(_) => Message::ShowPopup(Popup::WithCfg(
+            format!("key '{}' restored", trashed_key_pair.key_name),
+            ColorVariant::Success,
+        )),
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after restoring key pair");
+    msg_tx
+        .send(Message::RefreshTrash)
+        .expect("failed to send trash refresh message after restoring key pair");
+    msg_tx
+        .send(Message::RefreshPublicKeysList)
+        .expect("failed to send keys list refresh message after restoring key pair")
+}
+
+async fn handle_purge_key_pair(trashed_key_pair: TrashedKeyPair, msg_tx: mpsc::Sender<Message>) {
+    let msg = match files::purge_trashed_key_pair(&trashed_key_pair) {
+        Err(err) => Message::PrintError(err.to_string()),
+        Ok(_) => Message::ShowPopup(Popup::Trash),
+    };
+
+    msg_tx
+        .send(msg)
+        .expect("failed to send message after purging key pair");
+    msg_tx
+        .send(Message::RefreshTrash)
+        .expect("failed to send trash refresh message after purging key pair")
+}
@@ -1,22 +1,176 @@
+pub mod prompts;
+pub mod ssh_copy_id;
 pub mod ssh_keygen;
 
 use portable_pty::ChildKiller;
-use std::sync::mpsc;
+use std::{io, sync::mpsc};
 
 use color_eyre::eyre::{eyre, Result};
+use zeroize::Zeroizing;
 
 use crate::events::messages::Message;
 use crate::utils::constants;
 
 use std::collections::HashMap;
 
+use self::prompts::Prompt;
+
 pub type TaskMessageRx = mpsc::Receiver<Message>;
 pub type TaskMessageTx = mpsc::Sender<Message>;
 
-/// List of available command tasks
+/// List of available command tasks. Each one can run at the same time as the others
 #[derive(Clone, Copy, Eq, Hash, PartialEq, serde::Serialize, Debug)]
 pub enum CmdTask {
     SshKeygen,
+    SshCopyId,
+}
+
+/// What the app keeps about a running command, as the command itself is moved to the thread that
+/// reads its output
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RunningCmd {
+    pub task: CmdTask,
+    /// Text shown while the command runs and isn't waiting for an answer
+    pub waiting_text: &'static str,
+}
+
+impl RunningCmd {
+    pub fn new(cmd: &impl PtyCommand) -> Self {
+        Self {
+            task: cmd.get_task(),
+            waiting_text: cmd.get_waiting_text(),
+        }
+    }
+}
+
+/// An OpenSSH tool (ssh-keygen, ssh-keyscan, ssh-add, ssh...) run in a pseudo-terminal, so its
+/// prompts can be answered through the app. The command keeps its own state, as it's moved to the
+/// thread that reads its output
+pub trait PtyCommand: Send + 'static {
+    fn get_task(&self) -> CmdTask;
+
+    /// Name shown to the user, which isn't always the binary (e.g. ssh-copy-id runs ssh)
+    fn get_name(&self) -> &'static str;
+
+    /// Text shown while the command runs and isn't waiting for an answer
+    fn get_waiting_text(&self) -> &'static str;
+
+    /// Name of the binary to run
+    fn get_program(&self) -> &'static str;
+
+    fn get_args(&self) -> Result<Vec<String>>;
+
+    /// Tells what to do with a prompt printed by the command. Prompts it doesn't expect are left
+    /// unanswered
+    fn handle_prompt(&mut self, prompt: Prompt) -> Vec<Message>;
+
+    /// Tells what happened once the command exits, based on what it printed after the last
+    /// prompt
+    ///
+    /// # Returns
+    /// The messages to send before the command is marked as finished, or why it failed
+    fn handle_exit(&mut self, output: &str) -> Result<Vec<Message>, String>;
+}
+
+/// Starts the given command in a pseudo-terminal, creating a detached thread in charge of turning
+/// its prompts and exit into messages for the app
+pub fn spawn<C: PtyCommand>(cmd: C, task_msg_tx: TaskMessageTx) -> Result<CmdWriterEnd> {
+    let name = cmd.get_name();
+    let program = cmd.get_program();
+    let args = cmd.get_args()?;
+
+    let pty_system = portable_pty::native_pty_system();
+    let pty_pair = pty_system
+        .openpty(portable_pty::PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| eyre!("{}", e))?;
+
+    let mut cmd_builder = portable_pty::CommandBuilder::new(program);
+    cmd_builder.args(args);
+    // The prompts and the outcome of the commands are recognized by their English messages
+    cmd_builder.env("LC_ALL", "C");
+
+    let child_cmd = pty_pair
+        .slave
+        .spawn_command(cmd_builder)
+        .map_err(|e| eyre!("error spawning {}: {}", program, e))?;
+
+    let pty_reader = pty_pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| eyre!("error getting command reader: {}", e))?;
+
+    // Reading blocks until the command prints something, so every command gets its own thread
+    let reader_end = CmdReaderEnd {
+        reader: pty_reader,
+        msg_sender: task_msg_tx,
+    };
+    tokio::task::spawn_blocking(move || handle_output(reader_end, cmd));
+
+    let writer = pty_pair
+        .master
+        .take_writer()
+        .map_err(|e| eyre!("error getting command writer: {}", e))?;
+
+    let child_killer = child_cmd.clone_killer();
+
+    Ok(CmdWriterEnd {
+        name,
+        writer,
+        child_killer,
+    })
+}
+
+/// Reads the output of the command until it exits, handing it every complete prompt
+fn handle_output<C: PtyCommand>(mut reader_end: CmdReaderEnd, mut cmd: C) {
+    let task = cmd.get_task();
+    let mut buf = [0u8; 1024];
+    // Output since the last prompt, as a prompt can be split across reads
+    let mut pending_output = String::new();
+
+    loop {
+        match reader_end.reader.read(&mut buf) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            // Linux reports the exit of the command as an error rather than EOF
+            Ok(0) | Err(_) => {
+                let msgs = match cmd.handle_exit(&pending_output) {
+                    // The failure is only shown if the command wasn't killed by the user
+                    Err(error) => vec![Message::CmdFailed(task, error)],
+                    Ok(msgs) => msgs
+                        .into_iter()
+                        .chain(std::iter::once(Message::CmdFinished(task)))
+                        .collect(),
+                };
+                msgs.into_iter().for_each(|msg| {
+                    reader_end
+                        .msg_sender
+                        .send(msg)
+                        .expect("failed to terminate child command")
+                });
+                break;
+            }
+            Ok(n) => {
+                pending_output.push_str(&String::from_utf8_lossy(&buf[..n]));
+
+                if let Some(prompt) = prompts::detect_prompt(&pending_output) {
+                    pending_output.clear();
+                    let msgs = cmd.handle_prompt(prompt);
+                    // The popup opened for the prompt belongs to this command
+                    let prompted = (!msgs.is_empty()).then_some(Message::CmdPrompted(task));
+                    msgs.into_iter().chain(prompted).for_each(|msg| {
+                        reader_end
+                            .msg_sender
+                            .send(msg)
+                            .expect("failed to send command prompt message")
+                    });
+                }
+            }
+        };
+    }
 }
 
 pub type PtyReader = Box<dyn std::io::Read + Send>;
@@ -28,6 +182,7 @@ pub type PtyWriter = Box<dyn std::io::Write + Send>;
 /// This struct should be handled by the main thread, which forwards the input from the user to the
 /// child command
 pub struct CmdWriterEnd {
+    name: &'static str,
     writer: PtyWriter,
     child_killer: Box<dyn ChildKiller + Send + Sync>,
 }
@@ -36,22 +191,25 @@ impl CmdWriterEnd {
     pub fn kill_child(&mut self) -> Result<()> {
         self.child_killer
             .kill()
-            .map_err(|e| eyre!("failed to kill child command: {}", e))
+            .map_err(|e| eyre!("failed to stop {}: {}", self.name, e))
     }
 
     pub fn write(&mut self, content: &[u8]) -> Result<()> {
-        let content_with_newline: Vec<u8> = content
-            .iter()
-            .copied()
-            .chain(constants::LINE_TERMINATOR.to_owned())
-            .collect();
+        // The content may be a passphrase, so the copy is zeroed once it's written
+        let content_with_newline: Zeroizing<Vec<u8>> = Zeroizing::new(
+            content
+                .iter()
+                .copied()
+                .chain(constants::LINE_TERMINATOR.to_owned())
+                .collect(),
+        );
 
         self.writer
             .write_all(&content_with_newline)
-            .map_err(|e| eyre!("failed to write to command: {}", e))?;
+            .map_err(|e| eyre!("failed to write to {}: {}", self.name, e))?;
         self.writer
             .flush()
-            .map_err(|e| eyre!("failed to send data to command: {}", e))
+            .map_err(|e| eyre!("failed to send data to {}: {}", self.name, e))
     }
 }
 
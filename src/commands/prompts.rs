@@ -0,0 +1,139 @@
+//! Detection of the interactive prompts printed by the OpenSSH tools, shared by every command run
+//! in a pseudo-terminal
+
+/// A prompt waiting for an answer at the end of the output of a command
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Prompt {
+    /// Asks for a password or a passphrase. Holds the prompt line
+    Secret(String),
+    /// Asks if the key of an unknown host should be trusted (`yes/no`). Holds the whole prompt,
+    /// which includes the fingerprint of the key
+    HostKey(String),
+    /// Asks for a `y/n` confirmation, like overwriting a file. Holds the prompt line
+    Confirmation(String),
+}
+
+/// Looks for a prompt at the end of the output printed since the last answered one. The output is
+/// read in chunks, so a prompt is only detected once it's complete
+pub fn detect_prompt(pending_output: &str) -> Option<Prompt> {
+    const HOST_KEY_PROMPT: &str = "(yes/no";
+    const CONFIRMATION_PROMPT: &str = "(y/n)";
+
+    let trimmed = pending_output.trim_end();
+    if trimmed.contains(HOST_KEY_PROMPT) && trimmed.ends_with('?') {
+        return Some(Prompt::HostKey(trimmed.replace('\r', "")));
+    }
+
+    let last_line = trimmed.lines().last().unwrap_or_default().trim();
+    if last_line.contains(CONFIRMATION_PROMPT) && last_line.ends_with('?') {
+        return Some(Prompt::Confirmation(last_line.to_owned()));
+    }
+
+    let lowercase_line = last_line.to_lowercase();
+    if last_line.ends_with(':')
+        && (lowercase_line.contains("password") || lowercase_line.contains("passphrase"))
+    {
+        return Some(Prompt::Secret(last_line.to_owned()));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds the output chunk by chunk, like the reader does, and returns the prompts detected on
+    /// the way. The pending output is cleared after each prompt
+    fn detect_prompts(chunks: &[&str]) -> Vec<(usize, Prompt)> {
+        let mut pending_output = String::new();
+        let mut prompts = vec![];
+        for (idx, chunk) in chunks.iter().enumerate() {
+            pending_output.push_str(chunk);
+            if let Some(prompt) = detect_prompt(&pending_output) {
+                prompts.push((idx, prompt));
+                pending_output.clear();
+            }
+        }
+
+        prompts
+    }
+
+    #[test]
+    fn host_key_prompt_is_detected_once_complete() {
+        let prompts = detect_prompts(&[
+            "The authenticity of host 'example.com (10.0.0.1)' can't be established.\r\n",
+            "ED25519 key fingerprint is SHA256:abc.\r\nThis key is not known by any other names.\r\n",
+            "Are you sure you want to continue connecting (yes/no",
+            "/[fingerprint])? ",
+        ]);
+
+        assert_eq!(
+            prompts,
+            [(
+                3,
+                Prompt::HostKey(String::from(
+                    "The authenticity of host 'example.com (10.0.0.1)' can't be established.\n\
+                     ED25519 key fingerprint is SHA256:abc.\n\
+                     This key is not known by any other names.\n\
+                     Are you sure you want to continue connecting (yes/no/[fingerprint])?"
+                ))
+            )]
+        );
+    }
+
+    #[test]
+    fn confirmation_prompt_is_detected_once_complete() {
+        let prompts = detect_prompts(&[
+            "Generating public/private ed25519 key pair.\r\n",
+            "/home/user/.ssh/id_ed25519 already exists.\r\nOverwrite (y/",
+            "n)? ",
+        ]);
+
+        assert_eq!(
+            prompts,
+            [(2, Prompt::Confirmation(String::from("Overwrite (y/n)?")))]
+        );
+    }
+
+    #[test]
+    fn secret_prompts_are_detected_once_complete() {
+        let prompts = detect_prompts(&[
+            "user@example.com's pass",
+            "word: ",
+            "\r\nPermission denied, please try again.\r\n",
+            "user@example.com's password:",
+            " ",
+            "\r\nEnter passphrase (empty for no passphrase): ",
+        ]);
+
+        assert_eq!(
+            prompts,
+            [
+                (
+                    1,
+                    Prompt::Secret(String::from("user@example.com's password:"))
+                ),
+                (
+                    3,
+                    Prompt::Secret(String::from("user@example.com's password:"))
+                ),
+                (
+                    5,
+                    Prompt::Secret(String::from("Enter passphrase (empty for no passphrase):"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn regular_output_is_not_a_prompt() {
+        assert_eq!(detect_prompt(""), None);
+        assert_eq!(
+            detect_prompt("Your identification has been saved in key\r\n"),
+            None
+        );
+        assert_eq!(detect_prompt("Number of key(s) added: 1\r\n"), None);
+        assert_eq!(detect_prompt("the password: is wrong\r\n"), None);
+    }
+}
@@ -0,0 +1,369 @@
+use color_eyre::eyre::{eyre, Result};
+
+use super::{prompts::Prompt, CmdTask, PtyCommand};
+use crate::events::messages::Message;
+use crate::model::sections_state::deploy_key_state::DeployKeyState;
+use crate::ui::{
+    color_variants::ColorVariant,
+    components::popups::Popup::{PromptDeployHostKey, PromptRemoteSecret, WithCfg},
+};
+use crate::utils::{authorized_keys::AuthorizedKeysEntry, files};
+
+/// Printed by the remote script, so the outcome can be told apart from the rest of the output
+const KEY_ADDED_MARK: &str = "SSHY_KEY_ADDED";
+const KEY_EXISTS_MARK: &str = "SSHY_KEY_EXISTS";
+
+/// Quotes the given value so a POSIX shell takes it as a single word
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Script run on the remote host. It appends the key to ~/.ssh/authorized_keys (creating it with
+/// safe permissions), unless the key is already there, with or without options. Like ssh-copy-id,
+/// it's run by `sh`, whatever the login shell of the user is
+fn remote_command(entry: &AuthorizedKeysEntry) -> String {
+    let script = format!(
+        "umask 077 && mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys && \
+         if grep -qF {key} ~/.ssh/authorized_keys; then echo {exists}; else \
+         {{ [ -z \"$(tail -c 1 ~/.ssh/authorized_keys)\" ] || echo; }} >> ~/.ssh/authorized_keys && \
+         echo {line} >> ~/.ssh/authorized_keys && echo {added}; fi",
+        key = shell_quote(&entry.key),
+        line = shell_quote(&entry.to_string()),
+        exists = KEY_EXISTS_MARK,
+        added = KEY_ADDED_MARK,
+    );
+
+    format!("exec sh -c {}", shell_quote(&script))
+}
+
+pub struct SshCopyIdCmd {
+    public_key_name: String,
+    host: String,
+    entry: AuthorizedKeysEntry,
+}
+
+impl SshCopyIdCmd {
+    /// Prepares the deployment of the public key to the remote host, checking both first
+    pub fn new(deploy_key: &DeployKeyState) -> Result<Self> {
+        let public_key_name = deploy_key.get_public_key_name().to_owned();
+        let host = deploy_key.validate_host().map_err(|e| eyre!(e))?.to_owned();
+
+        let pub_key_content = files::get_pub_key_content(&public_key_name)?;
+        let entry = AuthorizedKeysEntry::from_public_key(vec![], &pub_key_content)
+            .ok_or_else(|| eyre!("'{}' is not a valid public key", public_key_name))?;
+
+        Ok(SshCopyIdCmd {
+            public_key_name,
+            host,
+            entry,
+        })
+    }
+}
+
+/// Installs the public key in the authorized_keys of the remote host through ssh, answering its
+/// prompts through the app
+impl PtyCommand for SshCopyIdCmd {
+    fn get_task(&self) -> CmdTask {
+        CmdTask::SshCopyId
+    }
+
+    fn get_name(&self) -> &'static str {
+        "ssh-copy-id"
+    }
+
+    fn get_waiting_text(&self) -> &'static str {
+        "Deploying key..."
+    }
+
+    fn get_program(&self) -> &'static str {
+        "ssh"
+    }
+
+    fn get_args(&self) -> Result<Vec<String>> {
+        // The key being deployed probably isn't authorized yet, so the password has to be asked
+        Ok(vec![
+            String::from("-o"),
+            String::from("BatchMode=no"),
+            String::from("--"),
+            self.host.clone(),
+            remote_command(&self.entry),
+        ])
+    }
+
+    fn handle_prompt(&mut self, prompt: Prompt) -> Vec<Message> {
+        match prompt {
+            Prompt::HostKey(prompt) => vec![Message::ShowPopup(PromptDeployHostKey(prompt))],
+            Prompt::Secret(prompt) => vec![Message::ShowPopup(PromptRemoteSecret(prompt))],
+            Prompt::Confirmation(_) => vec![],
+        }
+    }
+
+    fn handle_exit(&mut self, output: &str) -> Result<Vec<Message>, String> {
+        if output.contains(KEY_ADDED_MARK) {
+            return Ok(vec![Message::ShowPopup(WithCfg(
+                format!(
+                    "'{}' was added to the authorized_keys of '{}'",
+                    self.public_key_name, self.host
+                ),
+                ColorVariant::Success,
+            ))]);
+        }
+        if output.contains(KEY_EXISTS_MARK) {
+            return Ok(vec![Message::ShowPopup(WithCfg(
+                format!(
+                    "'{}' is already authorized on '{}'",
+                    self.public_key_name, self.host
+                ),
+                ColorVariant::Success,
+            ))]);
+        }
+
+        let output = output.replace('\r', "");
+        if output.trim().is_empty() {
+            Err(format!("failed to deploy the key to '{}'", self.host))
+        } else {
+            Err(output.trim().to_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, process::Command};
+
+    use super::*;
+    use crate::utils::authorized_keys::parse_options;
+
+    const PUBLIC_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIN0ag5geYprTvDJEgpSZSx6XgH65iM8/otxvK8/Yzvz1";
+
+    fn test_cmd() -> SshCopyIdCmd {
+        SshCopyIdCmd {
+            public_key_name: String::from("id_ed25519.pub"),
+            host: String::from("user@example.com"),
+            entry: AuthorizedKeysEntry::from_public_key(vec![], PUBLIC_KEY).unwrap(),
+        }
+    }
+
+    /// Runs the remote script locally, with the given directory as the home directory
+    fn run_remote_command(entry: &AuthorizedKeysEntry, home: &Path) -> String {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(remote_command(entry))
+            .env("HOME", home)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("''"), "''\\'''\\'''");
+    }
+
+    #[test]
+    fn remote_command_appends_the_entry_once() {
+        let home = tempfile::tempdir().unwrap();
+        let entry = AuthorizedKeysEntry::from_public_key(
+            parse_options("command=\"echo 'it works'; exit\",no-pty").unwrap(),
+            &format!("{} o'brien's $HOME `key` \"laptop\"", PUBLIC_KEY),
+        )
+        .unwrap();
+
+        let output = run_remote_command(&entry, home.path());
+        assert_eq!(output.trim(), KEY_ADDED_MARK);
+        let authorized_keys = home.path().join(".ssh/authorized_keys");
+        assert_eq!(
+            fs::read_to_string(&authorized_keys).unwrap(),
+            format!("{}\n", entry)
+        );
+
+        let output = run_remote_command(&entry, home.path());
+        assert_eq!(output.trim(), KEY_EXISTS_MARK);
+        assert_eq!(
+            fs::read_to_string(&authorized_keys).unwrap(),
+            format!("{}\n", entry)
+        );
+    }
+
+    #[test]
+    fn remote_command_keeps_existing_entries() {
+        let home = tempfile::tempdir().unwrap();
+        let authorized_keys = home.path().join(".ssh/authorized_keys");
+        fs::create_dir(home.path().join(".ssh")).unwrap();
+        // No newline at the end of the last entry
+        fs::write(&authorized_keys, "ssh-rsa AAAAB3NzaC1yc2E other").unwrap();
+
+        let entry = AuthorizedKeysEntry::from_public_key(vec![], PUBLIC_KEY).unwrap();
+        let output = run_remote_command(&entry, home.path());
+        assert_eq!(output.trim(), KEY_ADDED_MARK);
+        assert_eq!(
+            fs::read_to_string(&authorized_keys).unwrap(),
+            format!("ssh-rsa AAAAB3NzaC1yc2E other\n{}\n", PUBLIC_KEY)
+        );
+    }
+
+    #[test]
+    fn handle_exit_reports_added_key() {
+        let output = format!(
+            "Warning: Permanently added 'example.com'\r\n{}\r\n",
+            KEY_ADDED_MARK
+        );
+
+        let messages = test_cmd().handle_exit(&output).unwrap();
+        assert!(matches!(
+            messages.as_slice(),
+            [Message::ShowPopup(WithCfg(text, ColorVariant::Success))]
+                if text == "'id_ed25519.pub' was added to the authorized_keys of 'user@example.com'"
+        ));
+    }
+
+    #[test]
+    fn handle_exit_reports_existing_key() {
+        let messages = test_cmd()
+            .handle_exit(&format!("{}\r\n", KEY_EXISTS_MARK))
+            .unwrap();
+        assert!(matches!(
+            messages.as_slice(),
+            [Message::ShowPopup(WithCfg(text, ColorVariant::Success))]
+                if text == "'id_ed25519.pub' is already authorized on 'user@example.com'"
+        ));
+    }
+
+    #[test]
+    fn handle_exit_reports_failure_output() {
+        assert_eq!(
+            test_cmd()
+                .handle_exit("user@example.com: Permission denied (publickey,password).\r\n")
+                .unwrap_err(),
+            "user@example.com: Permission denied (publickey,password)."
+        );
+        assert_eq!(
+            test_cmd().handle_exit("\r\n").unwrap_err(),
+            "failed to deploy the key to 'user@example.com'"
+        );
+    }
+
+    /// Path of the sshd binary used by the integration test
+    const SSHD: &str = "/usr/sbin/sshd";
+
+    /// Generates a key pair without passphrase at the given path with ssh-keygen
+    fn ssh_keygen(path: &Path) {
+        let status = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    #[ignore = "needs sshd, started on a free local port"]
+    fn key_is_deployed_through_a_local_sshd() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        fs::create_dir(&home).unwrap();
+        ssh_keygen(&dir.path().join("host_key"));
+        ssh_keygen(&dir.path().join("client_key"));
+        // The client authenticates with its own key, so no password is asked
+        fs::copy(
+            dir.path().join("client_key.pub"),
+            dir.path().join("server_authorized_keys"),
+        )
+        .unwrap();
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config_path = dir.path().join("sshd_config");
+        // The remote script writes to the home directory given by SetEnv, not the real one
+        fs::write(
+            &config_path,
+            format!(
+                "ListenAddress 127.0.0.1:{port}\n\
+                 HostKey {dir}/host_key\n\
+                 AuthorizedKeysFile {dir}/server_authorized_keys\n\
+                 StrictModes no\n\
+                 PidFile none\n\
+                 SetEnv HOME={home}\n",
+                port = port,
+                dir = dir.path().to_string_lossy(),
+                home = home.to_string_lossy(),
+            ),
+        )
+        .unwrap();
+
+        let mut sshd = Command::new(SSHD)
+            .args(["-D", "-e", "-f"])
+            .arg(&config_path)
+            .spawn()
+            .expect("failed to start sshd");
+        for _ in 0..50 {
+            if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let user = String::from_utf8(Command::new("id").arg("-un").output().unwrap().stdout)
+            .unwrap()
+            .trim()
+            .to_owned();
+        let mut cmd = SshCopyIdCmd {
+            public_key_name: String::from("id_ed25519.pub"),
+            host: format!("{}@127.0.0.1", user),
+            entry: AuthorizedKeysEntry::from_public_key(vec![], &format!("{} it's me", PUBLIC_KEY))
+                .unwrap(),
+        };
+        let deploy = |cmd: &mut SshCopyIdCmd| {
+            // The first value of an option wins, so these take precedence over the ones of the
+            // command
+            let output = Command::new("ssh")
+                .args(["-p", &port.to_string(), "-i"])
+                .arg(dir.path().join("client_key"))
+                .args([
+                    "-o",
+                    "BatchMode=yes",
+                    "-o",
+                    "IdentitiesOnly=yes",
+                    "-o",
+                    "StrictHostKeyChecking=no",
+                    "-o",
+                    "UserKnownHostsFile=/dev/null",
+                    "-F",
+                    "/dev/null",
+                ])
+                .args(cmd.get_args().unwrap())
+                .output()
+                .unwrap();
+            let output = String::from_utf8_lossy(&output.stdout).into_owned()
+                + &String::from_utf8_lossy(&output.stderr);
+            cmd.handle_exit(&output)
+        };
+
+        let first = deploy(&mut cmd);
+        let second = deploy(&mut cmd);
+        let authorized_keys = fs::read_to_string(home.join(".ssh/authorized_keys"));
+        let _ = sshd.kill();
+        let _ = sshd.wait();
+
+        assert!(matches!(
+            first.unwrap().as_slice(),
+            [Message::ShowPopup(WithCfg(text, _))] if text.contains("was added")
+        ));
+        assert!(matches!(
+            second.unwrap().as_slice(),
+            [Message::ShowPopup(WithCfg(text, _))] if text.contains("is already authorized")
+        ));
+        assert_eq!(
+            authorized_keys.unwrap(),
+            format!("{} it's me\n", PUBLIC_KEY)
+        );
+    }
+}
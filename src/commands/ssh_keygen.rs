@@ -1,22 +1,85 @@
-use std::io;
-
-use color_eyre::eyre::{eyre, Result, WrapErr};
+use color_eyre::eyre::{eyre, Result};
 
+use super::{prompts::Prompt, CmdTask, PtyCommand};
+use crate::agent::AgentClient;
 use crate::events::messages::Message;
 use crate::model::sections_state::public_keys_list_state::NewPublicKeyState;
-use crate::ui::{color_variants::ColorVariant, components::popups::Popup::WithCfg};
+use crate::ui::{
+    color_variants::ColorVariant,
+    components::popups::Popup::{AddNewKeyToAgent, WithCfg},
+};
 use crate::utils;
 
-#[derive(Clone, Copy, Default)]
+/// Key types (and sizes) that can be generated
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
 pub enum PublicKeyType {
     #[default]
     ED25519,
+    RSA2048,
+    RSA3072,
+    RSA4096,
+    ECDSA256,
+    ECDSA384,
+    ECDSA521,
+}
+
+/// Order in which the key types are shown in the selector
+const PUBLIC_KEY_TYPES: [PublicKeyType; 7] = [
+    PublicKeyType::ED25519,
+    PublicKeyType::RSA2048,
+    PublicKeyType::RSA3072,
+    PublicKeyType::RSA4096,
+    PublicKeyType::ECDSA256,
+    PublicKeyType::ECDSA384,
+    PublicKeyType::ECDSA521,
+];
+
+impl PublicKeyType {
+    /// Value of the -b argument, if the type has a selectable size
+    pub fn get_bits(&self) -> Option<&'static str> {
+        match self {
+            PublicKeyType::ED25519 => None,
+            PublicKeyType::RSA2048 => Some("2048"),
+            PublicKeyType::RSA3072 => Some("3072"),
+            PublicKeyType::RSA4096 => Some("4096"),
+            PublicKeyType::ECDSA256 => Some("256"),
+            PublicKeyType::ECDSA384 => Some("384"),
+            PublicKeyType::ECDSA521 => Some("521"),
+        }
+    }
+
+    /// Returns the next type in the selector, wrapping around
+    pub fn next(&self) -> Self {
+        let idx = PUBLIC_KEY_TYPES.iter().position(|t| t == self).unwrap_or(0);
+        PUBLIC_KEY_TYPES[(idx + 1) % PUBLIC_KEY_TYPES.len()]
+    }
+
+    /// Returns the previous type in the selector, wrapping around
+    pub fn prev(&self) -> Self {
+        let idx = PUBLIC_KEY_TYPES.iter().position(|t| t == self).unwrap_or(0);
+        PUBLIC_KEY_TYPES[(idx + PUBLIC_KEY_TYPES.len() - 1) % PUBLIC_KEY_TYPES.len()]
+    }
 }
 
+/// Value of the -t argument
 impl From<PublicKeyType> for &str {
     fn from(value: PublicKeyType) -> Self {
         match value {
             PublicKeyType::ED25519 => "ed25519",
+            PublicKeyType::RSA2048 | PublicKeyType::RSA3072 | PublicKeyType::RSA4096 => "rsa",
+            PublicKeyType::ECDSA256 | PublicKeyType::ECDSA384 | PublicKeyType::ECDSA521 => {
+                "ecdsa"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PublicKeyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keytype: &str = (*self).into();
+        match self.get_bits() {
+            None => write!(f, "{}", keytype),
+            Some(bits) => write!(f, "{} {}", keytype, bits),
         }
     }
 }
@@ -28,151 +91,88 @@ pub struct SshKeygenCmd {
 }
 
 impl SshKeygenCmd {
-    /// Starts the ssh-keygen command, creating a detached green-thread in charge
-    /// of handling the messaging between the the command and the app.
-    pub fn start(
-        new_key: &NewPublicKeyState,
-        task_msg_tx: super::TaskMessageTx,
-    ) -> Result<super::CmdWriterEnd> {
-        let cmd = SshKeygenCmd {
+    pub fn new(new_key: &NewPublicKeyState) -> Self {
+        SshKeygenCmd {
             keytype: new_key.get_type(),
             filename: new_key.get_name().into(),
             comment: new_key.get_comment().into(),
-        };
-
-        let home_dir = utils::files::get_user_ssh_dir()?.join(&cmd.filename);
-
-        let home_str = home_dir
-            .as_os_str()
-            .to_str()
-            .ok_or_else(|| eyre!("invalid home directory"))?;
-
-        let args: [&str; 6] = ["-t", cmd.keytype.into(), "-f", home_str, "-C", &cmd.comment];
-
-        let pty_system = portable_pty::native_pty_system();
-        let pty_pair = pty_system
-            .openpty(portable_pty::PtySize {
-                rows: 24,
-                cols: 80,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| eyre!("{}", e))?;
-
-        let mut cmd_builder = portable_pty::CommandBuilder::new("ssh-keygen");
-        cmd_builder.args(args);
-
-        let child_cmd = pty_pair
-            .slave
-            .spawn_command(cmd_builder)
-            .map_err(|e| eyre!("error spawning ssh-keygen: {}", e))?;
-
-        let pty_reader = pty_pair
-            .master
-            .try_clone_reader()
-            .map_err(|e| eyre!("error getting command reader: {}", e))?;
-
-        tokio::spawn(handle_ssh_keygen(super::CmdReaderEnd {
-            reader: pty_reader,
-            msg_sender: task_msg_tx,
-        }));
-
-        let writer = pty_pair
-            .master
-            .take_writer()
-            .map_err(|e| eyre!("error getting command writer: {}", e))?;
-
-        let child_killer = child_cmd.clone_killer();
-
-        let writer_end = super::CmdWriterEnd {
-            writer,
-            child_killer,
-        };
-
-        Ok(writer_end)
+        }
     }
 }
 
-fn handle_ssh_keygen_output(content: &[u8]) -> Result<Vec<Message>> {
-    let content_string =
-        String::from_utf8(content.to_vec()).wrap_err("failed to read output content")?;
-
-    const SET_PASSPHRASE_PROMPT: &str = "Enter passphrase (empty for no passphrase)";
-    const SET_REENTER_PASS_PROMPT: &str = "Enter same passphrase again";
-    const SUCCESSFUL_KEYGEN: &str = "Your identification has been saved in";
-    const EXISTING_KEY: &str = " already exists";
-    const NO_SUCH_DIR: &str = " No such file or directory";
-
-    let match_pass_prompt = content_string.contains(SET_PASSPHRASE_PROMPT);
-    if match_pass_prompt {
-        return Ok(vec![Message::PromptNewKeyPassphrase]);
+impl PtyCommand for SshKeygenCmd {
+    fn get_task(&self) -> CmdTask {
+        CmdTask::SshKeygen
     }
 
-    let match_reenter_pass_prompt = content_string.contains(SET_REENTER_PASS_PROMPT);
-    if match_reenter_pass_prompt {
-        return Ok(vec![Message::PromptReenterNewKeyPassPhrase]);
+    fn get_name(&self) -> &'static str {
+        "ssh-keygen"
     }
 
-    let match_successful_keygen = content_string.contains(SUCCESSFUL_KEYGEN);
-    if match_successful_keygen {
-        let succ_popup_msg = Message::ShowPopup(WithCfg(content_string, ColorVariant::Success));
-        let reload_keys_msg = Message::RefreshPublicKeysList;
-        return Ok(vec![succ_popup_msg, reload_keys_msg]);
+    fn get_waiting_text(&self) -> &'static str {
+        "Generating key..."
     }
 
-    let match_key_exists = content_string.contains(EXISTING_KEY);
-    if match_key_exists {
-        return Ok(vec![Message::PromptKeyOverwrite]);
+    fn get_program(&self) -> &'static str {
+        "ssh-keygen"
     }
 
-    let match_no_such_dir = content_string.contains(NO_SUCH_DIR);
-    if match_no_such_dir {
-        return Ok(vec![Message::ShowPopup(WithCfg(
-            content_string,
-            ColorVariant::Danger,
-        ))]);
+    fn get_args(&self) -> Result<Vec<String>> {
+        let key_path = utils::files::get_user_ssh_dir()?.join(&self.filename);
+
+        let key_path_str = key_path
+            .as_os_str()
+            .to_str()
+            .ok_or_else(|| eyre!("invalid home directory"))?;
+
+        let mut args: Vec<&str> = vec!["-t", self.keytype.into()];
+        if let Some(bits) = self.keytype.get_bits() {
+            args.extend(["-b", bits]);
+        }
+        args.extend(["-f", key_path_str, "-C", &self.comment]);
+
+        Ok(args.into_iter().map(String::from).collect())
     }
 
-    // return Ok(Message::ShowPopup(WithCfg(
-    //     content_string,
-    //     ColorVariant::Warning,
-    // )));
-    Ok(vec![Message::Draw])
-}
+    fn handle_prompt(&mut self, prompt: Prompt) -> Vec<Message> {
+        const SET_REENTER_PASS_PROMPT: &str = "Enter same passphrase again";
 
-async fn handle_ssh_keygen(mut reader_end: super::CmdReaderEnd) {
-    let mut buf = [0u8; 1024];
-    loop {
-        match reader_end.reader.read(&mut buf) {
-            Ok(0) => {
-                // EOF reached
-                reader_end
-                    .msg_sender
-                    .send(Message::CmdFinished)
-                    .expect("failed to terminate child command");
-                break;
-            }
-            Ok(_n) => {
-                let msgs = handle_ssh_keygen_output(&buf)
-                    .map_err(|e| eyre!("error handling command output: {}", e));
-                match msgs {
-                    Err(e) => reader_end
-                        .msg_sender
-                        .send(Message::PrintError(e.to_string()))
-                        .unwrap(),
-                    Ok(msgs) => msgs
-                        .into_iter()
-                        .for_each(|msg| reader_end.msg_sender.send(msg).unwrap()),
-                }
-            }
-            Err(e) => {
-                if e.kind() == io::ErrorKind::WouldBlock {
-                    // No data available; optionally sleep or perform other work
-                    continue;
-                } else {
-                    panic!("error reading from PTY: {}", e);
-                }
+        match prompt {
+            // Both passphrases were already asked for when the first prompt was answered
+            Prompt::Secret(line) if line.contains(SET_REENTER_PASS_PROMPT) => {
+                vec![Message::SendNewKeyPassphraseCheck]
             }
+            Prompt::Secret(_) => vec![Message::PromptNewKeyPassphrase],
+            // Asked when there's already a key pair with the same name
+            Prompt::Confirmation(_) => vec![Message::PromptKeyOverwrite],
+            Prompt::HostKey(_) => vec![],
+        }
+    }
+
+    fn handle_exit(&mut self, output: &str) -> Result<Vec<Message>, String> {
+        const SUCCESSFUL_KEYGEN: &str = "Your identification has been saved in";
+
+        let output = output.replace('\r', "");
+        let Some(summary_idx) = output.find(SUCCESSFUL_KEYGEN) else {
+            return match output.trim() {
+                "" => Err(String::from("ssh-keygen failed to generate the key pair")),
+                error => Err(error.to_owned()),
+            };
         };
+
+        let summary = output[summary_idx..].trim_end().to_owned();
+        let reload_keys_msg = Message::RefreshPublicKeysList;
+        if AgentClient::is_available() {
+            return Ok(vec![
+                Message::CleanAddToAgentInput,
+                Message::ShowPopup(AddNewKeyToAgent(summary)),
+                reload_keys_msg,
+            ]);
+        }
+
+        Ok(vec![
+            Message::ShowPopup(WithCfg(summary, ColorVariant::Success)),
+            reload_keys_msg,
+        ])
     }
 }
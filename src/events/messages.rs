@@ -1,4 +1,10 @@
-use crate::{commands, ui::components::popups::Popup, model::vim_emulator::VimMode};
+use crate::{
+    commands,
+    model::{
+        sections_state::public_keys_list_state::PassphrasePurpose, vim_emulator::VimMode,
+    },
+    ui::components::popups::Popup,
+};
 use tui_textarea::{CursorMove, Scrolling, Input};
 
 /// Messages are events that the model has to react to and update it's
@@ -17,17 +23,26 @@ pub enum Message {
     SelPrevListItem,
     SelNextPopupItem,
     SelPrevPopupItem,
+    /// If focused on a selector, it indicates that the next option should be selected
+    SelNextPopupOption,
+    /// If focused on a selector, it indicates that the previous option should be selected
+    SelPrevPopupOption,
     /// If focused on a text input, it indicates that a char should be written
     WriteChar(char),
     /// If focused on a text input, removes the last character
     PopChar,
     /// If focused on a text input, removes the last word
     PopWord,
-    /// Indicates that a new command has been spawned and it must be set as the
-    /// currently running one
-    CmdSpawned(commands::CmdTask),
-    /// Indicates that the currently running command has finished
-    CmdFinished,
+    /// Indicates that a new command has been spawned and that the waiting popup belongs to it
+    CmdSpawned(commands::RunningCmd),
+    /// Indicates that the given command has just opened the current popup to answer one of its
+    /// prompts, so the popup belongs to it
+    CmdPrompted(commands::CmdTask),
+    /// Indicates that the given command has finished
+    CmdFinished(commands::CmdTask),
+    /// Indicates that the given command has finished without doing its job. The given String is
+    /// only printed if the command wasn't killed by the user
+    CmdFailed(commands::CmdTask, String),
     /// Indicates that the given String should be printed in the success popup
     PrintSuccess(String),
     /// Indicates that the given String should be printed in the error popup
@@ -38,10 +53,65 @@ pub enum Message {
     PromptNewKeyPassphrase,
     PromptReenterNewKeyPassPhrase,
     PromptKeyOverwrite,
+    /// Answers the second passphrase prompt of ssh-keygen with the passphrase the user already
+    /// re-entered. Handled by the event handler, which owns the input of the commands
+    SendNewKeyPassphraseCheck,
     CleanNewKeyPassphraseInput,
+    /// Moves the passphrase of the new key pair out of the prompts, keeping it until the user
+    /// decides if the key is added to the agent
+    HoldNewKeyForAgent,
+    CleanPendingAgentKey,
+    /// Opens the comment editor of the selected key pair
+    StartKeyCommentEdit,
+    /// Opens the rotation wizard of the selected key pair
+    StartKeyRotation,
+    /// Opens the form used to rename or move the selected key pair
+    StartKeyRename,
+    /// Opens the form used to issue a certificate for the selected public key
+    StartCertificateSigning,
+    /// Opens the form used to install the selected public key on a remote host
+    StartKeyDeploy,
+    /// Opens the form used to sign a file with the selected key pair
+    StartFileSigning,
+    /// Opens the form used to verify the signature of a file
+    StartSignatureVerification,
+    /// Audits the key pairs again and shows the ones that are weak or legacy
+    AuditKeys,
+    /// Reads the allowed_signers file again
+    RefreshAllowedSigners,
+    /// Reads the key pairs in the trash again
+    RefreshTrash,
+    /// Opens the form used to allow a new signer
+    StartAllowedSignerAdd,
+    /// Opens the form used to edit the selected allowed signer
+    StartAllowedSignerEdit,
+    CleanAllowedSignerInput,
+    /// Opens the form used to make git sign with the selected key pair
+    StartGitSigning,
+    /// Switches the content of the selected public key between its text and its QR codes
+    ToggleQrCode,
+    /// Forgets the answer given to the password prompt of the key deployment
+    CleanRemoteSecret,
+    /// Sets what the passphrase prompts are going to be used for
+    SetPassphrasePurpose(PassphrasePurpose),
     RefreshPublicKeysList,
     RefreshKnownHostsList,
+    /// Lists the identities loaded in the agent again
+    RefreshAgentIdentities,
     PromptDeleteKeyPairConfirmation,
+    /// Loads the keys of the selected known host and prompts for the one to revoke
+    StartHostKeyRevocation,
+    CleanNewCertAuthorityInput,
+    CleanImportKeyInput,
+    CleanAddToAgentInput,
+    RefreshAuthorizedKeys,
+    /// Opens the form used to authorize a new key
+    StartAuthorizedKeyAdd,
+    /// Opens the form used to edit the options of the selected authorized key
+    StartAuthorizedKeyEdit,
+    CleanAuthorizedKeyInput,
+    /// Reloads the content of the client config from its file, discarding unsaved changes
+    ReloadClientConfig,
     /// Enters interactive mode with the focused textarea, initiating a Vim state machine and
     /// updating the textarea's section state
     TextAreaInteract,
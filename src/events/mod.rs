@@ -12,12 +12,26 @@ use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 
 use crate::{
     async_jobs,
-    commands::{self, ssh_keygen::SshKeygenCmd, CmdTask},
-    model::Model,
+    commands::{
+        self, ssh_copy_id::SshCopyIdCmd, ssh_keygen::SshKeygenCmd, CmdTask, PtyCommand,
+        RunningCmd,
+    },
+    keys::{
+        convert,
+        info::{PrivateKeyStatus, PublicKeyInfo},
+        passphrase, KeygenBackend,
+    },
+    model::{
+        sections_state::{
+            public_keys_list_state::PassphrasePurpose, rotate_key_state::OldKeyAction,
+        },
+        Model,
+    },
     ui::{
         components::{popups::Popup, sections::Section},
         Focus,
     },
+    utils::{dates, files},
 };
 
 use self::messages::Message;
@@ -71,13 +85,44 @@ impl EventHandler {
             Err(err) if err != TryRecvError::Empty => {
                 queue.push_back(Message::PrintError(err.to_string()))
             }
-            Ok(msg) => queue.push_back(msg),
+            Ok(msg) => queue.extend(self.handle_task_message(msg, model)),
             _ => {}
         }
 
         Ok(queue.into_iter())
     }
 
+    /// Handles the messages of commands that need their writer ends. The rest, including the end
+    /// of the commands, is forwarded to the model
+    fn handle_task_message(&mut self, msg: Message, model: &Model) -> Vec<Message> {
+        match msg {
+            Message::SendNewKeyPassphraseCheck => {
+                let mut msgs: Vec<Message> = vec![];
+
+                if let Some(msg) = self.write_to_cmd(
+                    CmdTask::SshKeygen,
+                    model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_new_key_state()
+                        .get_passphrase_check()
+                        .as_bytes(),
+                ) {
+                    msgs.push(msg);
+                };
+                msgs.push(Message::HoldNewKeyForAgent);
+
+                msgs
+            }
+            // The command exited on its own, so there's nothing left to write to or kill
+            Message::CmdFinished(cmd_task) | Message::CmdFailed(cmd_task, _) => {
+                self.cmd_writer_ends.remove(&cmd_task);
+                vec![msg]
+            }
+            msg => vec![msg],
+        }
+    }
+
     /// Check if there's an unhandled event waiting to be handled
     ///
     /// # NOTE
@@ -134,41 +179,251 @@ impl EventHandler {
             KeyCode::Left | KeyCode::Char('h') => vec![Message::MoveToPrevSection],
             KeyCode::Up | KeyCode::Char('k') => vec![Message::SelPrevListItem],
             KeyCode::Down | KeyCode::Char('j') => vec![Message::SelNextListItem],
-            KeyCode::Char('n') => {
-                if let Section::PublicKeysList = current_section {
-                    vec![Message::ShowPopup(Popup::AddPubKey)]
-                } else {
-                    vec![]
-                }
-            }
+            KeyCode::Char('n') => match current_section {
+                Section::PublicKeysList => vec![Message::ShowPopup(Popup::AddPubKey)],
+                Section::AuthorizedKeys => vec![Message::StartAuthorizedKeyAdd],
+                _ => vec![],
+            },
             KeyCode::Char('R') => match current_section {
                 Section::PublicKeysList => vec![Message::RefreshPublicKeysList],
                 Section::KnownHostsList => vec![Message::RefreshKnownHostsList],
+                Section::Agent => vec![Message::RefreshAgentIdentities],
+                Section::AuthorizedKeys => vec![Message::RefreshAuthorizedKeys],
                 Section::ClientConfig => vec![],
             },
-            KeyCode::Char('d') => {
+            KeyCode::Char('d') => match current_section {
+                Section::PublicKeysList => vec![Message::PromptDeleteKeyPairConfirmation],
+                Section::Agent
+                    if model
+                        .get_sections_state()
+                        .get_agent_state()
+                        .get_selected_identity()
+                        .is_some() =>
+                {
+                    vec![Message::ShowPopup(
+                        Popup::PromptRemoveAgentIdentityConfirmation,
+                    )]
+                }
+                Section::AuthorizedKeys
+                    if model
+                        .get_sections_state()
+                        .get_authorized_keys_state()
+                        .get_selected_entry()
+                        .is_some() =>
+                {
+                    vec![Message::ShowPopup(
+                        Popup::PromptRemoveAuthorizedKeyConfirmation,
+                    )]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('D') => match current_section {
+                Section::Agent
+                    if !model
+                        .get_sections_state()
+                        .get_agent_state()
+                        .get_items()
+                        .is_empty() =>
+                {
+                    vec![Message::ShowPopup(
+                        Popup::PromptRemoveAgentIdentitiesConfirmation,
+                    )]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('A') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    self.start_add_to_agent(model)
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('s') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    vec![Message::StartCertificateSigning]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('u') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none() && !item.public_key_missing) =>
+                {
+                    vec![Message::StartKeyDeploy]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('f') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| {
+                            item.certificate.is_none()
+                                && item.private_key_status != PrivateKeyStatus::Missing
+                        }) =>
+                {
+                    vec![Message::StartFileSigning]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('v') => match current_section {
+                Section::PublicKeysList => vec![Message::StartSignatureVerification],
+                _ => vec![],
+            },
+            KeyCode::Char('W') => match current_section {
+                Section::PublicKeysList => vec![Message::AuditKeys],
+                _ => vec![],
+            },
+            KeyCode::Char('S') => match current_section {
+                Section::PublicKeysList => vec![
+                    Message::RefreshAllowedSigners,
+                    Message::ShowPopup(Popup::AllowedSigners),
+                ],
+                _ => vec![],
+            },
+            KeyCode::Char('T') => match current_section {
+                Section::PublicKeysList => {
+                    vec![Message::RefreshTrash, Message::ShowPopup(Popup::Trash)]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('G') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none() && !item.public_key_missing) =>
+                {
+                    vec![Message::StartGitSigning]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('c') => self.copy_pub_key_to_clipboard(model),
+            KeyCode::Char('a') => match current_section {
+                Section::PublicKeysList => {
+                    async_jobs::permissions::audit_permissions(self.task_msg_tx.clone());
+                    vec![]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('p') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    self.start_passphrase_change(model)
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('i') => {
                 if let Section::PublicKeysList = current_section {
-                    vec![Message::PromptDeleteKeyPairConfirmation]
+                    vec![Message::ShowPopup(Popup::ImportKey)]
                 } else {
                     vec![]
                 }
             }
-            KeyCode::Char('c') => {
-                self.copy_pub_key_to_clipboard(model);
-                vec![]
-            }
-            KeyCode::Enter => match current_section {
-                // TODO
-                // Disgustingly long. Must change in the future
+            KeyCode::Char('e') => match current_section {
                 Section::PublicKeysList
                     if model
                         .get_sections_state()
                         .get_public_keys_list_state()
-                        .get_selected_item_idx()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    vec![Message::StartKeyCommentEdit]
+                }
+                Section::AuthorizedKeys => vec![Message::StartAuthorizedKeyEdit],
+                _ => vec![],
+            },
+            KeyCode::Char('r') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    vec![Message::StartKeyRotation]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('g') => match current_section {
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .is_selected_item_orphaned() =>
+                {
+                    self.confirm_derive_public_key(model)
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('m') => match current_section {
+                Section::KnownHostsList => vec![Message::ShowPopup(Popup::KnownHostsMarkers)],
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    vec![Message::StartKeyRename]
+                }
+                _ => vec![],
+            },
+            KeyCode::Char('x') => match current_section {
+                Section::KnownHostsList
+                    if model
+                        .get_sections_state()
+                        .get_known_hosts_list_state()
+                        .get_selected_host()
                         .is_some() =>
                 {
-                    vec![Message::ShowPopup(Popup::ShowPubKeyContent)]
+                    vec![Message::StartHostKeyRevocation]
+                }
+                Section::PublicKeysList
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_selected_item()
+                        .is_some_and(|item| item.certificate.is_none()) =>
+                {
+                    vec![Message::ShowPopup(Popup::ExportKey)]
                 }
+                _ => vec![],
+            },
+            KeyCode::Enter => match current_section {
+                Section::PublicKeysList => match model
+                    .get_sections_state()
+                    .get_public_keys_list_state()
+                    .get_selected_item()
+                {
+                    None => vec![],
+                    Some(item) if item.public_key_missing => {
+                        vec![Message::PrintError(missing_public_key_error(item))]
+                    }
+                    Some(_) => vec![Message::ShowPopup(Popup::ShowPubKeyContent)],
+                },
                 Section::ClientConfig => vec![Message::TextAreaInteract],
                 _ => vec![],
             },
@@ -176,13 +431,24 @@ impl EventHandler {
         }
     }
 
-    fn copy_pub_key_to_clipboard(&self, model: &Model) {
-        let key_name = model
-            .get_sections_state()
-            .get_public_keys_list_state()
-            .get_selected_key_content()
-            .expect("Must select a key first");
-        async_jobs::copy_to_clipboard::copy_to_clipboard(key_name, self.task_msg_tx.clone());
+    fn copy_pub_key_to_clipboard(&self, model: &Model) -> Vec<Message> {
+        let public_keys_list_state = model.get_sections_state().get_public_keys_list_state();
+        match public_keys_list_state.get_selected_item() {
+            None => vec![],
+            Some(item) if item.public_key_missing => {
+                vec![Message::PrintError(missing_public_key_error(item))]
+            }
+            Some(_) => match public_keys_list_state.get_selected_key_content() {
+                Err(err) => vec![Message::PrintError(err.to_string())],
+                Ok(key_content) => {
+                    async_jobs::copy_to_clipboard::copy_to_clipboard(
+                        key_content,
+                        self.task_msg_tx.clone(),
+                    );
+                    vec![]
+                }
+            },
+        }
     }
 
     fn delete_key_pair(&self, model: &Model) {
@@ -194,51 +460,740 @@ impl EventHandler {
         async_jobs::delete_key_pair::delete_key_pair(key_name, self.task_msg_tx.clone());
     }
 
-    /// Starts the given command task
+    fn restore_trashed_key_pair(&self, model: &Model) {
+        let trashed_key_pair = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_trash_state()
+            .get_selected_item()
+            .expect("Must select a key pair to restore")
+            .clone();
+        async_jobs::trash::restore_key_pair(trashed_key_pair, self.task_msg_tx.clone());
+    }
+
+    fn add_cert_authority(&self, model: &Model) {
+        let new_ca_state = model
+            .get_sections_state()
+            .get_known_hosts_list_state()
+            .get_new_cert_authority_state();
+        async_jobs::known_hosts_markers::add_cert_authority(
+            new_ca_state.get_pub_key_path().to_owned(),
+            new_ca_state.get_host_pattern().to_owned(),
+            self.task_msg_tx.clone(),
+        );
+    }
+
+    fn revoke_host_key(&self, model: &Model) {
+        let known_hosts_state = model.get_sections_state().get_known_hosts_list_state();
+        // Nothing to revoke if the host has no keys left
+        if let Some(host_key) = known_hosts_state.get_selected_host_key() {
+            async_jobs::known_hosts_markers::revoke_host_key(
+                host_key.clone(),
+                self.task_msg_tx.clone(),
+            );
+        }
+    }
+
+    /// Confirms the new key pair form, either starting ssh-keygen or the native key generation
+    /// flow, based on the selected backend
+    fn confirm_new_key(&mut self, model: &Model) -> Message {
+        let new_key_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_new_key_state();
+
+        if let Err(validation_err) = new_key_state.validate_name() {
+            return Message::PrintError(validation_err);
+        }
+
+        match new_key_state.get_backend() {
+            KeygenBackend::SshKeygen => self.start_command(SshKeygenCmd::new(new_key_state)),
+            KeygenBackend::Native => match files::key_pair_exists(new_key_state.get_name()) {
+                Err(err) => Message::PrintError(err.to_string()),
+                Ok(true) => Message::PromptKeyOverwrite,
+                Ok(false) => Message::PromptNewKeyPassphrase,
+            },
+        }
+    }
+
+    /// Last step of the native key generation flow. Checks that both passphrases match and
+    /// starts generating the key pair
+    fn generate_key_pair(&self, model: &Model) -> Vec<Message> {
+        let new_key_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_new_key_state();
+
+        if !new_key_state.passphrases_match() {
+            return vec![
+                Message::CleanNewKeyPassphraseInput,
+                Message::PrintError(String::from("Passphrases do not match. Try again.")),
+            ];
+        }
+
+        async_jobs::generate_key_pair::generate_key_pair(
+            async_jobs::generate_key_pair::NewKeyPair {
+                name: new_key_state.get_name().to_owned(),
+                key_type: new_key_state.get_type(),
+                comment: new_key_state.get_comment().to_owned(),
+                passphrase: new_key_state.get_passphrase().clone(),
+            },
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::HoldNewKeyForAgent,
+            Message::ShowPopup(Popup::Loading(String::from("Generating key..."))),
+        ]
+    }
+
+    /// Cancels the flow of a new passphrase that doesn't meet the policy, stopping ssh-keygen if it
+    /// asked for it
+    fn reject_new_passphrase(&mut self, model: &Model) -> Vec<Message> {
+        let violation = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_new_key_state()
+            .check_passphrase_policy()
+            .err()
+            .unwrap_or_default();
+
+        let mut msgs = vec![];
+        if model.is_popup_of_command(CmdTask::SshKeygen) {
+            msgs.push(self.kill_command(CmdTask::SshKeygen));
+        }
+        msgs.push(Message::CleanNewKeyPassphraseInput);
+        msgs.push(Message::PrintError(violation));
+
+        msgs
+    }
+
+    /// Starts the passphrase change flow of the selected key pair. The current passphrase is only
+    /// asked for if the private key is encrypted
+    fn start_passphrase_change(&self, model: &Model) -> Vec<Message> {
+        let private_key_path = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_selected_private_key_path()
+            .expect("Must select a key to change its passphrase");
+
+        match passphrase::is_encrypted(&private_key_path) {
+            Err(err) => vec![Message::PrintError(err.to_string())],
+            Ok(encrypted) => {
+                let first_prompt = if encrypted {
+                    Popup::PromptOldPassphrase
+                } else {
+                    Popup::PromptPassphrase
+                };
+                vec![
+                    Message::SetPassphrasePurpose(PassphrasePurpose::ChangePassphrase),
+                    Message::ShowPopup(first_prompt),
+                ]
+            }
+        }
+    }
+
+    /// Confirms the new comment of the selected key pair. If the private key is encrypted, its
+    /// passphrase is asked for before changing the comment
+    fn confirm_comment_edit(&self, model: &Model) -> Vec<Message> {
+        let private_key_path = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_selected_private_key_path()
+            .expect("Must select a key to change its comment");
+
+        if !private_key_path.exists() {
+            return self.change_comment(model);
+        }
+
+        match passphrase::is_encrypted(&private_key_path) {
+            Err(err) => vec![Message::PrintError(err.to_string())],
+            Ok(true) => vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::EditComment),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ],
+            Ok(false) => self.change_comment(model),
+        }
+    }
+
+    fn change_comment(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let private_key_path = keys_state
+            .get_selected_private_key_path()
+            .expect("Must select a key to change its comment");
+
+        async_jobs::change_comment::change_comment(
+            private_key_path,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            keys_state.get_edited_comment().to_owned(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Changing comment..."))),
+        ]
+    }
+
+    fn verify_old_passphrase(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let private_key_path = keys_state
+            .get_selected_private_key_path()
+            .expect("Must select a key to change its passphrase");
+
+        async_jobs::change_passphrase::verify_passphrase(
+            private_key_path,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![Message::ShowPopup(Popup::Loading(String::from(
+            "Checking passphrase...",
+        )))]
+    }
+
+    /// Last step of the passphrase change flow. Checks that both new passphrases match and
+    /// replaces the passphrase of the selected key
+    fn change_passphrase(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let new_key_state = keys_state.get_new_key_state();
+
+        if !new_key_state.passphrases_match() {
+            return vec![
+                Message::CleanNewKeyPassphraseInput,
+                Message::PrintError(String::from("Passphrases do not match. Try again.")),
+            ];
+        }
+
+        let private_key_path = keys_state
+            .get_selected_private_key_path()
+            .expect("Must select a key to change its passphrase");
+
+        async_jobs::change_passphrase::change_passphrase(
+            private_key_path,
+            new_key_state.get_old_passphrase().clone(),
+            new_key_state.get_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Changing passphrase..."))),
+        ]
+    }
+
+    fn export_key(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let public_key_path = keys_state
+            .get_selected_key_path()
+            .expect("Must select a key to export it");
+
+        async_jobs::convert_key::export_key(
+            public_key_path,
+            keys_state.get_export_format(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![]
+    }
+
+    /// Confirms the import key form. The passphrase is only asked for if the key to import is
+    /// encrypted
+    fn confirm_import_key(&self, model: &Model) -> Vec<Message> {
+        let import_key_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_import_key_state();
+
+        if let Err(validation_err) = import_key_state.validate_name() {
+            return vec![Message::PrintError(validation_err)];
+        }
+
+        let source_path = files::expand_home(import_key_state.get_source_path());
+        match std::fs::read_to_string(&source_path) {
+            Err(err) => vec![Message::PrintError(format!(
+                "failed to read key '{}': {}",
+                source_path.to_string_lossy(),
+                err
+            ))],
+            Ok(content) if convert::import_needs_passphrase(&content) => vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::ImportKey),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ],
+            Ok(_) => self.import_key(model),
+        }
+    }
+
+    fn import_key(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let import_key_state = keys_state.get_import_key_state();
+
+        let ssh_dir = match files::get_user_ssh_dir() {
+            Err(err) => return vec![Message::PrintError(err.to_string())],
+            Ok(ssh_dir) => ssh_dir,
+        };
+
+        async_jobs::convert_key::import_key(
+            files::expand_home(import_key_state.get_source_path()),
+            ssh_dir.join(import_key_state.get_name()),
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Importing key..."))),
+        ]
+    }
+
+    /// Starts regenerating the public key of the selected private key. The passphrase is only
+    /// asked for if the private key is encrypted
+    fn confirm_derive_public_key(&self, model: &Model) -> Vec<Message> {
+        let private_key_status = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_selected_item()
+            .expect("Must select a key to regenerate its public key")
+            .private_key_status;
+
+        if private_key_status == PrivateKeyStatus::Encrypted {
+            vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::DerivePublicKey),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ]
+        } else {
+            self.derive_public_key(model)
+        }
+    }
+
+    fn derive_public_key(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let private_key_path = keys_state
+            .get_selected_private_key_path()
+            .expect("Must select a key to regenerate its public key");
+
+        async_jobs::derive_public_key::derive_public_key(
+            private_key_path,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Regenerating public key..."))),
+        ]
+    }
+
+    /// Confirms the key rotation form and asks for the passphrase of the new key
+    fn confirm_key_rotation(&self, model: &Model) -> Vec<Message> {
+        let rotate_key_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_rotate_key_state();
+
+        if let Err(validation_err) = rotate_key_state.validate_name() {
+            return vec![Message::PrintError(validation_err)];
+        }
+
+        match files::key_pair_exists(rotate_key_state.get_name()) {
+            Err(err) => vec![Message::PrintError(err.to_string())],
+            Ok(true) => vec![Message::PrintError(format!(
+                "a key named '{}' already exists",
+                rotate_key_state.get_name()
+            ))],
+            Ok(false) => vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::RotateKey),
+                Message::PromptNewKeyPassphrase,
+            ],
+        }
+    }
+
+    fn rename_key(&self, model: &Model) -> Vec<Message> {
+        let rename_key_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_rename_key_state();
+
+        let new_private_key_path = match rename_key_state.get_new_private_key_path() {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(new_private_key_path) => new_private_key_path,
+        };
+
+        async_jobs::rename_key::rename_key(
+            async_jobs::rename_key::KeyRename {
+                old_key_name: rename_key_state.get_old_key_name().to_owned(),
+                new_private_key_path,
+            },
+            self.task_msg_tx.clone(),
+        );
+
+        vec![Message::ShowPopup(Popup::Loading(String::from(
+            "Renaming key...",
+        )))]
+    }
+
+    /// Last step of the key rotation form. Checks that both passphrases match and starts
+    /// replacing the key
+    fn rotate_key(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let new_key_state = keys_state.get_new_key_state();
+        let rotate_key_state = keys_state.get_rotate_key_state();
+
+        if !new_key_state.passphrases_match() {
+            return vec![
+                Message::CleanNewKeyPassphraseInput,
+                Message::PrintError(String::from("Passphrases do not match. Try again.")),
+            ];
+        }
+
+        async_jobs::rotate_key::rotate_key(
+            async_jobs::rotate_key::KeyRotation {
+                old_key_name: rotate_key_state.get_old_key_name().to_owned(),
+                new_key: async_jobs::generate_key_pair::NewKeyPair {
+                    name: rotate_key_state.get_name().to_owned(),
+                    key_type: rotate_key_state.get_type(),
+                    comment: rotate_key_state.get_comment().to_owned(),
+                    passphrase: new_key_state.get_passphrase().clone(),
+                },
+                old_key_action: rotate_key_state.get_old_key_action(),
+            },
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Rotating key..."))),
+        ]
+    }
+
+    fn finish_key_rotation(&self, model: &Model, old_key_action: OldKeyAction) {
+        let old_key_name = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_rotate_key_state()
+            .get_old_key_name()
+            .to_owned();
+
+        async_jobs::rotate_key::finish_key_rotation(
+            old_key_name,
+            old_key_action,
+            self.task_msg_tx.clone(),
+        );
+    }
+
+    /// Opens the form used to add the selected key pair to the agent
+    fn start_add_to_agent(&self, model: &Model) -> Vec<Message> {
+        let selected_key = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_selected_item()
+            .expect("Must select a key to add it to the agent");
+
+        if selected_key.private_key_status == PrivateKeyStatus::Missing {
+            return vec![Message::PrintError(format!(
+                "the private key of '{}' is missing",
+                selected_key.file_name
+            ))];
+        }
+
+        vec![
+            Message::CleanAddToAgentInput,
+            Message::ShowPopup(Popup::AddToAgent),
+        ]
+    }
+
+    /// Confirms the add to agent form. The passphrase is only asked for if the private key is
+    /// encrypted
+    fn confirm_add_to_agent(&self, model: &Model) -> Vec<Message> {
+        let sections_state = model.get_sections_state();
+
+        if let Err(validation_err) = sections_state
+            .get_agent_state()
+            .get_add_to_agent_state()
+            .get_constraints()
+        {
+            return vec![Message::PrintError(validation_err)];
+        }
+
+        let private_key_status = sections_state
+            .get_public_keys_list_state()
+            .get_selected_item()
+            .expect("Must select a key to add it to the agent")
+            .private_key_status;
+
+        if private_key_status == PrivateKeyStatus::Encrypted {
+            vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::AddToAgent),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ]
+        } else {
+            self.add_to_agent(model)
+        }
+    }
+
+    fn add_to_agent(&self, model: &Model) -> Vec<Message> {
+        let sections_state = model.get_sections_state();
+        let keys_state = sections_state.get_public_keys_list_state();
+        let private_key_path = keys_state
+            .get_selected_private_key_path()
+            .expect("Must select a key to add it to the agent");
+
+        let constraints = match sections_state
+            .get_agent_state()
+            .get_add_to_agent_state()
+            .get_constraints()
+        {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(constraints) => constraints,
+        };
+
+        async_jobs::agent::add_to_agent(
+            private_key_path,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            constraints,
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::CleanAddToAgentInput,
+            Message::ShowPopup(Popup::Loading(String::from("Adding key to the agent..."))),
+        ]
+    }
+
+    /// Adds the key pair that was just generated to the agent, using the passphrase kept since
+    /// its generation
+    fn add_new_key_to_agent(&self, model: &Model) -> Vec<Message> {
+        let agent_state = model.get_sections_state().get_agent_state();
+
+        let constraints = match agent_state.get_add_to_agent_state().get_constraints() {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(constraints) => constraints,
+        };
+        let Some(pending_key) = agent_state.get_pending_key() else {
+            return vec![Message::HidePopup];
+        };
+
+        async_jobs::agent::add_to_agent(
+            pending_key.private_key_path.clone(),
+            pending_key.passphrase.clone(),
+            constraints,
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanPendingAgentKey,
+            Message::CleanAddToAgentInput,
+            Message::ShowPopup(Popup::Loading(String::from("Adding key to the agent..."))),
+        ]
+    }
+
+    fn remove_agent_identity(&self, model: &Model) {
+        if let Some(identity) = model
+            .get_sections_state()
+            .get_agent_state()
+            .get_selected_identity()
+        {
+            async_jobs::agent::remove_identity(identity.clone(), self.task_msg_tx.clone());
+        }
+    }
+
+    /// Confirms the certificate form. The passphrase is only asked for if the CA key is encrypted
+    fn confirm_sign_certificate(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+        let sign_certificate_state = keys_state.get_sign_certificate_state();
+
+        if let Err(validation_err) = sign_certificate_state.get_request(dates::now()) {
+            return vec![Message::PrintError(validation_err)];
+        }
+
+        let ca_key_encrypted = keys_state.get_items().iter().any(|item| {
+            Some(item.file_name.trim_end_matches(".pub"))
+                == sign_certificate_state.get_ca_key_name()
+                && item.private_key_status == PrivateKeyStatus::Encrypted
+        });
+
+        if ca_key_encrypted {
+            vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::SignCertificate),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ]
+        } else {
+            self.sign_certificate(model)
+        }
+    }
+
+    fn sign_certificate(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+
+        let request = match keys_state
+            .get_sign_certificate_state()
+            .get_request(dates::now())
+        {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(request) => request,
+        };
+
+        async_jobs::sign_certificate::sign_certificate(
+            request,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Signing certificate..."))),
+        ]
+    }
+
+    fn confirm_sign_file(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+
+        if let Err(validation_err) = keys_state.get_sign_file_state().get_request() {
+            return vec![Message::PrintError(validation_err)];
+        }
+
+        match keys_state.get_selected_item() {
+            Some(item) if item.private_key_status == PrivateKeyStatus::Encrypted => vec![
+                Message::SetPassphrasePurpose(PassphrasePurpose::SignFile),
+                Message::ShowPopup(Popup::PromptOldPassphrase),
+            ],
+            _ => self.sign_file(model),
+        }
+    }
+
+    fn sign_file(&self, model: &Model) -> Vec<Message> {
+        let keys_state = model.get_sections_state().get_public_keys_list_state();
+
+        let request = match keys_state.get_sign_file_state().get_request() {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(request) => request,
+        };
+
+        async_jobs::file_signature::sign_file(
+            request,
+            keys_state.get_new_key_state().get_old_passphrase().clone(),
+            self.task_msg_tx.clone(),
+        );
+
+        vec![
+            Message::CleanNewKeyPassphraseInput,
+            Message::ShowPopup(Popup::Loading(String::from("Signing file..."))),
+        ]
+    }
+
+    fn verify_signature(&self, model: &Model) -> Vec<Message> {
+        let request = match model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_verify_signature_state()
+            .get_request()
+        {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(request) => request,
+        };
+
+        async_jobs::file_signature::verify_file(request, self.task_msg_tx.clone());
+
+        vec![Message::ShowPopup(Popup::Loading(String::from(
+            "Verifying signature...",
+        )))]
+    }
+
+    /// Confirms the authorized key form, either authorizing a new key or replacing the options of
+    /// the edited one
+    fn confirm_authorized_key(&self, model: &Model) -> Vec<Message> {
+        let form_state = model
+            .get_sections_state()
+            .get_authorized_keys_state()
+            .get_form_state();
+
+        let entry = match form_state.get_entry() {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(entry) => entry,
+        };
+
+        match form_state.get_editing() {
+            Some(old_entry) => async_jobs::authorized_keys::update_entry(
+                old_entry.clone(),
+                Some(entry),
+                self.task_msg_tx.clone(),
+            ),
+            None => async_jobs::authorized_keys::add_entry(entry, self.task_msg_tx.clone()),
+        }
+
+        vec![]
+    }
+
+    /// Confirms the allowed signer form, either allowing a new signer or replacing the edited one
+    fn confirm_allowed_signer(&self, model: &Model) -> Vec<Message> {
+        let form_state = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_allowed_signers_state()
+            .get_form_state();
+
+        let entry = match form_state.get_entry() {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(entry) => entry,
+        };
+
+        match form_state.get_editing() {
+            Some(old_entry) => async_jobs::allowed_signers::update_entry(
+                old_entry.clone(),
+                Some(entry),
+                self.task_msg_tx.clone(),
+            ),
+            None => async_jobs::allowed_signers::add_entry(entry, self.task_msg_tx.clone()),
+        }
+
+        vec![]
+    }
+
+    fn setup_git_signing(&self, model: &Model) -> Vec<Message> {
+        let request = match model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_git_signing_state()
+            .get_request()
+        {
+            Err(validation_err) => return vec![Message::PrintError(validation_err)],
+            Ok(request) => request,
+        };
+
+        async_jobs::allowed_signers::setup_git_signing(request, self.task_msg_tx.clone());
+
+        vec![]
+    }
+
+    /// Starts the given command, which can run at the same time as the other ones
     ///
     /// # Returns
     /// Returns either:
     ///     - Message::CmdSpawned(cmd_task)
     ///     - Message::PrintError(error_str)
-    fn start_command(&mut self, cmd_task: commands::CmdTask, model: &Model) -> Message {
-        let msg_tx_cp = self.task_msg_tx.clone();
-
-        let name_validation = model
-            .get_sections_state()
-            .get_public_keys_list_state()
-            .get_new_key_state()
-            .validate_name();
-        if let Err(validation_err) = name_validation {
-            return Message::PrintError(validation_err);
+    fn start_command(&mut self, cmd: impl PtyCommand) -> Message {
+        let running_cmd = RunningCmd::new(&cmd);
+        if self.cmd_writer_ends.contains_key(&running_cmd.task) {
+            return Message::PrintError(format!("{} is already running", cmd.get_name()));
         }
 
-        match cmd_task {
-            CmdTask::SshKeygen => {
-                let cmd_startup = SshKeygenCmd::start(
-                    model
-                        .get_sections_state()
-                        .get_public_keys_list_state()
-                        .get_new_key_state(),
-                    msg_tx_cp,
-                );
-                match cmd_startup {
-                    Err(err) => Message::PrintError(err.to_string()),
-                    Ok(cmd_writer_end) => {
-                        self.cmd_writer_ends.insert(cmd_task, cmd_writer_end);
-                        Message::CmdSpawned(cmd_task)
-                    }
-                }
+        match commands::spawn(cmd, self.task_msg_tx.clone()) {
+            Err(err) => Message::PrintError(err.to_string()),
+            Ok(cmd_writer_end) => {
+                self.cmd_writer_ends.insert(running_cmd.task, cmd_writer_end);
+                Message::CmdSpawned(running_cmd)
             }
         }
     }
 
     fn kill_command(&mut self, cmd_task: commands::CmdTask) -> Message {
-        match self.cmd_writer_ends.get_mut(&cmd_task) {
-            Some(writer_end) => match writer_end.kill_child() {
+        match self.cmd_writer_ends.remove(&cmd_task) {
+            Some(mut writer_end) => match writer_end.kill_child() {
                 Err(err) => Message::FatalError(err.to_string()),
-                Ok(_) => Message::CmdFinished,
+                Ok(_) => Message::CmdFinished(cmd_task),
             },
-            None => Message::FatalError("ssh-keygen is not currently running".to_string()),
+            None => Message::FatalError(format!("{:?} is not currently running", cmd_task)),
         }
     }
 
@@ -268,7 +1223,24 @@ impl EventHandler {
                         vec![]
                     }
                 }
-                Popup::AddPubKey | Popup::PromptPassphrase
+                Popup::AddPubKey
+                | Popup::PromptPassphrase
+                | Popup::PromptOldPassphrase
+                | Popup::AddCertAuthority
+                | Popup::EditKeyComment
+                | Popup::ImportKey
+                | Popup::RotateKey
+                | Popup::RenameKey
+                | Popup::AddToAgent
+                | Popup::AddNewKeyToAgent(_)
+                | Popup::SignCertificate
+                | Popup::AuthorizedKey
+                | Popup::DeployKey
+                | Popup::PromptRemoteSecret(_)
+                | Popup::SignFile
+                | Popup::VerifySignature
+                | Popup::AllowedSigner
+                | Popup::GitSigning
                     if key.modifiers.contains(KeyModifiers::CONTROL) =>
                 {
                     if ch == 'w' {
@@ -277,55 +1249,252 @@ impl EventHandler {
                         vec![]
                     }
                 }
-                Popup::AddPubKey | Popup::PromptPassphrase | Popup::PromptReenterPassphrase => {
+                Popup::AddPubKey
+                | Popup::PromptPassphrase
+                | Popup::PromptReenterPassphrase
+                | Popup::PromptOldPassphrase
+                | Popup::AddCertAuthority
+                | Popup::EditKeyComment
+                | Popup::ImportKey
+                | Popup::RotateKey
+                | Popup::RenameKey
+                | Popup::AddToAgent
+                | Popup::AddNewKeyToAgent(_)
+                | Popup::SignCertificate
+                | Popup::AuthorizedKey
+                | Popup::DeployKey
+                | Popup::PromptRemoteSecret(_)
+                | Popup::SignFile
+                | Popup::VerifySignature
+                | Popup::AllowedSigner
+                | Popup::GitSigning => {
                     vec![Message::WriteChar(ch)]
                 }
-                Popup::WaitingCmd => match model.get_current_command() {
-                    None => vec![Message::HidePopup],
-                    Some(cmd_task) => vec![self.kill_command(cmd_task)],
+                Popup::FinishKeyRotation(_) => match ch {
+                    'a' => {
+                        self.finish_key_rotation(model, OldKeyAction::Archive);
+                        vec![]
+                    }
+                    'd' => {
+                        self.finish_key_rotation(model, OldKeyAction::Delete);
+                        vec![]
+                    }
+                    _ => vec![],
                 },
+                Popup::ShowPubKeyContent => match ch {
+                    'r' => vec![Message::ToggleQrCode],
+                    _ => vec![],
+                },
+                Popup::KnownHostsMarkers => match ch {
+                    'a' => vec![Message::ShowPopup(Popup::AddCertAuthority)],
+                    'j' => vec![Message::SelNextPopupItem],
+                    'k' => vec![Message::SelPrevPopupItem],
+                    _ => vec![],
+                },
+                Popup::RevokeHostKey => match ch {
+                    'j' => vec![Message::SelNextPopupItem],
+                    'k' => vec![Message::SelPrevPopupItem],
+                    _ => vec![],
+                },
+                Popup::AllowedSigners => {
+                    let has_selected_entry = model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_allowed_signers_state()
+                        .get_selected_entry()
+                        .is_some();
+
+                    match ch {
+                        'a' => vec![Message::StartAllowedSignerAdd],
+                        'e' => vec![Message::StartAllowedSignerEdit],
+                        'd' if has_selected_entry => vec![Message::ShowPopup(
+                            Popup::PromptRemoveAllowedSignerConfirmation,
+                        )],
+                        'j' => vec![Message::SelNextPopupItem],
+                        'k' => vec![Message::SelPrevPopupItem],
+                        _ => vec![],
+                    }
+                }
+                Popup::Trash => {
+                    let has_selected_item = model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_trash_state()
+                        .get_selected_item()
+                        .is_some();
+
+                    match ch {
+                        'r' if has_selected_item => {
+                            self.restore_trashed_key_pair(model);
+                            vec![]
+                        }
+                        'd' if has_selected_item => vec![Message::ShowPopup(
+                            Popup::PromptPurgeTrashedKeyConfirmation,
+                        )],
+                        'j' => vec![Message::SelNextPopupItem],
+                        'k' => vec![Message::SelPrevPopupItem],
+                        _ => vec![],
+                    }
+                }
+                // The command keeps running in the background, Esc is the way to stop it
+                Popup::WaitingCmd => vec![Message::HidePopup],
                 _ => vec![],
             },
             KeyCode::Backspace => vec![Message::PopChar],
-            KeyCode::Esc => match model.get_current_command() {
+            // Cancelling the popup of a command stops that command, any other popup is only hidden
+            KeyCode::Esc => match model.get_popup_command() {
                 None => vec![Message::HidePopup],
-                Some(cmd_task) => vec![self.kill_command(cmd_task)],
+                Some(running_cmd) => vec![self.kill_command(running_cmd.task)],
             },
             KeyCode::Tab => vec![Message::SelNextPopupItem],
             KeyCode::BackTab => vec![Message::SelPrevPopupItem],
+            KeyCode::Right => vec![Message::SelNextPopupOption],
+            KeyCode::Left => vec![Message::SelPrevPopupOption],
             KeyCode::Enter => match current_popup {
-                Popup::AddPubKey => vec![self.start_command(CmdTask::SshKeygen, model)],
-                Popup::PromptPassphrase => {
+                Popup::AddPubKey => vec![
+                    Message::SetPassphrasePurpose(PassphrasePurpose::NewKey),
+                    self.confirm_new_key(model),
+                ],
+                Popup::PromptOldPassphrase => match model
+                    .get_sections_state()
+                    .get_public_keys_list_state()
+                    .get_new_key_state()
+                    .get_passphrase_purpose()
+                {
+                    PassphrasePurpose::EditComment => self.change_comment(model),
+                    PassphrasePurpose::ImportKey => self.import_key(model),
+                    PassphrasePurpose::DerivePublicKey => self.derive_public_key(model),
+                    PassphrasePurpose::AddToAgent => self.add_to_agent(model),
+                    PassphrasePurpose::SignCertificate => self.sign_certificate(model),
+                    PassphrasePurpose::SignFile => self.sign_file(model),
+                    _ => self.verify_old_passphrase(model),
+                },
+                Popup::EditKeyComment => self.confirm_comment_edit(model),
+                Popup::ExportKey => self.export_key(model),
+                Popup::ImportKey => self.confirm_import_key(model),
+                Popup::RotateKey => self.confirm_key_rotation(model),
+                Popup::RenameKey => self.rename_key(model),
+                Popup::AddToAgent => self.confirm_add_to_agent(model),
+                Popup::AddNewKeyToAgent(_) => self.add_new_key_to_agent(model),
+                Popup::SignCertificate => self.confirm_sign_certificate(model),
+                Popup::AuthorizedKey => self.confirm_authorized_key(model),
+                Popup::DeployKey => match SshCopyIdCmd::new(
+                    model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_deploy_key_state(),
+                ) {
+                    Err(err) => vec![Message::PrintError(err.to_string())],
+                    Ok(cmd) => vec![self.start_command(cmd)],
+                },
+                Popup::SignFile => self.confirm_sign_file(model),
+                Popup::VerifySignature => self.verify_signature(model),
+                Popup::AllowedSigner => self.confirm_allowed_signer(model),
+                Popup::GitSigning => self.setup_git_signing(model),
+                Popup::PromptRemoteSecret(_) => {
+                    let mut msgs: Vec<Message> = vec![];
+
                     if let Some(msg) = self.write_to_cmd(
-                        CmdTask::SshKeygen,
-                        &model
+                        CmdTask::SshCopyId,
+                        model
                             .get_sections_state()
                             .get_public_keys_list_state()
-                            .get_new_key_state()
-                            .get_passphrase_bytes(),
+                            .get_deploy_key_state()
+                            .get_remote_secret()
+                            .as_bytes(),
                     ) {
-                        vec![msg]
-                    } else {
-                        vec![]
-                    }
+                        msgs.push(msg);
+                    };
+                    msgs.push(Message::CleanRemoteSecret);
+                    msgs.push(Message::ShowPopup(Popup::WaitingCmd));
+
+                    msgs
                 }
-                Popup::PromptReenterPassphrase => {
+                Popup::PromptDeployHostKey(_) => {
                     let mut msgs: Vec<Message> = vec![];
 
-                    if let Some(msg) = self.write_to_cmd(
-                        CmdTask::SshKeygen,
-                        &model
-                            .get_sections_state()
-                            .get_public_keys_list_state()
-                            .get_new_key_state()
-                            .get_passphrase_check_bytes(),
-                    ) {
+                    if let Some(msg) = self.write_to_cmd(CmdTask::SshCopyId, "yes".as_bytes()) {
                         msgs.push(msg);
                     };
-                    msgs.push(Message::CleanNewKeyPassphraseInput);
+                    msgs.push(Message::ShowPopup(Popup::WaitingCmd));
 
                     msgs
                 }
+                // Passphrases that don't meet the policy aren't submitted, the popup shows why
+                Popup::PromptPassphrase
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_new_key_state()
+                        .check_passphrase_policy()
+                        .is_err() =>
+                {
+                    vec![]
+                }
+                // Every flow that sets a new passphrase (new key, passphrase change, rotation) submits
+                // it from here, so the policy is enforced again whatever led to the prompts
+                Popup::PromptReenterPassphrase
+                    if model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_new_key_state()
+                        .check_passphrase_policy()
+                        .is_err() =>
+                {
+                    self.reject_new_passphrase(model)
+                }
+                // ssh-keygen would ask for both passphrases again, so it's stopped instead
+                Popup::PromptReenterPassphrase
+                    if model.is_popup_of_command(CmdTask::SshKeygen)
+                        && !model
+                            .get_sections_state()
+                            .get_public_keys_list_state()
+                            .get_new_key_state()
+                            .passphrases_match() =>
+                {
+                    vec![
+                        self.kill_command(CmdTask::SshKeygen),
+                        Message::CleanNewKeyPassphraseInput,
+                        Message::PrintError(String::from("Passphrases do not match. Try again.")),
+                    ]
+                }
+                // Nothing is sent to ssh-keygen until both passphrases are checked
+                Popup::PromptPassphrase => vec![Message::PromptReenterNewKeyPassPhrase],
+                // Unless ssh-keygen opened them, the prompts belong to the native flows
+                Popup::PromptReenterPassphrase if !model.is_popup_of_command(CmdTask::SshKeygen) => {
+                    match model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_new_key_state()
+                        .get_passphrase_purpose()
+                    {
+                        PassphrasePurpose::NewKey => self.generate_key_pair(model),
+                        PassphrasePurpose::ChangePassphrase => self.change_passphrase(model),
+                        PassphrasePurpose::RotateKey => self.rotate_key(model),
+                        PassphrasePurpose::EditComment
+                        | PassphrasePurpose::ImportKey
+                        | PassphrasePurpose::DerivePublicKey
+                        | PassphrasePurpose::AddToAgent
+                        | PassphrasePurpose::SignCertificate
+                        | PassphrasePurpose::SignFile => vec![],
+                    }
+                }
+                Popup::PromptKeyOverwrite if !model.is_popup_of_command(CmdTask::SshKeygen) => {
+                    vec![Message::PromptNewKeyPassphrase]
+                }
+                // The check is sent once ssh-keygen asks for it (see SendNewKeyPassphraseCheck)
+                Popup::PromptReenterPassphrase => self
+                    .write_to_cmd(
+                        CmdTask::SshKeygen,
+                        model
+                            .get_sections_state()
+                            .get_public_keys_list_state()
+                            .get_new_key_state()
+                            .get_passphrase()
+                            .as_bytes(),
+                    )
+                    .into_iter()
+                    .collect(),
                 Popup::PromptKeyOverwrite => {
                     let mut msgs: Vec<Message> = vec![];
 
@@ -339,9 +1508,80 @@ impl EventHandler {
                     self.delete_key_pair(model);
                     vec![]
                 }
+                Popup::PromptPurgeTrashedKeyConfirmation => {
+                    if let Some(trashed_key_pair) = model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_trash_state()
+                        .get_selected_item()
+                    {
+                        async_jobs::trash::purge_key_pair(
+                            trashed_key_pair.clone(),
+                            self.task_msg_tx.clone(),
+                        );
+                    }
+                    vec![]
+                }
+                Popup::AddCertAuthority => {
+                    self.add_cert_authority(model);
+                    vec![]
+                }
+                Popup::RevokeHostKey => {
+                    self.revoke_host_key(model);
+                    vec![]
+                }
+                Popup::PromptRemoveAgentIdentityConfirmation => {
+                    self.remove_agent_identity(model);
+                    vec![]
+                }
+                Popup::PromptRemoveAgentIdentitiesConfirmation => {
+                    async_jobs::agent::remove_all_identities(self.task_msg_tx.clone());
+                    vec![]
+                }
+                Popup::PromptRemoveAuthorizedKeyConfirmation => {
+                    if let Some(entry) = model
+                        .get_sections_state()
+                        .get_authorized_keys_state()
+                        .get_selected_entry()
+                    {
+                        async_jobs::authorized_keys::update_entry(
+                            entry.clone(),
+                            None,
+                            self.task_msg_tx.clone(),
+                        );
+                    }
+                    vec![]
+                }
+                Popup::PromptRemoveAllowedSignerConfirmation => {
+                    if let Some(entry) = model
+                        .get_sections_state()
+                        .get_public_keys_list_state()
+                        .get_allowed_signers_state()
+                        .get_selected_entry()
+                    {
+                        async_jobs::allowed_signers::update_entry(
+                            entry.clone(),
+                            None,
+                            self.task_msg_tx.clone(),
+                        );
+                    }
+                    vec![]
+                }
+                Popup::PermissionsAudit(issues) => {
+                    async_jobs::permissions::fix_permissions(issues, self.task_msg_tx.clone());
+                    vec![]
+                }
                 _ => vec![],
             },
             _ => vec![],
         }
     }
 }
+
+/// Error shown for the actions that need the public key of a private key listed without one
+fn missing_public_key_error(item: &PublicKeyInfo) -> String {
+    format!(
+        "'{}' has no public key, press 'g' to regenerate it",
+        item.file_name.trim_end_matches(".pub")
+    )
+}
@@ -0,0 +1,156 @@
+//! Audit of the key pairs against a policy that flags weak and legacy keys
+
+use std::{fmt::Display, fs, path::Path, time::SystemTime};
+
+use super::info::{PrivateKeyStatus, PublicKeyInfo};
+
+/// RSA keys smaller than this are refused by recent OpenSSH versions
+pub const MIN_RSA_BITS: usize = 2048;
+
+/// Maximum age of a key pair, in days. 0 disables the check
+const MAX_AGE_VAR: &str = "SSHY_MAX_KEY_AGE_DAYS";
+const DEFAULT_MAX_AGE_DAYS: u64 = 365;
+
+/// Comma separated list of the ECDSA curves that aren't allowed (e.g. `nistp256`)
+const DISALLOWED_CURVES_VAR: &str = "SSHY_DISALLOWED_CURVES";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What makes a key pair weak or legacy
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyPolicy {
+    /// None if the age of the keys isn't checked
+    pub max_age_days: Option<u64>,
+    pub disallowed_curves: Vec<String>,
+}
+
+impl KeyPolicy {
+    /// Reads the policy from the environment, falling back to the defaults for the variables that
+    /// are missing or invalid
+    pub fn from_env() -> Self {
+        let max_age_days = std::env::var(MAX_AGE_VAR)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_AGE_DAYS);
+        let disallowed_curves = std::env::var(DISALLOWED_CURVES_VAR)
+            .unwrap_or_default()
+            .split(',')
+            .map(|curve| curve.trim().to_lowercase())
+            .filter(|curve| !curve.is_empty())
+            .collect();
+
+        Self {
+            max_age_days: if max_age_days == 0 {
+                None
+            } else {
+                Some(max_age_days)
+            },
+            disallowed_curves,
+        }
+    }
+}
+
+/// A reason for a key pair to be replaced
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum KeyIssue {
+    /// DSA keys are disabled by default since OpenSSH 7.0
+    Dsa,
+    /// RSA key with the given number of bits
+    ShortRsa(usize),
+    /// ECDSA key on the given curve
+    DisallowedCurve(String),
+    /// The private key isn't protected by a passphrase
+    Unencrypted,
+    /// The key files are the given number of days old
+    Old(u64),
+}
+
+impl KeyIssue {
+    /// Short label shown in the public keys list
+    pub fn badge(&self) -> &'static str {
+        match self {
+            KeyIssue::Dsa => "DSA",
+            KeyIssue::ShortRsa(_) => "WEAK-RSA",
+            KeyIssue::DisallowedCurve(_) => "CURVE",
+            KeyIssue::Unencrypted => "NO-PASS",
+            KeyIssue::Old(_) => "OLD",
+        }
+    }
+}
+
+impl Display for KeyIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyIssue::Dsa => write!(f, "DSA key"),
+            KeyIssue::ShortRsa(bits) => {
+                write!(f, "RSA key of {} bits (< {})", bits, MIN_RSA_BITS)
+            }
+            KeyIssue::DisallowedCurve(curve) => write!(f, "ECDSA key on the {} curve", curve),
+            KeyIssue::Unencrypted => write!(f, "private key without passphrase"),
+            KeyIssue::Old(days) => write!(f, "{} days old", days),
+        }
+    }
+}
+
+/// Name of the curve of an ECDSA key, as used by OpenSSH, given its size
+fn curve_name(bits: usize) -> Option<&'static str> {
+    match bits {
+        256 => Some("nistp256"),
+        384 => Some("nistp384"),
+        521 => Some("nistp521"),
+        _ => None,
+    }
+}
+
+/// Days since the given file was last modified
+fn file_age_days(path: &Path, now: SystemTime) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+
+    now.duration_since(modified)
+        .ok()
+        .map(|age| age.as_secs() / SECONDS_PER_DAY)
+}
+
+/// Returns the problems of the given key pair, stored in the given ssh directory. Certificates
+/// aren't audited, as they are only as weak as the key they certify, which is audited on its own
+pub fn audit_key(ssh_dir: &Path, key: &PublicKeyInfo, policy: &KeyPolicy) -> Vec<KeyIssue> {
+    let mut issues = vec![];
+    if key.certificate.is_some() {
+        return issues;
+    }
+
+    match (key.algorithm.as_deref(), key.bits) {
+        (Some("DSA"), _) => issues.push(KeyIssue::Dsa),
+        (Some("RSA"), Some(bits)) if bits < MIN_RSA_BITS => issues.push(KeyIssue::ShortRsa(bits)),
+        (Some("ECDSA") | Some("ECDSA-SK"), Some(bits)) => {
+            if let Some(curve) =
+                curve_name(bits).filter(|curve| policy.disallowed_curves.iter().any(|c| c == curve))
+            {
+                issues.push(KeyIssue::DisallowedCurve(curve.to_owned()));
+            }
+        }
+        _ => {}
+    }
+
+    if key.private_key_status == PrivateKeyStatus::Unencrypted {
+        issues.push(KeyIssue::Unencrypted);
+    }
+
+    // The private key is what gets copied around, so its age is the one that matters
+    let private_key_path = ssh_dir.join(key.file_name.trim_end_matches(".pub"));
+    let key_path = if key.private_key_status == PrivateKeyStatus::Missing {
+        ssh_dir.join(&key.file_name)
+    } else {
+        private_key_path
+    };
+    if let (Some(max_age_days), Some(age_days)) = (
+        policy.max_age_days,
+        file_age_days(&key_path, SystemTime::now()),
+    ) {
+        if age_days > max_age_days {
+            issues.push(KeyIssue::Old(age_days));
+        }
+    }
+
+    issues
+}
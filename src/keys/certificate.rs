@@ -0,0 +1,602 @@
+//! Issuing and decoding of OpenSSH user certificates, like `ssh-keygen -s` and `ssh-keygen -L` do
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use base64ct::{Base64, Encoding};
+use color_eyre::eyre::{eyre, Result};
+use ssh_encoding::Reader;
+use ssh_key::{
+    certificate::{Builder, CertType},
+    rand_core::CryptoRngCore,
+    Certificate, PublicKey,
+};
+
+use super::{convert, signer::KeySigner};
+use crate::utils::{data_parsing, dates, permissions};
+
+/// Extensions given to the certificates by default, the same ones ssh-keygen gives
+const DEFAULT_EXTENSIONS: [&str; 5] = [
+    "permit-X11-forwarding",
+    "permit-agent-forwarding",
+    "permit-port-forwarding",
+    "permit-pty",
+    "permit-user-rc",
+];
+
+/// Valid before value of the certificates that never expire. OpenSSH uses `u64::MAX`, but the
+/// ssh-key crate only accepts timestamps up to `i64::MAX`, which is as good as forever
+const FOREVER: u64 = i64::MAX as u64;
+
+/// Number of fields of the certified public key, per certificate type
+const CERT_KEY_FIELDS: [(&str, usize); 8] = [
+    ("ssh-rsa-cert-v01@openssh.com", 2),
+    ("ssh-dss-cert-v01@openssh.com", 4),
+    ("ecdsa-sha2-nistp256-cert-v01@openssh.com", 2),
+    ("ecdsa-sha2-nistp384-cert-v01@openssh.com", 2),
+    ("ecdsa-sha2-nistp521-cert-v01@openssh.com", 2),
+    ("ssh-ed25519-cert-v01@openssh.com", 1),
+    ("sk-ecdsa-sha2-nistp256-cert-v01@openssh.com", 3),
+    ("sk-ssh-ed25519-cert-v01@openssh.com", 2),
+];
+
+/// Whether a certificate can be used at a given time
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CertValidity {
+    NotYetValid,
+    Valid,
+    Expired,
+}
+
+impl CertValidity {
+    pub fn of(certificate: &Certificate, now: u64) -> Self {
+        if now < certificate.valid_after() {
+            CertValidity::NotYetValid
+        } else if now >= certificate.valid_before() {
+            CertValidity::Expired
+        } else {
+            CertValidity::Valid
+        }
+    }
+}
+
+impl Display for CertValidity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CertValidity::NotYetValid => write!(f, "not yet valid"),
+            CertValidity::Valid => write!(f, "valid"),
+            CertValidity::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+/// Everything needed to issue a user certificate, except the passphrase of the CA key
+#[derive(Clone, Debug)]
+pub struct UserCertRequest {
+    pub ca_key_path: PathBuf,
+    pub public_key_path: PathBuf,
+    pub key_id: String,
+    pub principals: Vec<String>,
+    pub valid_after: u64,
+    pub valid_before: u64,
+    pub serial: u64,
+    pub critical_options: BTreeMap<String, String>,
+    pub extensions: BTreeMap<String, String>,
+}
+
+/// Parses a certificate in the OpenSSH format (`<type> <base64> [comment]`)
+///
+/// Timestamps bigger than the ones the ssh-key crate accepts (like the `forever` of ssh-keygen)
+/// are clamped before parsing
+pub fn read_certificate(content: &str) -> Result<Certificate> {
+    let parse_err = |e: ssh_key::Error| eyre!("failed to parse certificate: {}", e);
+
+    let mut fields = content.split_whitespace();
+    let (Some(algorithm), Some(encoded)) = (fields.next(), fields.next()) else {
+        return Err(eyre!("failed to parse certificate: missing fields"));
+    };
+    let comment = fields.collect::<Vec<&str>>().join(" ");
+
+    let mut blob =
+        Base64::decode_vec(encoded).map_err(|e| eyre!("failed to parse certificate: {}", e))?;
+    if let Some(valid_after_offset) = validity_offset(algorithm, &blob) {
+        for offset in [valid_after_offset, valid_after_offset + 8] {
+            let timestamp = &mut blob[offset..offset + 8];
+            if u64::from_be_bytes(timestamp.try_into().unwrap_or_default()) > FOREVER {
+                timestamp.copy_from_slice(&FOREVER.to_be_bytes());
+            }
+        }
+    }
+
+    let clamped = format!("{} {} {}", algorithm, Base64::encode_string(&blob), comment);
+    Certificate::from_openssh(clamped.trim_end()).map_err(parse_err)
+}
+
+/// Offset of the valid after timestamp in the certificate blob (valid before comes right after it)
+fn validity_offset(algorithm: &str, blob: &[u8]) -> Option<usize> {
+    let key_fields = CERT_KEY_FIELDS
+        .iter()
+        .find(|(name, _)| *name == algorithm)
+        .map(|(_, fields)| *fields)?;
+
+    let reader = &mut &blob[..];
+    // Type, nonce, key fields, serial, certificate type, key ID and principals
+    for _ in 0..2 + key_fields {
+        reader.drain_prefixed().ok()?;
+    }
+    reader.drain(8 + 4).ok()?;
+    for _ in 0..2 {
+        reader.drain_prefixed().ok()?;
+    }
+
+    let offset = blob.len() - reader.remaining_len();
+    (offset + 16 <= blob.len()).then_some(offset)
+}
+
+/// Path of the certificate of the given public key: `id_ed25519.pub` -> `id_ed25519-cert.pub`
+pub fn certificate_path(public_key_path: &Path) -> PathBuf {
+    let path = public_key_path.to_string_lossy();
+    let stem = path.strip_suffix(".pub").unwrap_or(&path);
+
+    PathBuf::from(format!("{}-cert.pub", stem))
+}
+
+/// Parses one of the times of a validity interval: `always`/`forever`, a relative time
+/// (`+52w`, `-1d`) or a date (`YYYYMMDD[HHMM[SS]]`, in UTC)
+fn parse_validity_time(value: &str, now: u64) -> Result<u64> {
+    match value {
+        "always" => Ok(0),
+        "forever" => Ok(FOREVER),
+        _ => {
+            if let Some(interval) = value.strip_prefix('+') {
+                Ok(now.saturating_add(data_parsing::parse_time_interval(interval)?.into()))
+            } else if let Some(interval) = value.strip_prefix('-') {
+                Ok(now.saturating_sub(data_parsing::parse_time_interval(interval)?.into()))
+            } else {
+                dates::parse_timestamp(value)
+            }
+        }
+    }
+}
+
+/// Parses a validity interval in the format of `ssh-keygen -V`: either `start:end` or only `end`
+/// (valid from now). An empty interval is valid forever
+///
+/// # Returns
+/// The valid after and valid before timestamps
+pub fn parse_validity_interval(value: &str, now: u64) -> Result<(u64, u64)> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok((0, FOREVER));
+    }
+
+    let (valid_after, valid_before) = match value.split_once(':') {
+        Some((start, end)) => (
+            parse_validity_time(start, now)?,
+            parse_validity_time(end, now)?,
+        ),
+        None => (now, parse_validity_time(value, now)?),
+    };
+
+    if valid_before <= valid_after {
+        return Err(eyre!("the validity interval ends before it starts"));
+    }
+
+    Ok((valid_after, valid_before))
+}
+
+/// Splits the options by whitespace, keeping together the quoted parts
+/// (e.g. `force-command="ls -l"`)
+fn split_options(value: &str) -> Result<Vec<String>> {
+    let mut options = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    options.push(std::mem::take(&mut current));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+    if quoted {
+        return Err(eyre!("unterminated quote in the certificate options"));
+    }
+    if !current.is_empty() {
+        options.push(current);
+    }
+
+    Ok(options)
+}
+
+/// Parses the certificate options, written like the `-O` options of ssh-keygen and separated by
+/// whitespace (e.g. `clear permit-pty source-address=10.0.0.0/8`)
+///
+/// # Returns
+/// The critical options and the extensions
+pub fn parse_cert_options(
+    value: &str,
+) -> Result<(BTreeMap<String, String>, BTreeMap<String, String>)> {
+    let mut critical_options = BTreeMap::new();
+    let mut extensions: BTreeMap<String, String> = DEFAULT_EXTENSIONS
+        .iter()
+        .map(|name| (name.to_string(), String::new()))
+        .collect();
+
+    for option in split_options(value)? {
+        let (name, data) = match option.split_once('=') {
+            Some((name, data)) => (name, Some(data)),
+            None => (option.as_str(), None),
+        };
+
+        match (name, data) {
+            ("clear", None) => extensions.clear(),
+            ("force-command" | "source-address", Some(data)) => {
+                critical_options.insert(name.to_owned(), data.to_owned());
+            }
+            ("verify-required", None) => {
+                critical_options.insert(name.to_owned(), String::new());
+            }
+            ("no-touch-required", None) => {
+                extensions.insert(name.to_owned(), String::new());
+            }
+            (name, None) if name.starts_with("permit-") => {
+                let extension = extension_name(name.trim_start_matches("permit-"))
+                    .ok_or_else(|| eyre!("unknown certificate option: '{}'", option))?;
+                extensions.insert(extension, String::new());
+            }
+            (name, None) if name.starts_with("no-") => {
+                let extension = extension_name(name.trim_start_matches("no-"))
+                    .ok_or_else(|| eyre!("unknown certificate option: '{}'", option))?;
+                extensions.remove(&extension);
+            }
+            (name, data) if name.starts_with("critical:") => {
+                critical_options.insert(
+                    name.trim_start_matches("critical:").to_owned(),
+                    data.unwrap_or_default().to_owned(),
+                );
+            }
+            (name, data) if name.starts_with("extension:") => {
+                extensions.insert(
+                    name.trim_start_matches("extension:").to_owned(),
+                    data.unwrap_or_default().to_owned(),
+                );
+            }
+            _ => return Err(eyre!("unknown certificate option: '{}'", option)),
+        }
+    }
+
+    Ok((critical_options, extensions))
+}
+
+/// Name of the `permit-*` extension of the given feature, as written in the certificates. As in
+/// ssh-keygen, the case of the feature doesn't matter (`permit-X11-forwarding`)
+fn extension_name(feature: &str) -> Option<String> {
+    let name = match feature.to_ascii_lowercase().as_str() {
+        "x11-forwarding" => "permit-X11-forwarding",
+        "agent-forwarding" => "permit-agent-forwarding",
+        "port-forwarding" => "permit-port-forwarding",
+        "pty" => "permit-pty",
+        "user-rc" => "permit-user-rc",
+        _ => return None,
+    };
+
+    Some(name.to_owned())
+}
+
+/// Formats the valid after timestamp of a certificate
+pub fn format_valid_after(certificate: &Certificate) -> String {
+    match certificate.valid_after() {
+        0 => String::from("always"),
+        valid_after => dates::format_timestamp(valid_after),
+    }
+}
+
+/// Formats the valid before timestamp of a certificate
+pub fn format_valid_before(certificate: &Certificate) -> String {
+    match certificate.valid_before() {
+        valid_before if valid_before >= FOREVER => String::from("forever"),
+        valid_before => dates::format_timestamp(valid_before),
+    }
+}
+
+/// Formats the validity interval of a certificate, like `ssh-keygen -L` does
+pub fn format_validity(certificate: &Certificate) -> String {
+    match (certificate.valid_after(), certificate.valid_before()) {
+        (0, valid_before) if valid_before >= FOREVER => String::from("forever"),
+        (0, valid_before) => format!("before {}", dates::format_timestamp(valid_before)),
+        (valid_after, valid_before) if valid_before >= FOREVER => {
+            format!("after {}", dates::format_timestamp(valid_after))
+        }
+        (valid_after, valid_before) => format!(
+            "from {} to {}",
+            dates::format_timestamp(valid_after),
+            dates::format_timestamp(valid_before)
+        ),
+    }
+}
+
+/// Issues a user certificate for a public key, signed with the CA key, and writes it next to the
+/// public key (`<name>-cert.pub`), like `ssh-keygen -s` does. The passphrase is only used if the CA
+/// key is encrypted
+///
+/// # Returns
+/// The path of the written certificate
+pub fn sign_user_key(
+    rng: &mut impl CryptoRngCore,
+    request: &UserCertRequest,
+    ca_passphrase: &[u8],
+) -> Result<PathBuf> {
+    let ca_key = convert::read_private_key(&request.ca_key_path, ca_passphrase)?;
+
+    let public_key = PublicKey::read_openssh_file(&request.public_key_path).map_err(|e| {
+        eyre!(
+            "failed to read public key '{}': {}",
+            request.public_key_path.to_string_lossy(),
+            e
+        )
+    })?;
+
+    if request.principals.is_empty() {
+        return Err(eyre!("at least one principal is required"));
+    }
+
+    let cert_err = |e: ssh_key::Error| eyre!("failed to issue certificate: {}", e);
+    let mut builder = Builder::new_with_random_nonce(
+        rng,
+        public_key.key_data().clone(),
+        request.valid_after,
+        request.valid_before,
+    )
+    .map_err(cert_err)?;
+    builder
+        .cert_type(CertType::User)
+        .and_then(|b| b.serial(request.serial))
+        .and_then(|b| b.key_id(request.key_id.as_str()))
+        .and_then(|b| b.comment(public_key.comment()))
+        .map_err(cert_err)?;
+    for principal in &request.principals {
+        builder
+            .valid_principal(principal.as_str())
+            .map_err(cert_err)?;
+    }
+    for (name, data) in &request.critical_options {
+        builder
+            .critical_option(name.as_str(), data.as_str())
+            .map_err(cert_err)?;
+    }
+    for (name, data) in &request.extensions {
+        builder
+            .extension(name.as_str(), data.as_str())
+            .map_err(cert_err)?;
+    }
+    let certificate = builder.sign(&KeySigner(&ca_key)).map_err(cert_err)?;
+
+    let certificate_content = certificate
+        .to_openssh()
+        .map_err(|e| eyre!("failed to encode certificate: {}", e))?;
+    let certificate_path = certificate_path(&request.public_key_path);
+    permissions::create_file_with_mode(&certificate_path, permissions::PUBLIC_FILE_MODE)
+        .and_then(|mut file| writeln!(file, "{}", certificate_content))
+        .map_err(|e| {
+            eyre!(
+                "failed to write certificate '{}': {}",
+                certificate_path.to_string_lossy(),
+                e
+            )
+        })?;
+
+    Ok(certificate_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use ssh_key::HashAlg;
+
+    use super::*;
+    use crate::{commands::ssh_keygen::PublicKeyType, keys::generate};
+
+    /// Issued by `ssh-keygen -s ca -I "build bot" -n deploy,git -z 42 -V always:forever -O clear
+    /// -O permit-pty -O force-command="ls -l"`, so it's valid until `u64::MAX`
+    const ED25519_CERT: &str = "ssh-ed25519-cert-v01@openssh.com AAAAIHNzaC1lZDI1NTE5LWNlcnQtdjAxQG9wZW5zc2guY29tAAAAIIyjtsNcKm+owGr8fVZFd+7COtZetr2GJkvvKhYF1cdLAAAAIDLOD9GPw+eutEEA6d1hq3qitRNRNNKPKlFawotFeew0AAAAAAAAACoAAAABAAAACWJ1aWxkIGJvdAAAABEAAAAGZGVwbG95AAAAA2dpdAAAAAAAAAAA//////////8AAAAeAAAADWZvcmNlLWNvbW1hbmQAAAAJAAAABWxzIC1sAAAAEgAAAApwZXJtaXQtcHR5AAAAAAAAAAAAAAAzAAAAC3NzaC1lZDI1NTE5AAAAIMy1Ml8hUQorfsYwmeGGIBmno/DlsMNOoLS9ZIrrUgZSAAAAUwAAAAtzc2gtZWQyNTUxOQAAAEABNZasleuMLp+/IutfvfNa+tFrbfqX+xzIo3jBgVKrJw7AkAIRzOyT3cuyrkjRPje0ENuw6ApmnceEHkdMmacC user@host";
+
+    /// Issued by the same CA with `ssh-keygen -s ca -I rsa -n alice -V 20240101:forever`
+    const RSA_CERT: &str = "ssh-rsa-cert-v01@openssh.com AAAAHHNzaC1yc2EtY2VydC12MDFAb3BlbnNzaC5jb20AAAAg+Hwckn2BvXPUltKJen4FfJcN5hykc5da1d8hP0yt8dwAAAADAQABAAAAgQDApyH2TYIV80Ii95DAYgkcJDTWozr4BbFeoVv2p/chObbduILjFizWdZaN/Q0rtFrU83sJk0kab+ncp4jx+tVWai3aYYWzLpwiWYA1pRe+4yDfcG75eBoquz0hQPQ+lUW3nuQDbPZF5Pp/LSpUCTrvRUMQSu/hoPe+1bj4GaLHkwAAAAAAAAAAAAAAAQAAAANyc2EAAAAJAAAABWFsaWNlAAAAAGWSAID//////////wAAAAAAAACCAAAAFXBlcm1pdC1YMTEtZm9yd2FyZGluZwAAAAAAAAAXcGVybWl0LWFnZW50LWZvcndhcmRpbmcAAAAAAAAAFnBlcm1pdC1wb3J0LWZvcndhcmRpbmcAAAAAAAAACnBlcm1pdC1wdHkAAAAAAAAADnBlcm1pdC11c2VyLXJjAAAAAAAAAAAAAAAzAAAAC3NzaC1lZDI1NTE5AAAAIMy1Ml8hUQorfsYwmeGGIBmno/DlsMNOoLS9ZIrrUgZSAAAAUwAAAAtzc2gtZWQyNTUxOQAAAEAoNXmq3cTMIjZIAImC3JySxcCGZNAN62Jgu6RJH10vkkBKSpQKENatOnceo57bjo5C8WKX8y+/Y0/1nGK3Vm8E rsa@host";
+
+    fn options(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, data)| (name.to_string(), data.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn ssh_keygen_certificates_are_parsed() {
+        let certificate = read_certificate(ED25519_CERT).unwrap();
+
+        assert_eq!(certificate.key_id(), "build bot");
+        assert_eq!(certificate.serial(), 42);
+        assert_eq!(certificate.cert_type(), CertType::User);
+        assert_eq!(certificate.valid_principals(), ["deploy", "git"]);
+        assert_eq!(
+            certificate
+                .critical_options()
+                .get("force-command")
+                .map(String::as_str),
+            Some("ls -l")
+        );
+        assert_eq!(
+            certificate.extensions().keys().collect::<Vec<&String>>(),
+            ["permit-pty"]
+        );
+        assert_eq!(certificate.comment(), "user@host");
+        assert_eq!(
+            certificate
+                .signature_key()
+                .fingerprint(HashAlg::Sha256)
+                .to_string(),
+            "SHA256:tiPftMsD6/jniHpEr8/3Kz4cExLYAeAvdvmFv/kbZxc"
+        );
+
+        assert!(read_certificate("ssh-ed25519-cert-v01@openssh.com").is_err());
+        assert!(read_certificate("ssh-ed25519-cert-v01@openssh.com AAAA!").is_err());
+    }
+
+    #[test]
+    fn validity_beyond_what_ssh_key_accepts_is_clamped() {
+        let certificate = read_certificate(ED25519_CERT).unwrap();
+        assert_eq!(certificate.valid_after(), 0);
+        assert_eq!(certificate.valid_before(), FOREVER);
+        assert_eq!(format_validity(&certificate), "forever");
+        assert_eq!(
+            CertValidity::of(&certificate, u64::MAX - 1),
+            CertValidity::Expired
+        );
+        assert_eq!(
+            CertValidity::of(&certificate, 1_700_000_000),
+            CertValidity::Valid
+        );
+
+        // The key of an RSA certificate has two fields before the validity, not one
+        let certificate = read_certificate(RSA_CERT).unwrap();
+        assert_eq!(certificate.valid_principals(), ["alice"]);
+        assert_eq!(certificate.valid_after(), 1_704_067_200);
+        assert_eq!(certificate.valid_before(), FOREVER);
+        assert_eq!(format_valid_before(&certificate), "forever");
+        assert_eq!(format_validity(&certificate), "after 2024-01-01 00:00:00");
+        assert_eq!(
+            CertValidity::of(&certificate, 1_704_067_199),
+            CertValidity::NotYetValid
+        );
+    }
+
+    #[test]
+    fn options_map_like_ssh_keygen() {
+        let default_extensions = DEFAULT_EXTENSIONS.map(|name| (name, ""));
+
+        assert_eq!(
+            parse_cert_options("").unwrap(),
+            (BTreeMap::new(), options(&default_extensions))
+        );
+        assert_eq!(
+            parse_cert_options("no-pty no-x11-forwarding permit-pty no-agent-forwarding").unwrap(),
+            (
+                BTreeMap::new(),
+                options(&[
+                    ("permit-port-forwarding", ""),
+                    ("permit-pty", ""),
+                    ("permit-user-rc", "")
+                ])
+            )
+        );
+        assert_eq!(
+            parse_cert_options(
+                r#"clear permit-X11-forwarding force-command="ls -l /" source-address=10.0.0.0/8,::1 verify-required no-touch-required"#
+            )
+            .unwrap(),
+            (
+                options(&[
+                    ("force-command", "ls -l /"),
+                    ("source-address", "10.0.0.0/8,::1"),
+                    ("verify-required", "")
+                ]),
+                options(&[("no-touch-required", ""), ("permit-X11-forwarding", "")])
+            )
+        );
+        assert_eq!(
+            parse_cert_options("clear critical:custom@example.com=a extension:login@example.com")
+                .unwrap(),
+            (
+                options(&[("custom@example.com", "a")]),
+                options(&[("login@example.com", "")])
+            )
+        );
+
+        assert!(parse_cert_options("permit-everything").is_err());
+        assert!(parse_cert_options("no-pty=yes").is_err());
+        assert!(parse_cert_options("force-command").is_err());
+        assert!(parse_cert_options(r#"force-command="ls"#).is_err());
+    }
+
+    #[test]
+    fn validity_intervals_parse_like_ssh_keygen() {
+        let now = 1_700_000_000;
+
+        assert_eq!(parse_validity_interval("", now).unwrap(), (0, FOREVER));
+        assert_eq!(
+            parse_validity_interval("always:forever", now).unwrap(),
+            (0, FOREVER)
+        );
+        assert_eq!(
+            parse_validity_interval("+52w", now).unwrap(),
+            (now, now + 52 * 7 * 86400)
+        );
+        assert_eq!(
+            parse_validity_interval("-1d:+1h", now).unwrap(),
+            (now - 86400, now + 3600)
+        );
+        assert_eq!(
+            parse_validity_interval("20240101:20250101", now).unwrap(),
+            (1_704_067_200, 1_735_689_600)
+        );
+
+        assert!(parse_validity_interval("+1d:-1d", now).is_err());
+        assert!(parse_validity_interval("-1d", now).is_err());
+        assert!(parse_validity_interval("+1x", now).is_err());
+    }
+
+    #[test]
+    fn issued_certificates_are_signed_by_the_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(5);
+        let ca_key_path = dir.path().join("ca");
+        let ca_key_pair =
+            generate::generate_key_pair(&mut rng, PublicKeyType::ECDSA256, "ca", b"secret")
+                .unwrap();
+        generate::write_key_pair(&ca_key_pair, &ca_key_path).unwrap();
+        let user_key_path = dir.path().join("id_ed25519");
+        let user_key_pair =
+            generate::generate_key_pair(&mut rng, PublicKeyType::ED25519, "me@host", b"").unwrap();
+        generate::write_key_pair(&user_key_pair, &user_key_path).unwrap();
+
+        let (critical_options, extensions) = parse_cert_options("clear permit-pty").unwrap();
+        let request = UserCertRequest {
+            ca_key_path,
+            public_key_path: dir.path().join("id_ed25519.pub"),
+            key_id: String::from("me"),
+            principals: vec![String::from("me"), String::from("root")],
+            valid_after: 1_700_000_000,
+            valid_before: FOREVER,
+            serial: 7,
+            critical_options,
+            extensions,
+        };
+        assert!(sign_user_key(&mut rng, &request, b"wrong").is_err());
+        let certificate_path = sign_user_key(&mut rng, &request, b"secret").unwrap();
+        assert_eq!(certificate_path, dir.path().join("id_ed25519-cert.pub"));
+
+        let certificate =
+            read_certificate(&std::fs::read_to_string(&certificate_path).unwrap()).unwrap();
+        let ca_fingerprint = ca_key_pair.public_key.fingerprint(HashAlg::Sha256);
+        certificate
+            .validate_at(1_700_000_000, [&ca_fingerprint])
+            .unwrap();
+        assert_eq!(
+            certificate.public_key(),
+            user_key_pair.public_key.key_data()
+        );
+        assert_eq!(certificate.valid_principals(), ["me", "root"]);
+        assert_eq!(certificate.serial(), 7);
+        assert_eq!(certificate.comment(), "me@host");
+        assert_eq!(format_validity(&certificate), "after 2023-11-14 22:13:20");
+    }
+}
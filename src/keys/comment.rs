@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result};
+use ssh_key::{rand_core::CryptoRngCore, LineEnding, PublicKey};
+
+use super::{convert, passphrase};
+
+/// Changes the comment of a key pair, like `ssh-keygen -c`. Both the private key (if it exists)
+/// and the public key are updated
+///
+/// The passphrase is only used if the private key is encrypted, which is encrypted again with
+/// the same passphrase after changing the comment
+pub fn change_comment(
+    rng: &mut impl CryptoRngCore,
+    private_key_path: &Path,
+    passphrase: &[u8],
+    comment: &str,
+) -> Result<()> {
+    let mut public_key_path = private_key_path.as_os_str().to_owned();
+    public_key_path.push(".pub");
+    let public_key_path = Path::new(&public_key_path);
+
+    if private_key_path.exists() {
+        let encrypted = passphrase::is_encrypted(private_key_path)?;
+        let mut private_key = convert::read_private_key(private_key_path, passphrase)?;
+
+        private_key.set_comment(comment);
+
+        if encrypted {
+            private_key = private_key
+                .encrypt(rng, passphrase)
+                .map_err(|e| eyre!("failed to encrypt private key: {}", e))?;
+        }
+
+        private_key
+            .write_openssh_file(private_key_path, LineEnding::LF)
+            .map_err(|e| {
+                eyre!(
+                    "failed to write private key '{}': {}",
+                    private_key_path.to_string_lossy(),
+                    e
+                )
+            })?;
+    }
+
+    let mut public_key = PublicKey::read_openssh_file(public_key_path).map_err(|e| {
+        eyre!(
+            "failed to read public key '{}': {}",
+            public_key_path.to_string_lossy(),
+            e
+        )
+    })?;
+    public_key.set_comment(comment);
+    public_key.write_openssh_file(public_key_path).map_err(|e| {
+        eyre!(
+            "failed to write public key '{}': {}",
+            public_key_path.to_string_lossy(),
+            e
+        )
+    })
+}
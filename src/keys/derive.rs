@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+
+use super::convert;
+
+/// Derives the public key of the private key at the given path and writes it next to it
+/// (`<private key>.pub`), like `ssh-keygen -y` does
+///
+/// The passphrase is only used if the private key is encrypted. Decrypting OpenSSH keys isn't
+/// needed to get their public key, but it is to recover their comment
+///
+/// # Returns
+/// The path of the written public key
+pub fn derive_public_key(private_key_path: &Path, passphrase: &[u8]) -> Result<PathBuf> {
+    let mut public_key_path = private_key_path.as_os_str().to_owned();
+    public_key_path.push(".pub");
+    let public_key_path = PathBuf::from(public_key_path);
+
+    if public_key_path.exists() {
+        return Err(eyre!(
+            "'{}' already exists",
+            public_key_path.to_string_lossy()
+        ));
+    }
+
+    let public_key = convert::read_private_key(private_key_path, passphrase)?
+        .public_key()
+        .clone();
+
+    public_key
+        .write_openssh_file(&public_key_path)
+        .map_err(|e| {
+            eyre!(
+                "failed to write public key '{}': {}",
+                public_key_path.to_string_lossy(),
+                e
+            )
+        })?;
+
+    Ok(public_key_path)
+}
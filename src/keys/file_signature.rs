@@ -0,0 +1,217 @@
+//! Signing of arbitrary files with SSH keys and verification of the signatures, like
+//! `ssh-keygen -Y sign` and `ssh-keygen -Y verify`
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use ssh_key::{HashAlg, LineEnding, SshSig};
+
+use super::{convert, info, signer::KeySigner};
+use crate::utils::{allowed_signers, permissions};
+
+/// Everything needed to sign a file
+#[derive(Clone, Debug)]
+pub struct SignFileRequest {
+    pub private_key_path: PathBuf,
+    pub file_path: PathBuf,
+    /// Domain the signature is valid for (e.g. `file`, `git`), so signatures made for one purpose
+    /// can't be used for another
+    pub namespace: String,
+}
+
+/// Everything needed to verify the signature of a file
+#[derive(Clone, Debug)]
+pub struct VerifyFileRequest {
+    pub file_path: PathBuf,
+    pub signature_path: PathBuf,
+    pub allowed_signers_path: PathBuf,
+    /// Principal the signature is expected to come from
+    pub identity: String,
+    pub namespace: String,
+}
+
+/// Path of the signature of the given file (`<file>.sig`), where ssh-keygen writes it
+pub fn signature_path(file_path: &Path) -> PathBuf {
+    let mut signature_path = file_path.as_os_str().to_owned();
+    signature_path.push(".sig");
+
+    PathBuf::from(signature_path)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|e| eyre!("failed to read '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Signs the file with the private key and writes the signature next to it (`<file>.sig`). The
+/// passphrase is only used if the private key is encrypted. An existing signature is never
+/// overwritten
+///
+/// # Returns
+/// The path of the written signature
+pub fn sign_file(request: &SignFileRequest, passphrase: &[u8]) -> Result<PathBuf> {
+    if request.namespace.trim().is_empty() {
+        return Err(eyre!("the namespace can't be empty"));
+    }
+
+    let signature_path = signature_path(&request.file_path);
+    if signature_path.exists() {
+        return Err(eyre!(
+            "'{}' already exists, delete or move it before signing the file again",
+            signature_path.to_string_lossy()
+        ));
+    }
+
+    let private_key = convert::read_private_key(&request.private_key_path, passphrase)?;
+
+    let message = read_file(&request.file_path)?;
+    let signature = SshSig::sign(
+        &KeySigner(&private_key),
+        request.namespace.trim(),
+        HashAlg::Sha512,
+        &message,
+    )
+    .map_err(|e| eyre!("failed to sign file: {}", e))?;
+    let signature_pem = signature
+        .to_pem(LineEnding::LF)
+        .map_err(|e| eyre!("failed to encode signature: {}", e))?;
+
+    permissions::create_file_with_mode(&signature_path, permissions::PUBLIC_FILE_MODE)
+        .and_then(|mut file| file.write_all(signature_pem.as_bytes()))
+        .map_err(|e| {
+            eyre!(
+                "failed to write signature '{}': {}",
+                signature_path.to_string_lossy(),
+                e
+            )
+        })?;
+
+    Ok(signature_path)
+}
+
+/// Verifies the signature of the file: it must be made under the namespace by a key that the
+/// allowed_signers file trusts for the identity, at the given time
+///
+/// # Returns
+/// A description of the good signature, in the same words ssh-keygen uses
+pub fn verify_file(request: &VerifyFileRequest, now: u64) -> Result<String> {
+    let signature_pem = fs::read_to_string(&request.signature_path).map_err(|e| {
+        eyre!(
+            "failed to read signature '{}': {}",
+            request.signature_path.to_string_lossy(),
+            e
+        )
+    })?;
+    let signature =
+        SshSig::from_pem(signature_pem).map_err(|e| eyre!("invalid signature: {}", e))?;
+
+    if signature.namespace() != request.namespace {
+        return Err(eyre!(
+            "signature made for the \"{}\" namespace, not \"{}\"",
+            signature.namespace(),
+            request.namespace
+        ));
+    }
+
+    let allowed_signers_content =
+        fs::read_to_string(&request.allowed_signers_path).map_err(|e| {
+            eyre!(
+                "failed to read allowed signers '{}': {}",
+                request.allowed_signers_path.to_string_lossy(),
+                e
+            )
+        })?;
+    let signer = allowed_signers::parse_allowed_signers(&allowed_signers_content)
+        .into_iter()
+        .filter(|entry| !entry.is_cert_authority() && entry.matches_principal(&request.identity))
+        .find_map(|entry| {
+            entry
+                .public_key()
+                .filter(|key| key.key_data() == signature.public_key())
+                .map(|key| (entry, key))
+        });
+    let Some((entry, public_key)) = signer else {
+        return Err(eyre!(
+            "the key of the signature isn't an allowed signer for '{}'",
+            request.identity
+        ));
+    };
+    if !entry.allows_namespace(&request.namespace) {
+        return Err(eyre!(
+            "'{}' isn't allowed to sign under the \"{}\" namespace",
+            request.identity,
+            request.namespace
+        ));
+    }
+    if !entry.is_valid_at(now) {
+        return Err(eyre!(
+            "the key of '{}' isn't valid at the current time",
+            request.identity
+        ));
+    }
+
+    let message = read_file(&request.file_path)?;
+    public_key
+        .verify(&request.namespace, &message, &signature)
+        .map_err(|_| eyre!("bad signature for '{}'", request.identity))?;
+
+    Ok(format!(
+        "Good \"{}\" signature for {} with {} key {}",
+        request.namespace,
+        request.identity,
+        info::algorithm_name(public_key.key_data()),
+        public_key.fingerprint(HashAlg::Sha256)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    use super::*;
+    use crate::{commands::ssh_keygen::PublicKeyType, keys::generate};
+
+    #[test]
+    fn existing_signature_is_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let private_key_path = dir.path().join("id_ed25519");
+        let key_pair = generate::generate_key_pair(
+            &mut ChaCha20Rng::seed_from_u64(3),
+            PublicKeyType::ED25519,
+            "c",
+            b"",
+        )
+        .unwrap();
+        generate::write_key_pair(&key_pair, &private_key_path).unwrap();
+        let file_path = dir.path().join("release.tar");
+        fs::write(&file_path, "content").unwrap();
+        let request = SignFileRequest {
+            private_key_path,
+            file_path: file_path.clone(),
+            namespace: String::from("file"),
+        };
+
+        let signature_path = sign_file(&request, b"").unwrap();
+        assert_eq!(signature_path, dir.path().join("release.tar.sig"));
+        let signature = fs::read_to_string(&signature_path).unwrap();
+        let signature = SshSig::from_pem(signature).unwrap();
+        assert_eq!(signature.namespace(), "file");
+        assert_eq!(signature.public_key(), key_pair.public_key.key_data());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&signature_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, permissions::PUBLIC_FILE_MODE);
+        }
+
+        fs::write(&signature_path, "other signature").unwrap();
+        assert!(sign_file(&request, b"").is_err());
+        assert_eq!(
+            fs::read_to_string(&signature_path).unwrap(),
+            "other signature"
+        );
+    }
+}
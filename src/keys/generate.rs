@@ -0,0 +1,212 @@
+use std::{io::Write, path::Path};
+
+use color_eyre::eyre::{eyre, Context, Result};
+use ssh_key::{
+    private::{EcdsaKeypair, Ed25519Keypair, KeypairData, RsaKeypair},
+    rand_core::CryptoRngCore,
+    EcdsaCurve, HashAlg, LineEnding, PrivateKey, PublicKey,
+};
+
+use crate::{commands::ssh_keygen::PublicKeyType, utils::permissions};
+
+/// A freshly generated key pair
+pub struct GeneratedKeyPair {
+    /// The private key, already encrypted if a passphrase was given
+    pub private_key: PrivateKey,
+    pub public_key: PublicKey,
+}
+
+impl GeneratedKeyPair {
+    /// SHA256 fingerprint of the public key, as shown by ssh-keygen
+    pub fn fingerprint(&self) -> String {
+        self.public_key.fingerprint(HashAlg::Sha256).to_string()
+    }
+}
+
+fn random_keypair_data(rng: &mut impl CryptoRngCore, key_type: PublicKeyType) -> Result<KeypairData> {
+    let keypair_data = match key_type {
+        PublicKeyType::ED25519 => KeypairData::from(Ed25519Keypair::random(rng)),
+        PublicKeyType::RSA2048 => KeypairData::from(RsaKeypair::random(rng, 2048)?),
+        PublicKeyType::RSA3072 => KeypairData::from(RsaKeypair::random(rng, 3072)?),
+        PublicKeyType::RSA4096 => KeypairData::from(RsaKeypair::random(rng, 4096)?),
+        PublicKeyType::ECDSA256 => {
+            KeypairData::from(EcdsaKeypair::random(rng, EcdsaCurve::NistP256)?)
+        }
+        PublicKeyType::ECDSA384 => {
+            KeypairData::from(EcdsaKeypair::random(rng, EcdsaCurve::NistP384)?)
+        }
+        PublicKeyType::ECDSA521 => {
+            KeypairData::from(EcdsaKeypair::random(rng, EcdsaCurve::NistP521)?)
+        }
+    };
+
+    Ok(keypair_data)
+}
+
+/// Generates a new key pair of the given type
+///
+/// The randomness comes exclusively from the given rng, so the result is deterministic for a
+/// seeded one. If the passphrase is not empty, the private key is encrypted with it, using
+/// aes256-ctr and bcrypt-pbkdf (the same defaults as ssh-keygen).
+pub fn generate_key_pair(
+    rng: &mut impl CryptoRngCore,
+    key_type: PublicKeyType,
+    comment: &str,
+    passphrase: &[u8],
+) -> Result<GeneratedKeyPair> {
+    let keypair_data = random_keypair_data(rng, key_type)
+        .map_err(|e| eyre!("failed to generate {} key: {}", key_type, e))?;
+
+    let mut private_key = PrivateKey::new(keypair_data, comment)
+        .map_err(|e| eyre!("failed to build private key: {}", e))?;
+    let public_key = private_key.public_key().clone();
+
+    if !passphrase.is_empty() {
+        private_key = private_key
+            .encrypt(rng, passphrase)
+            .map_err(|e| eyre!("failed to encrypt private key: {}", e))?;
+    }
+
+    Ok(GeneratedKeyPair {
+        private_key,
+        public_key,
+    })
+}
+
+/// Writes the key pair to the given path, in OpenSSH format. The public key is written to the
+/// same path, with the .pub extension appended. Existing files are overwritten and get the modes
+/// ssh expects
+pub fn write_key_pair(key_pair: &GeneratedKeyPair, private_key_path: &Path) -> Result<()> {
+    let private_key_content = key_pair
+        .private_key
+        .to_openssh(LineEnding::LF)
+        .map_err(|e| eyre!("failed to encode private key: {}", e))?;
+    permissions::create_file_with_mode(private_key_path, permissions::PRIVATE_FILE_MODE)
+        .and_then(|mut file| file.write_all(private_key_content.as_bytes()))
+        .wrap_err_with(|| {
+            format!(
+                "Failed to write private key '{}'",
+                private_key_path.to_string_lossy()
+            )
+        })?;
+
+    let mut public_key_path = private_key_path.as_os_str().to_owned();
+    public_key_path.push(".pub");
+    let public_key_path = Path::new(&public_key_path);
+    let mut public_key_content = key_pair
+        .public_key
+        .to_openssh()
+        .map_err(|e| eyre!("failed to encode public key: {}", e))?;
+    public_key_content.push('\n');
+    permissions::create_file_with_mode(public_key_path, permissions::PUBLIC_FILE_MODE)
+        .and_then(|mut file| file.write_all(public_key_content.as_bytes()))
+        .wrap_err_with(|| {
+            format!(
+                "Failed to write public key '{}'",
+                public_key_path.to_string_lossy()
+            )
+        })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    use super::*;
+
+    fn all_key_types() -> Vec<PublicKeyType> {
+        let mut key_types = vec![PublicKeyType::default()];
+        let mut key_type = PublicKeyType::default().next();
+        while key_type != PublicKeyType::default() {
+            key_types.push(key_type);
+            key_type = key_type.next();
+        }
+
+        key_types
+    }
+
+    #[test]
+    fn same_seed_generates_same_key_pair() {
+        for key_type in all_key_types() {
+            let first =
+                generate_key_pair(&mut ChaCha20Rng::seed_from_u64(7), key_type, "c", b"").unwrap();
+            let second =
+                generate_key_pair(&mut ChaCha20Rng::seed_from_u64(7), key_type, "c", b"").unwrap();
+            let other =
+                generate_key_pair(&mut ChaCha20Rng::seed_from_u64(8), key_type, "c", b"").unwrap();
+
+            assert_eq!(first.public_key, second.public_key, "{}", key_type);
+            assert_ne!(first.public_key, other.public_key, "{}", key_type);
+        }
+    }
+
+    #[test]
+    fn written_key_pair_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        for key_type in all_key_types() {
+            for passphrase in [&b""[..], b"correct horse"] {
+                let key_pair =
+                    generate_key_pair(&mut rng, key_type, "user@host", passphrase).unwrap();
+                let path = dir
+                    .path()
+                    .join(format!("{}-{}", key_type, passphrase.len()));
+                write_key_pair(&key_pair, &path).unwrap();
+
+                let private_key = PrivateKey::read_openssh_file(&path).unwrap();
+                assert_eq!(private_key.is_encrypted(), !passphrase.is_empty());
+                let private_key = if passphrase.is_empty() {
+                    private_key
+                } else {
+                    assert!(private_key.decrypt(b"wrong").is_err());
+                    private_key.decrypt(passphrase).unwrap()
+                };
+                assert_eq!(
+                    private_key.public_key().key_data(),
+                    key_pair.public_key.key_data()
+                );
+                assert_eq!(private_key.comment(), "user@host");
+
+                let mut public_key_path = path.into_os_string();
+                public_key_path.push(".pub");
+                let public_key = PublicKey::read_openssh_file(Path::new(&public_key_path)).unwrap();
+                assert_eq!(public_key.key_data(), key_pair.public_key.key_data());
+                assert_eq!(
+                    key_pair.fingerprint(),
+                    public_key.fingerprint(HashAlg::Sha256).to_string()
+                );
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn overwritten_key_pair_gets_private_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id_ed25519");
+        std::fs::write(&path, "old key").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let key_pair = generate_key_pair(
+            &mut ChaCha20Rng::seed_from_u64(1),
+            PublicKeyType::ED25519,
+            "c",
+            b"",
+        )
+        .unwrap();
+        write_key_pair(&key_pair, &path).unwrap();
+
+        let mode = |path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), permissions::PRIVATE_FILE_MODE);
+        assert_eq!(
+            mode(&dir.path().join("id_ed25519.pub")),
+            permissions::PUBLIC_FILE_MODE
+        );
+        assert!(PrivateKey::read_openssh_file(&path).is_ok());
+    }
+}
@@ -0,0 +1,43 @@
+//! Native (in-process) handling of SSH keys, based on the ssh-key crate
+
+pub mod audit;
+pub mod certificate;
+pub mod comment;
+pub mod convert;
+pub mod derive;
+pub mod file_signature;
+pub mod generate;
+pub mod info;
+pub mod passphrase;
+pub mod passphrase_policy;
+pub mod signer;
+
+use std::fmt::Display;
+
+/// Which program generates the new key pairs
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum KeygenBackend {
+    /// Keys are generated in-process, without depending on the ssh-keygen prompts
+    #[default]
+    Native,
+    /// Keys are generated by running ssh-keygen in a PTY
+    SshKeygen,
+}
+
+impl KeygenBackend {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Native => Self::SshKeygen,
+            Self::SshKeygen => Self::Native,
+        }
+    }
+}
+
+impl Display for KeygenBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::SshKeygen => write!(f, "ssh-keygen"),
+        }
+    }
+}
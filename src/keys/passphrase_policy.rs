@@ -0,0 +1,286 @@
+//! Strength estimate of the passphrases given to new keys, and the minimum policy they must meet
+
+use std::fmt::Display;
+
+/// Minimum number of characters of a passphrase
+const MIN_LENGTH_VAR: &str = "SSHY_PASSPHRASE_MIN_LENGTH";
+const DEFAULT_MIN_LENGTH: usize = 8;
+
+/// Minimum estimated entropy of a passphrase, in bits
+const MIN_ENTROPY_VAR: &str = "SSHY_PASSPHRASE_MIN_ENTROPY";
+const DEFAULT_MIN_ENTROPY_BITS: u32 = 0;
+
+/// Whether keys without passphrase are refused (`1` or `true`)
+const REQUIRED_VAR: &str = "SSHY_PASSPHRASE_REQUIRED";
+
+/// Entropy at which the strength gauge is full
+pub const FULL_GAUGE_BITS: f64 = 128.0;
+
+/// Characters in each class an attacker would have to try
+const LOWERCASE_POOL: u32 = 26;
+const UPPERCASE_POOL: u32 = 26;
+const DIGITS_POOL: u32 = 10;
+const SYMBOLS_POOL: u32 = 33;
+const NON_ASCII_POOL: u32 = 100;
+
+/// Minimum a new passphrase must meet to be accepted
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PassphrasePolicy {
+    pub min_length: usize,
+    pub min_entropy_bits: u32,
+    /// Whether an empty passphrase (a key that isn't encrypted) is refused
+    pub required: bool,
+}
+
+impl PassphrasePolicy {
+    /// Reads the policy from the environment, falling back to the defaults for the variables that
+    /// are missing or invalid
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Builds the policy from the value of each variable, as returned by get_var
+    fn from_vars(get_var: impl Fn(&str) -> Option<String>) -> Self {
+        let min_length = get_var(MIN_LENGTH_VAR)
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MIN_LENGTH);
+        let min_entropy_bits = get_var(MIN_ENTROPY_VAR)
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(DEFAULT_MIN_ENTROPY_BITS);
+        let required = get_var(REQUIRED_VAR)
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        Self {
+            min_length,
+            min_entropy_bits,
+            required,
+        }
+    }
+
+    /// Checks the passphrase against the policy. An empty passphrase is only checked against
+    /// `required`, since it means the key won't be encrypted at all
+    ///
+    /// # Returns
+    /// Why the passphrase isn't accepted
+    pub fn check(&self, passphrase: &str) -> Result<(), String> {
+        if passphrase.is_empty() {
+            return match self.required {
+                true => Err(String::from("A passphrase is required")),
+                false => Ok(()),
+            };
+        }
+
+        if passphrase.chars().count() < self.min_length {
+            return Err(format!(
+                "Too short, at least {} characters",
+                self.min_length
+            ));
+        }
+
+        let entropy_bits = estimate_entropy_bits(passphrase);
+        if entropy_bits < f64::from(self.min_entropy_bits) {
+            return Err(format!(
+                "Too weak, at least {} bits of entropy",
+                self.min_entropy_bits
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Estimates the entropy of the passphrase, in bits, from the classes of characters it uses and
+/// its length. Characters repeating or following the previous one (e.g. `aaa` or `abc`) barely
+/// add to it, so they aren't counted
+pub fn estimate_entropy_bits(passphrase: &str) -> f64 {
+    let (mut lowercase, mut uppercase, mut digits, mut symbols, mut non_ascii) =
+        (false, false, false, false, false);
+    let mut counted_chars = 0;
+    let mut prev_char: Option<char> = None;
+
+    for ch in passphrase.chars() {
+        match ch {
+            'a'..='z' => lowercase = true,
+            'A'..='Z' => uppercase = true,
+            '0'..='9' => digits = true,
+            _ if ch.is_ascii() => symbols = true,
+            _ => non_ascii = true,
+        }
+
+        let is_predictable = prev_char.is_some_and(|prev| (prev as i64 - ch as i64).abs() <= 1);
+        if !is_predictable {
+            counted_chars += 1;
+        }
+        prev_char = Some(ch);
+    }
+
+    let pool: u32 = [
+        (lowercase, LOWERCASE_POOL),
+        (uppercase, UPPERCASE_POOL),
+        (digits, DIGITS_POOL),
+        (symbols, SYMBOLS_POOL),
+        (non_ascii, NON_ASCII_POOL),
+    ]
+    .iter()
+    .filter(|(used, _)| *used)
+    .map(|(_, size)| size)
+    .sum();
+
+    if pool == 0 {
+        return 0.0;
+    }
+
+    f64::from(counted_chars) * f64::from(pool).log2()
+}
+
+/// Rough strength of a passphrase, from its estimated entropy
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PassphraseStrength {
+    Empty,
+    VeryWeak,
+    Weak,
+    Reasonable,
+    Strong,
+    VeryStrong,
+}
+
+impl PassphraseStrength {
+    pub fn of(passphrase: &str) -> Self {
+        if passphrase.is_empty() {
+            return Self::Empty;
+        }
+
+        match estimate_entropy_bits(passphrase) {
+            bits if bits < 28.0 => Self::VeryWeak,
+            bits if bits < 36.0 => Self::Weak,
+            bits if bits < 60.0 => Self::Reasonable,
+            bits if bits < FULL_GAUGE_BITS => Self::Strong,
+            _ => Self::VeryStrong,
+        }
+    }
+}
+
+impl Display for PassphraseStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no passphrase"),
+            Self::VeryWeak => write!(f, "very weak"),
+            Self::Weak => write!(f, "weak"),
+            Self::Reasonable => write!(f, "reasonable"),
+            Self::Strong => write!(f, "strong"),
+            Self::VeryStrong => write!(f, "very strong"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn policy_from(vars: &[(&str, &str)]) -> PassphrasePolicy {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        PassphrasePolicy::from_vars(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn policy_is_read_from_the_variables() {
+        assert_eq!(
+            policy_from(&[]),
+            PassphrasePolicy {
+                min_length: DEFAULT_MIN_LENGTH,
+                min_entropy_bits: DEFAULT_MIN_ENTROPY_BITS,
+                required: false,
+            }
+        );
+        assert_eq!(
+            policy_from(&[
+                (MIN_LENGTH_VAR, " 12 "),
+                (MIN_ENTROPY_VAR, "60"),
+                (REQUIRED_VAR, "TRUE"),
+            ]),
+            PassphrasePolicy {
+                min_length: 12,
+                min_entropy_bits: 60,
+                required: true,
+            }
+        );
+        assert!(policy_from(&[(REQUIRED_VAR, "1")]).required);
+        assert!(policy_from(&[(REQUIRED_VAR, "yes")]).required);
+    }
+
+    #[test]
+    fn invalid_variables_fall_back_to_the_defaults() {
+        let policy = policy_from(&[
+            (MIN_LENGTH_VAR, "-1"),
+            (MIN_ENTROPY_VAR, "lots"),
+            (REQUIRED_VAR, "sure"),
+        ]);
+
+        assert_eq!(policy.min_length, DEFAULT_MIN_LENGTH);
+        assert_eq!(policy.min_entropy_bits, DEFAULT_MIN_ENTROPY_BITS);
+        assert!(!policy.required);
+        assert_eq!(
+            policy_from(&[(MIN_ENTROPY_VAR, "40.5")]).min_entropy_bits,
+            0
+        );
+    }
+
+    #[test]
+    fn length_is_counted_in_characters() {
+        let policy = policy_from(&[(MIN_LENGTH_VAR, "4")]);
+
+        assert!(policy.check("q7z").is_err());
+        assert!(policy.check("q7zx").is_ok());
+        // 4 characters, but 8 bytes
+        assert!(policy.check("ñññ").is_err());
+        assert!(policy.check("ñañ1").is_ok());
+    }
+
+    #[test]
+    fn entropy_is_checked_at_the_boundary() {
+        // 4 unpredictable lowercase characters: 4 * log2(26) ~ 18.8 bits
+        let passphrase = "qzmx";
+        let bits = estimate_entropy_bits(passphrase);
+        assert!((bits - 4.0 * 26f64.log2()).abs() < 1e-9);
+
+        let policy = |min_entropy_bits| PassphrasePolicy {
+            min_length: 0,
+            min_entropy_bits,
+            required: false,
+        };
+        assert!(policy(bits.floor() as u32).check(passphrase).is_ok());
+        assert!(policy(bits.ceil() as u32).check(passphrase).is_err());
+    }
+
+    #[test]
+    fn empty_passphrase_is_only_refused_when_required() {
+        let policy = policy_from(&[(MIN_LENGTH_VAR, "20"), (MIN_ENTROPY_VAR, "100")]);
+        assert!(policy.check("").is_ok());
+
+        let policy = policy_from(&[(REQUIRED_VAR, "true"), (MIN_LENGTH_VAR, "0")]);
+        assert!(policy.check("").is_err());
+        assert!(policy.check("x").is_ok());
+    }
+
+    #[test]
+    fn predictable_characters_barely_add_entropy() {
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+        assert_eq!(
+            estimate_entropy_bits("aaaaaaaa"),
+            estimate_entropy_bits("a")
+        );
+        assert_eq!(
+            estimate_entropy_bits("abcdefgh"),
+            estimate_entropy_bits("a")
+        );
+        assert!(estimate_entropy_bits("aZ3!") > estimate_entropy_bits("azqm"));
+        assert_eq!(PassphraseStrength::of(""), PassphraseStrength::Empty);
+        assert_eq!(
+            PassphraseStrength::of("abcdefgh"),
+            PassphraseStrength::VeryWeak
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use rsa::{
+    pkcs1v15,
+    signature::{Error, SignatureEncoding, Signer},
+    BigUint, RsaPrivateKey,
+};
+use ssh_key::{
+    private::{KeypairData, RsaKeypair},
+    public::KeyData,
+    sha2::Sha512,
+    Algorithm, HashAlg, PrivateKey, Signature,
+};
+
+/// Decrypted private key used to sign certificates and files.
+///
+/// ssh-key builds the RSA private key out of its first prime twice, so every RSA signature it makes
+/// fails. RSA keys are signed here instead (rsa-sha2-512, as ssh-keygen does), while the other
+/// algorithms are left to ssh-key
+pub struct KeySigner<'a>(pub &'a PrivateKey);
+
+impl Signer<Signature> for KeySigner<'_> {
+    fn try_sign(&self, message: &[u8]) -> Result<Signature, Error> {
+        match self.0.key_data() {
+            KeypairData::Rsa(keypair) => sign_rsa(keypair, message),
+            _ => self.0.try_sign(message),
+        }
+    }
+}
+
+impl From<&KeySigner<'_>> for KeyData {
+    fn from(signer: &KeySigner<'_>) -> Self {
+        signer.0.public_key().key_data().clone()
+    }
+}
+
+fn sign_rsa(keypair: &RsaKeypair, message: &[u8]) -> Result<Signature, Error> {
+    let to_biguint = |mpint: &ssh_key::Mpint| {
+        mpint
+            .as_positive_bytes()
+            .map(BigUint::from_bytes_be)
+            .ok_or_else(Error::new)
+    };
+
+    let private_key = RsaPrivateKey::from_components(
+        to_biguint(&keypair.public.n)?,
+        to_biguint(&keypair.public.e)?,
+        to_biguint(&keypair.private.d)?,
+        vec![
+            to_biguint(&keypair.private.p)?,
+            to_biguint(&keypair.private.q)?,
+        ],
+    )
+    .map_err(Error::from_source)?;
+
+    let signature = pkcs1v15::SigningKey::<Sha512>::new(private_key).try_sign(message)?;
+
+    Signature::new(
+        Algorithm::Rsa {
+            hash: Some(HashAlg::Sha512),
+        },
+        signature.to_vec(),
+    )
+    .map_err(Error::from_source)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use rsa::signature::Verifier;
+    use ssh_key::{certificate::Builder, SshSig};
+
+    use super::*;
+    use crate::{commands::ssh_keygen::PublicKeyType, keys::generate};
+
+    #[test]
+    fn rsa_signatures_verify() {
+        let mut rng = ChaCha20Rng::seed_from_u64(9);
+        let ca_key = generate::generate_key_pair(&mut rng, PublicKeyType::RSA2048, "ca", b"")
+            .unwrap()
+            .private_key;
+        let user_key =
+            generate::generate_key_pair(&mut rng, PublicKeyType::ED25519, "me", b"").unwrap();
+
+        let signature = KeySigner(&ca_key).try_sign(b"message").unwrap();
+        assert_eq!(
+            signature.algorithm(),
+            Algorithm::Rsa {
+                hash: Some(HashAlg::Sha512)
+            }
+        );
+        let ca_public_key = ca_key.public_key().key_data();
+        ca_public_key.verify(b"message", &signature).unwrap();
+        assert!(ca_public_key.verify(b"other message", &signature).is_err());
+
+        let mut builder = Builder::new_with_random_nonce(
+            &mut rng,
+            user_key.public_key.key_data().clone(),
+            0,
+            100,
+        )
+        .unwrap();
+        builder.valid_principal("me").unwrap();
+        let certificate = builder.sign(&KeySigner(&ca_key)).unwrap();
+        certificate
+            .validate_at(50, [&ca_key.fingerprint(HashAlg::Sha256)])
+            .unwrap();
+
+        let file_signature =
+            SshSig::sign(&KeySigner(&ca_key), "file", HashAlg::Sha512, b"content").unwrap();
+        ca_key
+            .public_key()
+            .verify("file", b"content", &file_signature)
+            .unwrap();
+    }
+}
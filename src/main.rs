@@ -1,5 +1,7 @@
+mod agent;
 mod commands;
 mod events;
+mod keys;
 mod model;
 mod terminal;
 mod ui;
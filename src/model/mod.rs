@@ -1,17 +1,21 @@
 pub mod sections_state;
 pub mod vim_emulator;
 
-use sections_state::{client_config_state::ClientConfigState, public_keys_list_state::NewPublicKeyFocus};
+use sections_state::{
+    agent_state::AddToAgentFocus, client_config_state::ClientConfigState,
+    public_keys_list_state::NewPublicKeyFocus,
+};
 use vim_emulator::VimMode;
 
 use crate::{
-    commands::{self, CmdTask},
+    commands,
     events::messages::Message,
     ui::{
         color_variants::ColorVariant,
         components::{popups::Popup, sections::Section},
         Focus,
     },
+    utils::files,
 };
 
 use self::sections_state::SectionsStates;
@@ -35,10 +39,11 @@ pub struct Model {
     current_section: Section,
     current_focus: Focus,
     sections_states: SectionsStates,
-    /// Indicates which, if any, is the currently running command
-    /// NOTE: For now, there'll only be a single command running at a time. In the future, I'm
-    /// thinking of being able to allow multiple commands at a time
-    current_commands: Vec<commands::CmdTask>,
+    /// Commands currently running, at most one per task. The ones whose popup was hidden keep
+    /// running in the background
+    current_commands: Vec<commands::RunningCmd>,
+    /// Command the current popup belongs to, if any. Cancelling the popup stops that command only
+    popup_command: Option<commands::CmdTask>,
     /// When this field is Some, the content must the displayed in an error popup and ANY input
     /// must end in the process termination
     fatal_error: Option<String>,
@@ -81,7 +86,15 @@ impl Model {
                             .sections_states
                             .get_public_keys_list_state_mut()
                             .next_item(),
-                        Section::ClientConfig => {}
+                        Section::Agent => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .next_item(),
+                        Section::ClientConfig => {},
+                        Section::AuthorizedKeys => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .next_item(),
                     }
                 }
             }
@@ -96,7 +109,15 @@ impl Model {
                             .sections_states
                             .get_public_keys_list_state_mut()
                             .prev_item(),
-                        Section::ClientConfig => {}
+                        Section::Agent => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .prev_item(),
+                        Section::ClientConfig => {},
+                        Section::AuthorizedKeys => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .prev_item(),
                     }
                 }
             }
@@ -110,6 +131,75 @@ impl Model {
                                 .get_new_key_state_mut();
                             new_key_state.next_focus();
                         }
+                        Popup::AddCertAuthority => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .get_new_cert_authority_state_mut()
+                            .next_focus(),
+                        Popup::ImportKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_import_key_state_mut()
+                            .next_focus(),
+                        Popup::RotateKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rotate_key_state_mut()
+                            .next_focus(),
+                        Popup::SignCertificate => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_certificate_state_mut()
+                            .next_focus(),
+                        Popup::SignFile => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_file_state_mut()
+                            .next_focus(),
+                        Popup::VerifySignature => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_verify_signature_state_mut()
+                            .next_focus(),
+                        Popup::AllowedSigners => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .next_item(),
+                        Popup::Trash => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_trash_state_mut()
+                            .next_item(),
+                        Popup::AllowedSigner => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .get_form_state_mut()
+                            .next_focus(),
+                        Popup::GitSigning => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_git_signing_state_mut()
+                            .next_focus(),
+                        Popup::AuthorizedKey => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .get_form_state_mut()
+                            .next_focus(),
+                        Popup::AddToAgent | Popup::AddNewKeyToAgent(_) => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .get_add_to_agent_state_mut()
+                            .next_focus(),
+                        Popup::KnownHostsMarkers => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .next_marker(),
+                        Popup::RevokeHostKey => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .next_host_key(),
                         Popup::ExitPrompt => {}
                         _ => {}
                     }
@@ -125,11 +215,204 @@ impl Model {
                                 .get_new_key_state_mut();
                             new_key_state.prev_focus();
                         }
+                        Popup::AddCertAuthority => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .get_new_cert_authority_state_mut()
+                            .prev_focus(),
+                        Popup::ImportKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_import_key_state_mut()
+                            .prev_focus(),
+                        Popup::RotateKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rotate_key_state_mut()
+                            .prev_focus(),
+                        Popup::SignCertificate => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_certificate_state_mut()
+                            .prev_focus(),
+                        Popup::SignFile => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_file_state_mut()
+                            .prev_focus(),
+                        Popup::VerifySignature => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_verify_signature_state_mut()
+                            .prev_focus(),
+                        Popup::AllowedSigners => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .prev_item(),
+                        Popup::Trash => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_trash_state_mut()
+                            .prev_item(),
+                        Popup::AllowedSigner => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .get_form_state_mut()
+                            .prev_focus(),
+                        Popup::GitSigning => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_git_signing_state_mut()
+                            .prev_focus(),
+                        Popup::AuthorizedKey => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .get_form_state_mut()
+                            .prev_focus(),
+                        Popup::AddToAgent | Popup::AddNewKeyToAgent(_) => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .get_add_to_agent_state_mut()
+                            .prev_focus(),
+                        Popup::KnownHostsMarkers => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .prev_marker(),
+                        Popup::RevokeHostKey => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .prev_host_key(),
                         Popup::ExitPrompt => {}
                         _ => {}
                     }
                 }
             }
+            Message::SelNextPopupOption => match self.current_focus {
+                Focus::Popup(Popup::AddPubKey) => {
+                    let new_key_state = self
+                        .sections_states
+                        .get_public_keys_list_state_mut()
+                        .get_new_key_state_mut();
+                    match new_key_state.get_focus() {
+                        NewPublicKeyFocus::KeyType => new_key_state.next_type(),
+                        NewPublicKeyFocus::Backend => new_key_state.toggle_backend(),
+                        _ => {}
+                    }
+                }
+                Focus::Popup(Popup::ExportKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .next_export_format(),
+                Focus::Popup(Popup::ShowPubKeyContent) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .next_qr_part(),
+                Focus::Popup(Popup::RotateKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_rotate_key_state_mut()
+                    .next_option(),
+                Focus::Popup(Popup::SignCertificate) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_sign_certificate_state_mut()
+                    .next_option(),
+                Focus::Popup(Popup::DeployKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_deploy_key_state_mut()
+                    .next_option(),
+                Focus::Popup(Popup::AllowedSigner) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut()
+                    .get_form_state_mut()
+                    .next_option(),
+                Focus::Popup(Popup::GitSigning) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_git_signing_state_mut()
+                    .toggle_option(),
+                Focus::Popup(Popup::AuthorizedKey) => self
+                    .sections_states
+                    .get_authorized_keys_state_mut()
+                    .get_form_state_mut()
+                    .next_option(),
+                Focus::Popup(Popup::AddToAgent) | Focus::Popup(Popup::AddNewKeyToAgent(_)) => {
+                    let add_to_agent_state = self
+                        .sections_states
+                        .get_agent_state_mut()
+                        .get_add_to_agent_state_mut();
+                    if let AddToAgentFocus::Confirm = add_to_agent_state.get_focus() {
+                        add_to_agent_state.toggle_confirm();
+                    }
+                }
+                _ => {}
+            },
+            Message::SelPrevPopupOption => match self.current_focus {
+                Focus::Popup(Popup::AddPubKey) => {
+                    let new_key_state = self
+                        .sections_states
+                        .get_public_keys_list_state_mut()
+                        .get_new_key_state_mut();
+                    match new_key_state.get_focus() {
+                        NewPublicKeyFocus::KeyType => new_key_state.prev_type(),
+                        NewPublicKeyFocus::Backend => new_key_state.toggle_backend(),
+                        _ => {}
+                    }
+                }
+                Focus::Popup(Popup::ExportKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .prev_export_format(),
+                Focus::Popup(Popup::ShowPubKeyContent) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .prev_qr_part(),
+                Focus::Popup(Popup::RotateKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_rotate_key_state_mut()
+                    .prev_option(),
+                Focus::Popup(Popup::SignCertificate) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_sign_certificate_state_mut()
+                    .prev_option(),
+                Focus::Popup(Popup::DeployKey) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_deploy_key_state_mut()
+                    .prev_option(),
+                Focus::Popup(Popup::AllowedSigner) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut()
+                    .get_form_state_mut()
+                    .prev_option(),
+                Focus::Popup(Popup::GitSigning) => self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_git_signing_state_mut()
+                    .toggle_option(),
+                Focus::Popup(Popup::AuthorizedKey) => self
+                    .sections_states
+                    .get_authorized_keys_state_mut()
+                    .get_form_state_mut()
+                    .prev_option(),
+                Focus::Popup(Popup::AddToAgent) | Focus::Popup(Popup::AddNewKeyToAgent(_)) => {
+                    let add_to_agent_state = self
+                        .sections_states
+                        .get_agent_state_mut()
+                        .get_add_to_agent_state_mut();
+                    if let AddToAgentFocus::Confirm = add_to_agent_state.get_focus() {
+                        add_to_agent_state.toggle_confirm();
+                    }
+                }
+                _ => {}
+            },
             Message::WriteChar(ch) => {
                 if let Focus::Popup(ref popup) = self.current_focus {
                     match popup {
@@ -142,6 +425,7 @@ impl Model {
                             match new_key_state.get_focus() {
                                 NewPublicKeyFocus::Name => new_key_state.write_name(ch),
                                 NewPublicKeyFocus::Comment => new_key_state.write_comment(ch),
+                                NewPublicKeyFocus::KeyType | NewPublicKeyFocus::Backend => {}
                             }
                         }
                         Popup::PromptPassphrase => {
@@ -160,6 +444,81 @@ impl Model {
 
                             new_key_state.write_passphrase_check(ch);
                         }
+                        Popup::PromptOldPassphrase => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_new_key_state_mut()
+                            .write_old_passphrase(ch),
+                        Popup::EditKeyComment => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .write_edited_comment(ch),
+                        Popup::AddCertAuthority => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .get_new_cert_authority_state_mut()
+                            .write_char(ch),
+                        Popup::ImportKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_import_key_state_mut()
+                            .write_char(ch),
+                        Popup::RotateKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rotate_key_state_mut()
+                            .write_char(ch),
+                        Popup::RenameKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rename_key_state_mut()
+                            .write_char(ch),
+                        Popup::SignCertificate => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_certificate_state_mut()
+                            .write_char(ch),
+                        Popup::SignFile => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_file_state_mut()
+                            .write_char(ch),
+                        Popup::VerifySignature => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_verify_signature_state_mut()
+                            .write_char(ch),
+                        Popup::DeployKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .write_char(ch),
+                        Popup::AllowedSigner => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .get_form_state_mut()
+                            .write_char(ch),
+                        Popup::GitSigning => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_git_signing_state_mut()
+                            .write_char(ch),
+                        Popup::PromptRemoteSecret(_) => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .write_remote_secret(ch),
+                        Popup::AuthorizedKey => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .get_form_state_mut()
+                            .write_char(ch),
+                        Popup::AddToAgent | Popup::AddNewKeyToAgent(_) => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .get_add_to_agent_state_mut()
+                            .write_char(ch),
                         Popup::ExitPrompt => {}
                         _ => {}
                     }
@@ -177,6 +536,7 @@ impl Model {
                             match new_key_state.get_focus() {
                                 NewPublicKeyFocus::Name => new_key_state.del_name_char(),
                                 NewPublicKeyFocus::Comment => new_key_state.del_comment_char(),
+                                NewPublicKeyFocus::KeyType | NewPublicKeyFocus::Backend => {}
                             }
                         }
                         Popup::PromptPassphrase => {
@@ -195,6 +555,81 @@ impl Model {
 
                             new_key_state.del_passphrase_check_char();
                         }
+                        Popup::PromptOldPassphrase => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_new_key_state_mut()
+                            .del_old_passphrase_char(),
+                        Popup::EditKeyComment => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .del_edited_comment_char(),
+                        Popup::AddCertAuthority => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .get_new_cert_authority_state_mut()
+                            .del_char(),
+                        Popup::ImportKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_import_key_state_mut()
+                            .del_char(),
+                        Popup::RotateKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rotate_key_state_mut()
+                            .del_char(),
+                        Popup::RenameKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rename_key_state_mut()
+                            .del_char(),
+                        Popup::SignCertificate => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_certificate_state_mut()
+                            .del_char(),
+                        Popup::SignFile => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_file_state_mut()
+                            .del_char(),
+                        Popup::VerifySignature => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_verify_signature_state_mut()
+                            .del_char(),
+                        Popup::DeployKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .del_char(),
+                        Popup::AllowedSigner => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .get_form_state_mut()
+                            .del_char(),
+                        Popup::GitSigning => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_git_signing_state_mut()
+                            .del_char(),
+                        Popup::PromptRemoteSecret(_) => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .del_remote_secret_char(),
+                        Popup::AuthorizedKey => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .get_form_state_mut()
+                            .del_char(),
+                        Popup::AddToAgent | Popup::AddNewKeyToAgent(_) => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .get_add_to_agent_state_mut()
+                            .del_char(),
                         Popup::ExitPrompt => {}
                         _ => {}
                     }
@@ -212,6 +647,7 @@ impl Model {
                             match new_key_state.get_focus() {
                                 NewPublicKeyFocus::Name => new_key_state.del_name_word(),
                                 NewPublicKeyFocus::Comment => new_key_state.del_comment_word(),
+                                NewPublicKeyFocus::KeyType | NewPublicKeyFocus::Backend => {}
                             }
                         }
                         Popup::PromptPassphrase => {
@@ -230,21 +666,124 @@ impl Model {
 
                             new_key_state.del_passphrase_check();
                         }
+                        Popup::PromptOldPassphrase => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_new_key_state_mut()
+                            .del_old_passphrase(),
+                        Popup::EditKeyComment => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .del_edited_comment_word(),
+                        Popup::AddCertAuthority => self
+                            .sections_states
+                            .get_known_hosts_list_state_mut()
+                            .get_new_cert_authority_state_mut()
+                            .del_word(),
+                        Popup::ImportKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_import_key_state_mut()
+                            .del_word(),
+                        Popup::RotateKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rotate_key_state_mut()
+                            .del_word(),
+                        Popup::RenameKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_rename_key_state_mut()
+                            .del_word(),
+                        Popup::SignCertificate => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_certificate_state_mut()
+                            .del_word(),
+                        Popup::SignFile => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_sign_file_state_mut()
+                            .del_word(),
+                        Popup::VerifySignature => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_verify_signature_state_mut()
+                            .del_word(),
+                        Popup::DeployKey => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .del_word(),
+                        Popup::AllowedSigner => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_allowed_signers_state_mut()
+                            .get_form_state_mut()
+                            .del_word(),
+                        Popup::GitSigning => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_git_signing_state_mut()
+                            .del_word(),
+                        Popup::PromptRemoteSecret(_) => self
+                            .sections_states
+                            .get_public_keys_list_state_mut()
+                            .get_deploy_key_state_mut()
+                            .clean_remote_secret(),
+                        Popup::AuthorizedKey => self
+                            .sections_states
+                            .get_authorized_keys_state_mut()
+                            .get_form_state_mut()
+                            .del_word(),
+                        Popup::AddToAgent | Popup::AddNewKeyToAgent(_) => self
+                            .sections_states
+                            .get_agent_state_mut()
+                            .get_add_to_agent_state_mut()
+                            .del_word(),
                         Popup::ExitPrompt => {}
                         _ => {}
                     }
                 }
             }
-            Message::CmdSpawned(cmd_task) => match cmd_task {
-                CmdTask::SshKeygen => {
-                    self.current_commands.push(cmd_task);
-                    self.current_popup = Some(Popup::WaitingCmd);
+            Message::CmdSpawned(running_cmd) => {
+                if !self.is_command_running(running_cmd.task) {
+                    self.current_commands.push(running_cmd);
                 }
-            },
-            Message::CmdFinished => {
-                self.current_commands.clear();
-                self.set_popup(None);
+                self.current_popup = Some(Popup::WaitingCmd);
+                self.popup_command = Some(running_cmd.task);
+            }
+            Message::CmdPrompted(cmd_task) if self.is_command_running(cmd_task) => {
+                self.popup_command = Some(cmd_task);
             }
+            Message::CmdPrompted(_) => {}
+            Message::CmdFinished(cmd_task) => {
+                // Commands running in the background leave the current popup alone
+                let was_current = self.popup_command == Some(cmd_task);
+                if was_current {
+                    self.popup_command = None;
+                }
+                self.current_commands.retain(|cmd| cmd.task != cmd_task);
+                // The outcome of the command (or the offer to add the new key to the agent) outlives it
+                if was_current
+                    && !matches!(
+                        self.current_popup,
+                        Some(Popup::AddNewKeyToAgent(_)) | Some(Popup::WithCfg(_, _))
+                    )
+                {
+                    self.set_popup(None);
+                }
+            }
+            // A command killed by the user is no longer current, and its failure is expected
+            Message::CmdFailed(cmd_task, error_str) if self.is_command_running(cmd_task) => {
+                self.current_commands.retain(|cmd| cmd.task != cmd_task);
+                if self.popup_command == Some(cmd_task) {
+                    self.popup_command = None;
+                }
+                self.current_error = Some(error_str);
+                self.set_popup(Some(Popup::ErrorMsg));
+            }
+            Message::CmdFailed(_, _) => {}
             Message::PrintSuccess(success_str) => {
                 self.set_popup(Some(Popup::WithCfg(success_str, ColorVariant::Success)));
             }
@@ -260,6 +799,132 @@ impl Model {
                 )));
             }
             Message::PromptNewKeyPassphrase => self.set_popup(Some(Popup::PromptPassphrase)),
+            Message::StartKeyCommentEdit => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_comment_edit();
+                self.set_popup(Some(Popup::EditKeyComment));
+            }
+            Message::StartKeyRotation => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_key_rotation();
+                self.set_popup(Some(Popup::RotateKey));
+            }
+            Message::StartKeyRename => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_key_rename();
+                self.set_popup(Some(Popup::RenameKey));
+            }
+            Message::StartCertificateSigning => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_certificate_signing();
+                self.set_popup(Some(Popup::SignCertificate));
+            }
+            Message::StartKeyDeploy => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_key_deploy();
+                self.set_popup(Some(Popup::DeployKey));
+            }
+            Message::StartFileSigning => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_file_signing();
+                self.set_popup(Some(Popup::SignFile));
+            }
+            Message::StartSignatureVerification => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_signature_verification();
+                self.set_popup(Some(Popup::VerifySignature));
+            }
+            Message::AuditKeys => {
+                let keys_state = self.sections_states.get_public_keys_list_state_mut();
+                // The keys (and the policy) could have changed since they were last loaded
+                keys_state.load_public_keys();
+                if keys_state.get_items().iter().all(|key| key.issues.is_empty()) {
+                    self.set_popup(Some(Popup::WithCfg(
+                        String::from("No weak or legacy keys found"),
+                        ColorVariant::Success,
+                    )));
+                } else {
+                    self.set_popup(Some(Popup::KeyAudit));
+                }
+            }
+            Message::ToggleQrCode => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .toggle_qr_code();
+            }
+            Message::RefreshTrash => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_trash_state_mut()
+                    .load_items();
+            }
+            Message::RefreshAllowedSigners => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut()
+                    .load_entries();
+            }
+            Message::StartAllowedSignerAdd => {
+                // Certificates are verified through their CA, which is allowed on its own
+                let local_keys = self
+                    .sections_states
+                    .get_public_keys_list_state()
+                    .get_items()
+                    .iter()
+                    .filter(|item| !item.public_key_missing && item.certificate.is_none())
+                    .map(|item| item.file_name.clone())
+                    .collect();
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut()
+                    .get_form_state_mut()
+                    .start_adding(local_keys);
+                self.set_popup(Some(Popup::AllowedSigner));
+            }
+            Message::StartAllowedSignerEdit => {
+                let allowed_signers_state = self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut();
+                if let Some(entry) = allowed_signers_state.get_selected_entry().cloned() {
+                    allowed_signers_state
+                        .get_form_state_mut()
+                        .start_editing(&entry);
+                    self.set_popup(Some(Popup::AllowedSigner));
+                }
+            }
+            Message::CleanAllowedSignerInput => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_allowed_signers_state_mut()
+                    .get_form_state_mut()
+                    .clean();
+            }
+            Message::StartGitSigning => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_git_signing();
+                self.set_popup(Some(Popup::GitSigning));
+            }
+            Message::CleanRemoteSecret => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_deploy_key_state_mut()
+                    .clean_remote_secret();
+            }
+            Message::SetPassphrasePurpose(purpose) => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_new_key_state_mut()
+                    .set_passphrase_purpose(purpose);
+            }
             Message::PromptReenterNewKeyPassPhrase => {
                 self.set_popup(Some(Popup::PromptReenterPassphrase))
             }
@@ -269,6 +934,26 @@ impl Model {
                     .get_new_key_state_mut()
                     .clean_passphrases();
             }
+            Message::HoldNewKeyForAgent => {
+                let new_key_state = self
+                    .sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_new_key_state_mut();
+                let passphrase = new_key_state.get_passphrase().clone();
+                let private_key_path = files::get_user_ssh_dir()
+                    .unwrap_or_default()
+                    .join(new_key_state.get_name());
+                new_key_state.clean_passphrases();
+
+                self.sections_states
+                    .get_agent_state_mut()
+                    .hold_pending_key(private_key_path, passphrase);
+            }
+            Message::CleanPendingAgentKey => {
+                self.sections_states
+                    .get_agent_state_mut()
+                    .clean_pending_key();
+            }
             Message::RefreshPublicKeysList => {
                 self.sections_states
                     .get_public_keys_list_state_mut()
@@ -283,8 +968,79 @@ impl Model {
                     .load_known_hosts();
             }
             Message::PromptDeleteKeyPairConfirmation => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .start_key_deletion();
                 self.set_popup(Some(Popup::PromptDeleteKeyPairConfirmation));
             }
+            Message::StartHostKeyRevocation => {
+                self.sections_states
+                    .get_known_hosts_list_state_mut()
+                    .start_host_key_revocation();
+                self.set_popup(Some(Popup::RevokeHostKey));
+            }
+            Message::CleanNewCertAuthorityInput => {
+                self.sections_states
+                    .get_known_hosts_list_state_mut()
+                    .get_new_cert_authority_state_mut()
+                    .clean();
+            }
+            Message::CleanImportKeyInput => {
+                self.sections_states
+                    .get_public_keys_list_state_mut()
+                    .get_import_key_state_mut()
+                    .clean();
+            }
+            Message::CleanAddToAgentInput => {
+                self.sections_states
+                    .get_agent_state_mut()
+                    .get_add_to_agent_state_mut()
+                    .clean();
+            }
+            Message::RefreshAgentIdentities => {
+                self.sections_states
+                    .get_agent_state_mut()
+                    .load_identities();
+            }
+            Message::RefreshAuthorizedKeys => {
+                self.sections_states
+                    .get_authorized_keys_state_mut()
+                    .load_entries();
+            }
+            Message::StartAuthorizedKeyAdd => {
+                // Certificates can't be authorized by themselves, only through their CA
+                let local_keys = self
+                    .sections_states
+                    .get_public_keys_list_state()
+                    .get_items()
+                    .iter()
+                    .filter(|item| !item.public_key_missing && item.certificate.is_none())
+                    .map(|item| item.file_name.clone())
+                    .collect();
+                self.sections_states
+                    .get_authorized_keys_state_mut()
+                    .get_form_state_mut()
+                    .start_adding(local_keys);
+                self.set_popup(Some(Popup::AuthorizedKey));
+            }
+            Message::StartAuthorizedKeyEdit => {
+                let authorized_keys_state = self.sections_states.get_authorized_keys_state_mut();
+                if let Some(entry) = authorized_keys_state.get_selected_entry().cloned() {
+                    authorized_keys_state
+                        .get_form_state_mut()
+                        .start_editing(&entry);
+                    self.set_popup(Some(Popup::AuthorizedKey));
+                }
+            }
+            Message::CleanAuthorizedKeyInput => {
+                self.sections_states
+                    .get_authorized_keys_state_mut()
+                    .get_form_state_mut()
+                    .clean();
+            }
+            Message::ReloadClientConfig => {
+                self.get_client_config_state_mut().reload_content();
+            }
             Message::TextAreaInteract => {
                 let ccstate = self.get_client_config_state_mut();
                 ccstate.set_vim_mode(VimMode::Normal);
@@ -328,12 +1084,23 @@ impl Model {
         &self.sections_states
     }
 
-    pub fn get_current_command(&self) -> Option<commands::CmdTask> {
-        if let Some(cmd_task) = self.current_commands.first() {
-            return Some(*cmd_task);
-        }
+    /// Returns the running command the current popup belongs to, if any
+    pub fn get_popup_command(&self) -> Option<commands::RunningCmd> {
+        self.popup_command.and_then(|cmd_task| {
+            self.current_commands
+                .iter()
+                .find(|cmd| cmd.task == cmd_task)
+                .copied()
+        })
+    }
+
+    pub fn is_popup_of_command(&self, cmd_task: commands::CmdTask) -> bool {
+        self.get_popup_command()
+            .is_some_and(|cmd| cmd.task == cmd_task)
+    }
 
-        None
+    pub fn is_command_running(&self, cmd_task: commands::CmdTask) -> bool {
+        self.current_commands.iter().any(|cmd| cmd.task == cmd_task)
     }
 
     pub fn get_fatal_error(&self) -> Option<String> {
@@ -361,9 +1128,51 @@ impl Model {
             _ => {}
         }
 
+        // The passphrase of a new key pair is only kept while it can still be added to the agent
+        if !matches!(
+            new_popup,
+            Some(Popup::Loading(_)) | Some(Popup::AddNewKeyToAgent(_))
+        ) {
+            self.sections_states
+                .get_agent_state_mut()
+                .clean_pending_key();
+        }
+
+        // Passphrases are only kept while they're prompted for or used by a job in the background,
+        // so they're forgotten on every way out of the prompts (cancel, error or end of ssh-keygen)
+        if !matches!(
+            new_popup,
+            Some(Popup::PromptPassphrase)
+                | Some(Popup::PromptReenterPassphrase)
+                | Some(Popup::PromptOldPassphrase)
+                | Some(Popup::Loading(_))
+        ) {
+            self.sections_states
+                .get_public_keys_list_state_mut()
+                .get_new_key_state_mut()
+                .clean_passphrases();
+        }
+
+        // The answer to a password prompt of ssh is never kept once the prompt is gone
+        if !matches!(new_popup, Some(Popup::PromptRemoteSecret(_))) {
+            self.sections_states
+                .get_public_keys_list_state_mut()
+                .get_deploy_key_state_mut()
+                .clean_remote_secret();
+        }
+
+        // The QR codes are only kept while the content of the key is shown
+        if new_popup != Some(Popup::ShowPubKeyContent) {
+            self.sections_states
+                .get_public_keys_list_state_mut()
+                .hide_qr_code();
+        }
+
         if let Some(ref popup) = new_popup {
             self.current_focus = Focus::Popup(popup.clone());
         } else {
+            // A hidden popup leaves its command running in the background
+            self.popup_command = None;
             self.current_focus = Focus::Section(self.current_section);
         }
         self.current_popup = new_popup;
@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use ssh_key::PublicKey;
+
+use crate::{
+    agent::{AgentClient, AgentConstraints},
+    utils::{data_parsing, secret::SecretString, strings},
+};
+
+type ListItems = Vec<PublicKey>;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum AddToAgentFocus {
+    #[default]
+    Lifetime,
+    Confirm,
+}
+
+/// State of the form used to add the selected key pair to the agent
+#[derive(Clone, Default)]
+pub struct AddToAgentState {
+    /// Time interval after which the agent forgets the key. Empty for no limit
+    lifetime: String,
+    confirm: bool,
+    current_focus: AddToAgentFocus,
+}
+
+impl AddToAgentState {
+    pub fn get_lifetime(&self) -> &str {
+        self.lifetime.as_str()
+    }
+
+    pub fn get_confirm(&self) -> bool {
+        self.confirm
+    }
+
+    pub fn toggle_confirm(&mut self) {
+        self.confirm = !self.confirm;
+    }
+
+    pub fn get_focus(&self) -> AddToAgentFocus {
+        self.current_focus
+    }
+
+    pub fn has_focus_on(&self, possible_focus: AddToAgentFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        match self.current_focus {
+            AddToAgentFocus::Lifetime => self.current_focus = AddToAgentFocus::Confirm,
+            AddToAgentFocus::Confirm => self.current_focus = AddToAgentFocus::Lifetime,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        if let AddToAgentFocus::Lifetime = self.current_focus {
+            self.lifetime.push(ch);
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        if let AddToAgentFocus::Lifetime = self.current_focus {
+            self.lifetime.pop();
+        }
+    }
+
+    pub fn del_word(&mut self) {
+        if let AddToAgentFocus::Lifetime = self.current_focus {
+            self.lifetime = strings::del_last_word(self.lifetime.to_owned());
+        }
+    }
+
+    /// Returns the constraints set in the form, or an error if the lifetime is not a valid time
+    /// interval
+    pub fn get_constraints(&self) -> Result<AgentConstraints, String> {
+        let lifetime = if self.lifetime.trim().is_empty() {
+            None
+        } else {
+            Some(data_parsing::parse_time_interval(&self.lifetime).map_err(|e| e.to_string())?)
+        };
+
+        Ok(AgentConstraints {
+            lifetime,
+            confirm: self.confirm,
+        })
+    }
+
+    pub fn clean(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Key pair generated by the app whose passphrase is kept until the user decides if it's added to
+/// the agent, so it doesn't have to be typed again
+pub struct PendingAgentKey {
+    pub private_key_path: PathBuf,
+    pub passphrase: SecretString,
+}
+
+pub struct AgentState {
+    /// Identities loaded in the agent
+    items: ListItems,
+    selected_item_idx: Option<usize>,
+    has_focus: bool,
+    /// Why the identities couldn't be listed (e.g. there's no agent running)
+    error: Option<String>,
+    add_to_agent_state: AddToAgentState,
+    pending_key: Option<PendingAgentKey>,
+}
+
+impl AgentState {
+    pub fn load_identities(&mut self) {
+        match AgentClient::connect().and_then(|mut agent| agent.list_identities()) {
+            Ok(identities) => {
+                self.items = identities;
+                self.error = None;
+            }
+            Err(err) => {
+                self.items = vec![];
+                self.error = Some(err.to_string());
+            }
+        }
+
+        match self.selected_item_idx {
+            Some(idx) if idx >= self.items.len() => {
+                self.selected_item_idx = self.items.len().checked_sub(1);
+            }
+            None if self.has_focus && !self.items.is_empty() => self.selected_item_idx = Some(0),
+            _ => {}
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.has_focus = true;
+        if !self.items.is_empty() {
+            self.selected_item_idx = Some(0);
+        }
+    }
+
+    pub fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.selected_item_idx = None;
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.has_focus
+    }
+
+    pub fn get_items(&self) -> &[PublicKey] {
+        &self.items
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn get_selected_item_idx(&self) -> Option<usize> {
+        self.selected_item_idx
+    }
+
+    pub fn get_selected_identity(&self) -> Option<&PublicKey> {
+        self.selected_item_idx.and_then(|idx| self.items.get(idx))
+    }
+
+    pub fn next_item(&mut self) {
+        match self.selected_item_idx {
+            None => {
+                if !self.items.is_empty() {
+                    self.selected_item_idx = Some(0)
+                }
+            }
+            Some(idx) => {
+                if idx < self.items.len() - 1 {
+                    self.selected_item_idx = Some(idx + 1)
+                }
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        match self.selected_item_idx {
+            None => {
+                if !self.items.is_empty() {
+                    self.selected_item_idx = Some(0)
+                }
+            }
+            Some(idx) => {
+                if idx > 0 {
+                    self.selected_item_idx = Some(idx - 1)
+                }
+            }
+        }
+    }
+
+    pub fn get_add_to_agent_state(&self) -> &AddToAgentState {
+        &self.add_to_agent_state
+    }
+
+    pub fn get_add_to_agent_state_mut(&mut self) -> &mut AddToAgentState {
+        &mut self.add_to_agent_state
+    }
+
+    pub fn hold_pending_key(&mut self, private_key_path: PathBuf, passphrase: SecretString) {
+        self.pending_key = Some(PendingAgentKey {
+            private_key_path,
+            passphrase,
+        });
+    }
+
+    pub fn get_pending_key(&self) -> Option<&PendingAgentKey> {
+        self.pending_key.as_ref()
+    }
+
+    pub fn clean_pending_key(&mut self) {
+        self.pending_key = None;
+    }
+}
+
+impl Default for AgentState {
+    fn default() -> Self {
+        let mut state = Self {
+            items: vec![],
+            selected_item_idx: None,
+            has_focus: false,
+            error: None,
+            add_to_agent_state: AddToAgentState::default(),
+            pending_key: None,
+        };
+
+        state.load_identities();
+
+        state
+    }
+}
+
+impl Serialize for AgentState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str("Serializer not implemented")
+    }
+}
@@ -0,0 +1,444 @@
+use std::path::PathBuf;
+
+use crate::utils::{
+    allowed_signers::{self, AllowedSignersEntry},
+    authorized_keys::{AuthorizedKeysEntry, KeyOption},
+    files, strings,
+};
+
+/// Options of an allowed_signers entry that are edited through their own inputs of the form
+const FORM_OPTIONS: [&str; 3] = ["namespaces", "valid-after", "valid-before"];
+
+/// Namespace of the signatures made by git, the one the keys used for git signing are limited to
+pub const GIT_NAMESPACE: &str = "git";
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum AllowedSignerFocus {
+    #[default]
+    Principals,
+    Key,
+    Namespaces,
+    ValidAfter,
+    ValidBefore,
+}
+
+/// State of the form used to allow a new signer, or to edit an allowed one
+#[derive(Clone, Default)]
+pub struct AllowedSignerFormState {
+    /// Entry being edited. None when a new signer is being allowed
+    editing: Option<AllowedSignersEntry>,
+    /// Comma separated list of principal patterns
+    principals: String,
+    /// Public key of the signer
+    key: String,
+    /// Names of the public keys of the ssh directory, which can be picked instead of pasting a key
+    local_keys: Vec<String>,
+    local_key_idx: Option<usize>,
+    /// Comma separated list of namespace patterns. Empty to accept any namespace
+    namespaces: String,
+    valid_after: String,
+    valid_before: String,
+    current_focus: AllowedSignerFocus,
+}
+
+impl AllowedSignerFormState {
+    pub fn start_adding(&mut self, local_keys: Vec<String>) {
+        *self = Self {
+            local_keys,
+            ..Self::default()
+        };
+    }
+
+    pub fn start_editing(&mut self, entry: &AllowedSignersEntry) {
+        let option = |name| entry.get_option(name).unwrap_or_default().to_owned();
+
+        *self = Self {
+            principals: entry.principals.clone(),
+            key: format!("{} {}", entry.key_type, entry.key),
+            namespaces: option("namespaces"),
+            valid_after: option("valid-after"),
+            valid_before: option("valid-before"),
+            editing: Some(entry.to_owned()),
+            ..Self::default()
+        };
+    }
+
+    pub fn get_editing(&self) -> Option<&AllowedSignersEntry> {
+        self.editing.as_ref()
+    }
+
+    pub fn get_principals(&self) -> &str {
+        self.principals.as_str()
+    }
+
+    pub fn get_key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    pub fn get_local_key_name(&self) -> Option<&str> {
+        self.local_key_idx
+            .and_then(|idx| self.local_keys.get(idx))
+            .map(String::as_str)
+    }
+
+    pub fn get_namespaces(&self) -> &str {
+        self.namespaces.as_str()
+    }
+
+    pub fn get_valid_after(&self) -> &str {
+        self.valid_after.as_str()
+    }
+
+    pub fn get_valid_before(&self) -> &str {
+        self.valid_before.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: AllowedSignerFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    /// The key of an entry that is being edited can't change, so it's skipped
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            AllowedSignerFocus::Principals if self.editing.is_some() => {
+                AllowedSignerFocus::Namespaces
+            }
+            AllowedSignerFocus::Principals => AllowedSignerFocus::Key,
+            AllowedSignerFocus::Key => AllowedSignerFocus::Namespaces,
+            AllowedSignerFocus::Namespaces => AllowedSignerFocus::ValidAfter,
+            AllowedSignerFocus::ValidAfter => AllowedSignerFocus::ValidBefore,
+            AllowedSignerFocus::ValidBefore => AllowedSignerFocus::Principals,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            AllowedSignerFocus::Principals => AllowedSignerFocus::ValidBefore,
+            AllowedSignerFocus::Key => AllowedSignerFocus::Principals,
+            AllowedSignerFocus::Namespaces if self.editing.is_some() => {
+                AllowedSignerFocus::Principals
+            }
+            AllowedSignerFocus::Namespaces => AllowedSignerFocus::Key,
+            AllowedSignerFocus::ValidAfter => AllowedSignerFocus::Namespaces,
+            AllowedSignerFocus::ValidBefore => AllowedSignerFocus::ValidAfter,
+        }
+    }
+
+    /// Fills the key with the content of the local public key at the given index
+    fn pick_local_key(&mut self, idx: usize) {
+        self.local_key_idx = Some(idx);
+        self.key = files::get_pub_key_content(&self.local_keys[idx])
+            .map(|content| content.trim().to_owned())
+            .unwrap_or_default();
+    }
+
+    pub fn next_option(&mut self) {
+        if self.current_focus != AllowedSignerFocus::Key || self.local_keys.is_empty() {
+            return;
+        }
+
+        let idx = match self.local_key_idx {
+            Some(idx) => (idx + 1) % self.local_keys.len(),
+            None => 0,
+        };
+        self.pick_local_key(idx);
+    }
+
+    pub fn prev_option(&mut self) {
+        if self.current_focus != AllowedSignerFocus::Key || self.local_keys.is_empty() {
+            return;
+        }
+
+        let idx = match self.local_key_idx {
+            Some(idx) => (idx + self.local_keys.len() - 1) % self.local_keys.len(),
+            None => self.local_keys.len() - 1,
+        };
+        self.pick_local_key(idx);
+    }
+
+    fn focused_input(&mut self) -> &mut String {
+        match self.current_focus {
+            AllowedSignerFocus::Principals => &mut self.principals,
+            AllowedSignerFocus::Key => {
+                // Once typed on, the key no longer is the picked local key
+                self.local_key_idx = None;
+                &mut self.key
+            }
+            AllowedSignerFocus::Namespaces => &mut self.namespaces,
+            AllowedSignerFocus::ValidAfter => &mut self.valid_after,
+            AllowedSignerFocus::ValidBefore => &mut self.valid_before,
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        self.focused_input().push(ch);
+    }
+
+    pub fn del_char(&mut self) {
+        self.focused_input().pop();
+    }
+
+    pub fn del_word(&mut self) {
+        let input = self.focused_input();
+        *input = strings::del_last_word(input.to_owned());
+    }
+
+    /// Builds the entry out of the form, or returns why the form is invalid. The options that
+    /// don't have an input of their own (e.g. `cert-authority`) are kept as they are
+    pub fn get_entry(&self) -> Result<AllowedSignersEntry, String> {
+        let principals = self.principals.trim();
+        if principals.is_empty() {
+            return Err(String::from("the principals can't be empty"));
+        }
+        if principals.contains('"') {
+            return Err(String::from("the principals can't contain quotes"));
+        }
+
+        let mut entry = match &self.editing {
+            Some(entry) => entry.to_owned(),
+            None => {
+                let key = AuthorizedKeysEntry::parse(&self.key)
+                    .ok_or_else(|| String::from("the key is not a valid public key"))?;
+                AllowedSignersEntry {
+                    principals: String::new(),
+                    options: vec![],
+                    key_type: key.key_type,
+                    key: key.key,
+                    comment: key.comment,
+                }
+            }
+        };
+        entry.principals = principals.to_owned();
+        entry.options.retain(|option| {
+            !FORM_OPTIONS
+                .iter()
+                .any(|name| option.name.eq_ignore_ascii_case(name))
+        });
+
+        for (name, value) in [
+            ("valid-after", self.valid_after.trim()),
+            ("valid-before", self.valid_before.trim()),
+        ] {
+            if !value.is_empty() && !allowed_signers::is_valid_option_time(value) {
+                return Err(format!(
+                    "invalid {} '{}'. The format is YYYYMMDD[HHMM[SS]], in UTC",
+                    name, value
+                ));
+            }
+        }
+
+        for (name, value) in [
+            ("namespaces", self.namespaces.trim()),
+            ("valid-after", self.valid_after.trim()),
+            ("valid-before", self.valid_before.trim()),
+        ] {
+            if !value.is_empty() {
+                entry.options.push(KeyOption {
+                    name: name.to_owned(),
+                    value: Some(value.to_owned()),
+                });
+            }
+        }
+
+        Ok(entry)
+    }
+
+    pub fn clean(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// State of the popup that manages the allowed_signers file, used to verify the signatures made
+/// with SSH keys
+#[derive(Default)]
+pub struct AllowedSignersState {
+    items: Vec<AllowedSignersEntry>,
+    selected_item_idx: Option<usize>,
+    path: PathBuf,
+    /// Why the allowed_signers file couldn't be read
+    error: Option<String>,
+    form_state: AllowedSignerFormState,
+}
+
+impl AllowedSignersState {
+    pub fn load_entries(&mut self) {
+        self.path = files::get_allowed_signers_path().unwrap_or_default();
+        match files::get_allowed_signers_entries() {
+            Ok(entries) => {
+                self.items = entries;
+                self.error = None;
+            }
+            Err(err) => {
+                self.items = vec![];
+                self.error = Some(err.to_string());
+            }
+        }
+
+        self.selected_item_idx = match self.selected_item_idx {
+            _ if self.items.is_empty() => None,
+            Some(idx) if idx >= self.items.len() => Some(self.items.len() - 1),
+            Some(idx) => Some(idx),
+            None => Some(0),
+        };
+    }
+
+    pub fn get_items(&self) -> &[AllowedSignersEntry] {
+        &self.items
+    }
+
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn get_selected_item_idx(&self) -> Option<usize> {
+        self.selected_item_idx
+    }
+
+    pub fn get_selected_entry(&self) -> Option<&AllowedSignersEntry> {
+        self.selected_item_idx.and_then(|idx| self.items.get(idx))
+    }
+
+    pub fn next_item(&mut self) {
+        if let Some(idx) = self.selected_item_idx {
+            if idx + 1 < self.items.len() {
+                self.selected_item_idx = Some(idx + 1);
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        if let Some(idx) = self.selected_item_idx {
+            if idx > 0 {
+                self.selected_item_idx = Some(idx - 1);
+            }
+        }
+    }
+
+    pub fn get_form_state(&self) -> &AllowedSignerFormState {
+        &self.form_state
+    }
+
+    pub fn get_form_state_mut(&mut self) -> &mut AllowedSignerFormState {
+        &mut self.form_state
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum GitSigningFocus {
+    #[default]
+    Principal,
+    AddSigner,
+}
+
+/// Everything needed to make git sign with a key
+#[derive(Clone, Debug)]
+pub struct GitSigningRequest {
+    pub public_key_path: PathBuf,
+    /// Principal the key is allowed to sign as, if it has to be added to the allowed_signers file
+    pub signer_principal: Option<String>,
+}
+
+/// State of the form used to make git sign with the selected key
+#[derive(Clone, Default)]
+pub struct GitSigningState {
+    public_key_name: String,
+    /// Identity the signatures are made as, usually the e-mail address of the commits
+    principal: String,
+    /// Whether the key is added to the allowed_signers file, so git can verify its own signatures
+    add_signer: bool,
+    current_focus: GitSigningFocus,
+}
+
+impl GitSigningState {
+    pub fn start(&mut self, public_key_name: &str) {
+        *self = Self {
+            public_key_name: public_key_name.to_owned(),
+            principal: files::get_git_user_email()
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            add_signer: true,
+            ..Self::default()
+        };
+    }
+
+    pub fn get_public_key_name(&self) -> &str {
+        self.public_key_name.as_str()
+    }
+
+    pub fn get_principal(&self) -> &str {
+        self.principal.as_str()
+    }
+
+    pub fn get_add_signer(&self) -> bool {
+        self.add_signer
+    }
+
+    pub fn has_focus_on(&self, possible_focus: GitSigningFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            GitSigningFocus::Principal => GitSigningFocus::AddSigner,
+            GitSigningFocus::AddSigner => GitSigningFocus::Principal,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    pub fn toggle_option(&mut self) {
+        if self.current_focus == GitSigningFocus::AddSigner {
+            self.add_signer = !self.add_signer;
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        if self.current_focus == GitSigningFocus::Principal {
+            self.principal.push(ch);
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        if self.current_focus == GitSigningFocus::Principal {
+            self.principal.pop();
+        }
+    }
+
+    pub fn del_word(&mut self) {
+        if self.current_focus == GitSigningFocus::Principal {
+            self.principal = strings::del_last_word(self.principal.to_owned());
+        }
+    }
+
+    /// Builds the request out of the form, or returns why the form is invalid
+    pub fn get_request(&self) -> Result<GitSigningRequest, String> {
+        let principal = self.principal.trim();
+        if self.add_signer && principal.is_empty() {
+            return Err(String::from(
+                "the principal can't be empty if the key is added to the allowed signers",
+            ));
+        }
+        if principal.contains([',', '"']) || principal.contains(char::is_whitespace) {
+            return Err(String::from(
+                "the principal can't contain commas, quotes nor spaces",
+            ));
+        }
+
+        let ssh_dir = files::get_user_ssh_dir().map_err(|e| e.to_string())?;
+        Ok(GitSigningRequest {
+            public_key_path: ssh_dir.join(&self.public_key_name),
+            signer_principal: if self.add_signer {
+                Some(principal.to_owned())
+            } else {
+                None
+            },
+        })
+    }
+}
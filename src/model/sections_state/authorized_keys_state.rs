@@ -0,0 +1,300 @@
+use serde::Serialize;
+
+use crate::utils::{authorized_keys, authorized_keys::AuthorizedKeysEntry, files, strings};
+
+type ListItems = Vec<AuthorizedKeysEntry>;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum AuthorizedKeyFocus {
+    #[default]
+    Key,
+    Options,
+}
+
+/// State of the form used to authorize a new key, or to edit the options of an authorized one
+#[derive(Clone, Default)]
+pub struct AuthorizedKeyFormState {
+    /// Entry whose options are being edited. None when a new key is being authorized
+    editing: Option<AuthorizedKeysEntry>,
+    /// Public key (or whole authorized_keys line) to authorize
+    key: String,
+    /// Names of the public keys of the ssh directory, which can be picked instead of pasting a key
+    local_keys: Vec<String>,
+    local_key_idx: Option<usize>,
+    /// Comma separated options, as written in the authorized_keys file
+    options: String,
+    current_focus: AuthorizedKeyFocus,
+}
+
+impl AuthorizedKeyFormState {
+    pub fn start_adding(&mut self, local_keys: Vec<String>) {
+        *self = Self {
+            local_keys,
+            ..Self::default()
+        };
+    }
+
+    pub fn start_editing(&mut self, entry: &AuthorizedKeysEntry) {
+        *self = Self {
+            key: format!("{} {}", entry.key_type, entry.key),
+            options: entry.options_string(),
+            editing: Some(entry.to_owned()),
+            current_focus: AuthorizedKeyFocus::Options,
+            ..Self::default()
+        };
+    }
+
+    pub fn get_editing(&self) -> Option<&AuthorizedKeysEntry> {
+        self.editing.as_ref()
+    }
+
+    pub fn get_key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    pub fn get_local_key_name(&self) -> Option<&str> {
+        self.local_key_idx
+            .and_then(|idx| self.local_keys.get(idx))
+            .map(String::as_str)
+    }
+
+    pub fn get_options(&self) -> &str {
+        self.options.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: AuthorizedKeyFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    /// The key of an entry that is being edited can't change, so the focus stays on the options
+    pub fn next_focus(&mut self) {
+        if self.editing.is_some() {
+            return;
+        }
+
+        self.current_focus = match self.current_focus {
+            AuthorizedKeyFocus::Key => AuthorizedKeyFocus::Options,
+            AuthorizedKeyFocus::Options => AuthorizedKeyFocus::Key,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    /// Fills the key with the content of the local public key at the given index
+    fn pick_local_key(&mut self, idx: usize) {
+        self.local_key_idx = Some(idx);
+        self.key = files::get_pub_key_content(&self.local_keys[idx])
+            .map(|content| content.trim().to_owned())
+            .unwrap_or_default();
+    }
+
+    pub fn next_option(&mut self) {
+        if self.current_focus != AuthorizedKeyFocus::Key
+            || self.editing.is_some()
+            || self.local_keys.is_empty()
+        {
+            return;
+        }
+
+        let idx = match self.local_key_idx {
+            Some(idx) => (idx + 1) % self.local_keys.len(),
+            None => 0,
+        };
+        self.pick_local_key(idx);
+    }
+
+    pub fn prev_option(&mut self) {
+        if self.current_focus != AuthorizedKeyFocus::Key
+            || self.editing.is_some()
+            || self.local_keys.is_empty()
+        {
+            return;
+        }
+
+        let idx = match self.local_key_idx {
+            Some(idx) => (idx + self.local_keys.len() - 1) % self.local_keys.len(),
+            None => self.local_keys.len() - 1,
+        };
+        self.pick_local_key(idx);
+    }
+
+    fn focused_input(&mut self) -> Option<&mut String> {
+        match self.current_focus {
+            AuthorizedKeyFocus::Key if self.editing.is_some() => None,
+            AuthorizedKeyFocus::Key => {
+                // Once typed on, the key no longer is the picked local key
+                self.local_key_idx = None;
+                Some(&mut self.key)
+            }
+            AuthorizedKeyFocus::Options => Some(&mut self.options),
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        if let Some(input) = self.focused_input() {
+            input.push(ch);
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        if let Some(input) = self.focused_input() {
+            input.pop();
+        }
+    }
+
+    pub fn del_word(&mut self) {
+        if let Some(input) = self.focused_input() {
+            *input = strings::del_last_word(input.to_owned());
+        }
+    }
+
+    /// Builds the entry out of the form, or returns why the form is invalid. A pasted
+    /// authorized_keys line keeps its options, unless some are typed in the form
+    pub fn get_entry(&self) -> Result<AuthorizedKeysEntry, String> {
+        let options = authorized_keys::parse_options(&self.options).map_err(|e| e.to_string())?;
+
+        let mut entry = match &self.editing {
+            Some(entry) => entry.to_owned(),
+            None => AuthorizedKeysEntry::parse(&self.key)
+                .ok_or_else(|| String::from("the key is not a valid public key"))?,
+        };
+        if self.editing.is_some() || !options.is_empty() {
+            entry.options = options;
+        }
+
+        Ok(entry)
+    }
+
+    pub fn clean(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub struct AuthorizedKeysState {
+    items: ListItems,
+    selected_item_idx: Option<usize>,
+    has_focus: bool,
+    /// Why the authorized_keys file couldn't be read
+    error: Option<String>,
+    form_state: AuthorizedKeyFormState,
+}
+
+impl AuthorizedKeysState {
+    pub fn load_entries(&mut self) {
+        match files::get_authorized_keys_entries() {
+            Ok(entries) => {
+                self.items = entries;
+                self.error = None;
+            }
+            Err(err) => {
+                self.items = vec![];
+                self.error = Some(err.to_string());
+            }
+        }
+
+        match self.selected_item_idx {
+            Some(idx) if idx >= self.items.len() => {
+                self.selected_item_idx = self.items.len().checked_sub(1);
+            }
+            None if self.has_focus && !self.items.is_empty() => self.selected_item_idx = Some(0),
+            _ => {}
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.has_focus = true;
+        if !self.items.is_empty() {
+            self.selected_item_idx = Some(0);
+        }
+    }
+
+    pub fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.selected_item_idx = None;
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.has_focus
+    }
+
+    pub fn get_items(&self) -> &[AuthorizedKeysEntry] {
+        &self.items
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn get_selected_item_idx(&self) -> Option<usize> {
+        self.selected_item_idx
+    }
+
+    pub fn get_selected_entry(&self) -> Option<&AuthorizedKeysEntry> {
+        self.selected_item_idx.and_then(|idx| self.items.get(idx))
+    }
+
+    pub fn next_item(&mut self) {
+        match self.selected_item_idx {
+            None => {
+                if !self.items.is_empty() {
+                    self.selected_item_idx = Some(0)
+                }
+            }
+            Some(idx) => {
+                if idx < self.items.len() - 1 {
+                    self.selected_item_idx = Some(idx + 1)
+                }
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        match self.selected_item_idx {
+            None => {
+                if !self.items.is_empty() {
+                    self.selected_item_idx = Some(0)
+                }
+            }
+            Some(idx) => {
+                if idx > 0 {
+                    self.selected_item_idx = Some(idx - 1)
+                }
+            }
+        }
+    }
+
+    pub fn get_form_state(&self) -> &AuthorizedKeyFormState {
+        &self.form_state
+    }
+
+    pub fn get_form_state_mut(&mut self) -> &mut AuthorizedKeyFormState {
+        &mut self.form_state
+    }
+}
+
+impl Default for AuthorizedKeysState {
+    fn default() -> Self {
+        let mut state = Self {
+            items: vec![],
+            selected_item_idx: None,
+            has_focus: false,
+            error: None,
+            form_state: AuthorizedKeyFormState::default(),
+        };
+
+        state.load_entries();
+
+        state
+    }
+}
+
+impl Serialize for AuthorizedKeysState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str("Serializer not implemented")
+    }
+}
@@ -17,7 +17,7 @@ pub struct ClientConfigState {
 }
 
 impl ClientConfigState {
-    pub fn get_textarea(&self) -> &TextArea {
+    pub fn get_textarea(&self) -> &TextArea<'_> {
         &self.textarea
     }
 
@@ -112,6 +112,14 @@ impl ClientConfigState {
         files::truncate_client_config_content(content_lines).expect("FAILED TO WRITE");
     }
 
+    /// Reloads the content of the textarea from the config file
+    pub fn reload_content(&mut self) {
+        let config_content =
+            utils::files::get_client_config_content().unwrap_or(String::from("FAILED TO LOAD"));
+        self.textarea = TextArea::from(config_content.lines());
+        self.update_textarea_block();
+    }
+
     pub fn textarea_scroll(&mut self, scroll: Scrolling) {
         self.textarea.scroll(scroll)
     }
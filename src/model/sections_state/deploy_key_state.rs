@@ -0,0 +1,114 @@
+use crate::utils::{secret::SecretString, strings};
+
+/// State of the form used to install the selected public key in the authorized_keys of a remote
+/// host, and of the prompts shown while it's being installed
+#[derive(Clone, Default)]
+pub struct DeployKeyState {
+    /// Name of the public key file being deployed
+    public_key_name: String,
+    /// Aliases of the Host blocks of the client config, which can be picked instead of typing
+    hosts: Vec<String>,
+    host_idx: Option<usize>,
+    /// Destination given to ssh, either an alias or `[user@]hostname`
+    host: String,
+    /// Answer to the password (or passphrase) prompt of ssh. Forgotten once it's sent
+    remote_secret: SecretString,
+}
+
+impl DeployKeyState {
+    pub fn start(&mut self, public_key_name: &str, hosts: Vec<String>) {
+        *self = Self {
+            public_key_name: public_key_name.to_owned(),
+            hosts,
+            ..Self::default()
+        };
+        self.next_option();
+    }
+
+    pub fn get_public_key_name(&self) -> &str {
+        self.public_key_name.as_str()
+    }
+
+    pub fn get_host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    pub fn is_host_picked(&self) -> bool {
+        self.host_idx.is_some()
+    }
+
+    pub fn next_option(&mut self) {
+        if self.hosts.is_empty() {
+            return;
+        }
+
+        let idx = match self.host_idx {
+            Some(idx) => (idx + 1) % self.hosts.len(),
+            None => 0,
+        };
+        self.host_idx = Some(idx);
+        self.host = self.hosts[idx].clone();
+    }
+
+    pub fn prev_option(&mut self) {
+        if self.hosts.is_empty() {
+            return;
+        }
+
+        let idx = match self.host_idx {
+            Some(idx) => (idx + self.hosts.len() - 1) % self.hosts.len(),
+            None => self.hosts.len() - 1,
+        };
+        self.host_idx = Some(idx);
+        self.host = self.hosts[idx].clone();
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        // Once typed on, the host no longer is the picked alias
+        self.host_idx = None;
+        self.host.push(ch);
+    }
+
+    pub fn del_char(&mut self) {
+        self.host_idx = None;
+        self.host.pop();
+    }
+
+    pub fn del_word(&mut self) {
+        self.host_idx = None;
+        self.host = strings::del_last_word(self.host.to_owned());
+    }
+
+    /// Returns the host the key is going to be deployed to, or why it isn't valid
+    pub fn validate_host(&self) -> Result<&str, String> {
+        let host = self.host.trim();
+        if host.is_empty() {
+            return Err(String::from("the host can't be empty"));
+        }
+        if host.starts_with('-') || host.contains(char::is_whitespace) {
+            return Err(format!("invalid host: '{}'", host));
+        }
+
+        Ok(host)
+    }
+
+    pub fn get_remote_secret_len(&self) -> usize {
+        self.remote_secret.len()
+    }
+
+    pub fn get_remote_secret(&self) -> &SecretString {
+        &self.remote_secret
+    }
+
+    pub fn write_remote_secret(&mut self, ch: char) {
+        self.remote_secret.push(ch);
+    }
+
+    pub fn del_remote_secret_char(&mut self) {
+        self.remote_secret.pop();
+    }
+
+    pub fn clean_remote_secret(&mut self) {
+        self.remote_secret.clear();
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    keys::file_signature::{self, SignFileRequest, VerifyFileRequest},
+    utils::{files, strings},
+};
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum SignFileFocus {
+    #[default]
+    File,
+    Namespace,
+}
+
+/// Namespace signatures are made under by default, the same one ssh-keygen suggests for files
+const DEFAULT_SIGNATURE_NAMESPACE: &str = "file";
+
+/// State of the form used to sign a file with the selected key pair
+#[derive(Clone, Default)]
+pub struct SignFileState {
+    /// Name of the private key the file is signed with
+    private_key_name: String,
+    file_path: String,
+    namespace: String,
+    current_focus: SignFileFocus,
+}
+
+impl SignFileState {
+    pub fn start(&mut self, private_key_name: &str) {
+        *self = Self {
+            private_key_name: private_key_name.to_owned(),
+            namespace: String::from(DEFAULT_SIGNATURE_NAMESPACE),
+            ..Self::default()
+        };
+    }
+
+    pub fn get_private_key_name(&self) -> &str {
+        self.private_key_name.as_str()
+    }
+
+    pub fn get_file_path(&self) -> &str {
+        self.file_path.as_str()
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        self.namespace.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: SignFileFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            SignFileFocus::File => SignFileFocus::Namespace,
+            SignFileFocus::Namespace => SignFileFocus::File,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    fn focused_input(&mut self) -> &mut String {
+        match self.current_focus {
+            SignFileFocus::File => &mut self.file_path,
+            SignFileFocus::Namespace => &mut self.namespace,
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        self.focused_input().push(ch);
+    }
+
+    pub fn del_char(&mut self) {
+        self.focused_input().pop();
+    }
+
+    pub fn del_word(&mut self) {
+        let input = self.focused_input();
+        *input = strings::del_last_word(input.to_owned());
+    }
+
+    /// Builds the signing request out of the form, or returns why the form is invalid
+    pub fn get_request(&self) -> Result<SignFileRequest, String> {
+        if self.file_path.trim().is_empty() {
+            return Err(String::from("the file to sign can't be empty"));
+        }
+        if self.namespace.trim().is_empty() {
+            return Err(String::from("the namespace can't be empty"));
+        }
+
+        let file_path = files::expand_home(self.file_path.trim());
+        let signature_path = file_signature::signature_path(&file_path);
+        if signature_path.exists() {
+            return Err(format!(
+                "'{}' already exists, delete or move it before signing the file again",
+                signature_path.to_string_lossy()
+            ));
+        }
+
+        let ssh_dir = files::get_user_ssh_dir().map_err(|e| e.to_string())?;
+        Ok(SignFileRequest {
+            private_key_path: ssh_dir.join(&self.private_key_name),
+            file_path,
+            namespace: self.namespace.trim().to_owned(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum VerifySignatureFocus {
+    #[default]
+    File,
+    Signature,
+    AllowedSigners,
+    Identity,
+    Namespace,
+}
+
+/// State of the form used to verify the signature of a file
+#[derive(Clone, Default)]
+pub struct VerifySignatureState {
+    file_path: String,
+    /// Path of the signature. When empty, the one ssh-keygen writes next to the file is used
+    signature_path: String,
+    allowed_signers_path: String,
+    identity: String,
+    namespace: String,
+    current_focus: VerifySignatureFocus,
+}
+
+impl VerifySignatureState {
+    pub fn start(&mut self, allowed_signers_path: &str) {
+        *self = Self {
+            allowed_signers_path: allowed_signers_path.to_owned(),
+            namespace: String::from(DEFAULT_SIGNATURE_NAMESPACE),
+            ..Self::default()
+        };
+    }
+
+    pub fn get_file_path(&self) -> &str {
+        self.file_path.as_str()
+    }
+
+    pub fn get_signature_path(&self) -> &str {
+        self.signature_path.as_str()
+    }
+
+    pub fn get_allowed_signers_path(&self) -> &str {
+        self.allowed_signers_path.as_str()
+    }
+
+    pub fn get_identity(&self) -> &str {
+        self.identity.as_str()
+    }
+
+    pub fn get_namespace(&self) -> &str {
+        self.namespace.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: VerifySignatureFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            VerifySignatureFocus::File => VerifySignatureFocus::Signature,
+            VerifySignatureFocus::Signature => VerifySignatureFocus::AllowedSigners,
+            VerifySignatureFocus::AllowedSigners => VerifySignatureFocus::Identity,
+            VerifySignatureFocus::Identity => VerifySignatureFocus::Namespace,
+            VerifySignatureFocus::Namespace => VerifySignatureFocus::File,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            VerifySignatureFocus::File => VerifySignatureFocus::Namespace,
+            VerifySignatureFocus::Signature => VerifySignatureFocus::File,
+            VerifySignatureFocus::AllowedSigners => VerifySignatureFocus::Signature,
+            VerifySignatureFocus::Identity => VerifySignatureFocus::AllowedSigners,
+            VerifySignatureFocus::Namespace => VerifySignatureFocus::Identity,
+        }
+    }
+
+    fn focused_input(&mut self) -> &mut String {
+        match self.current_focus {
+            VerifySignatureFocus::File => &mut self.file_path,
+            VerifySignatureFocus::Signature => &mut self.signature_path,
+            VerifySignatureFocus::AllowedSigners => &mut self.allowed_signers_path,
+            VerifySignatureFocus::Identity => &mut self.identity,
+            VerifySignatureFocus::Namespace => &mut self.namespace,
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        self.focused_input().push(ch);
+    }
+
+    pub fn del_char(&mut self) {
+        self.focused_input().pop();
+    }
+
+    pub fn del_word(&mut self) {
+        let input = self.focused_input();
+        *input = strings::del_last_word(input.to_owned());
+    }
+
+    /// Builds the verification request out of the form, or returns why the form is invalid
+    pub fn get_request(&self) -> Result<VerifyFileRequest, String> {
+        if self.file_path.trim().is_empty() {
+            return Err(String::from("the signed file can't be empty"));
+        }
+        if self.allowed_signers_path.trim().is_empty() {
+            return Err(String::from("the allowed signers file can't be empty"));
+        }
+        if self.identity.trim().is_empty() {
+            return Err(String::from("the identity of the signer can't be empty"));
+        }
+        if self.namespace.trim().is_empty() {
+            return Err(String::from("the namespace can't be empty"));
+        }
+
+        let file_path = files::expand_home(self.file_path.trim());
+        let signature_path = if self.signature_path.trim().is_empty() {
+            file_signature::signature_path(&file_path)
+        } else {
+            files::expand_home(self.signature_path.trim())
+        };
+
+        Ok(VerifyFileRequest {
+            file_path,
+            signature_path,
+            allowed_signers_path: files::expand_home(self.allowed_signers_path.trim()),
+            identity: self.identity.trim().to_owned(),
+            namespace: self.namespace.trim().to_owned(),
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::utils::strings;
+
+use super::public_keys_list_state::validate_key_file_name;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum ImportKeyFocus {
+    #[default]
+    SourcePath,
+    Name,
+}
+
+/// State of the form used to import a key in a foreign format (PEM, PKCS#8, RFC4716)
+#[derive(Clone, Default)]
+pub struct ImportKeyState {
+    source_path: String,
+    name: String,
+    current_focus: ImportKeyFocus,
+}
+
+impl ImportKeyState {
+    pub fn get_source_path(&self) -> &str {
+        self.source_path.as_str()
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: ImportKeyFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        match self.current_focus {
+            ImportKeyFocus::SourcePath => self.current_focus = ImportKeyFocus::Name,
+            ImportKeyFocus::Name => self.current_focus = ImportKeyFocus::SourcePath,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        match self.current_focus {
+            ImportKeyFocus::SourcePath => self.source_path.push(ch),
+            ImportKeyFocus::Name => self.name.push(ch),
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        match self.current_focus {
+            ImportKeyFocus::SourcePath => self.source_path.pop(),
+            ImportKeyFocus::Name => self.name.pop(),
+        };
+    }
+
+    pub fn del_word(&mut self) {
+        match self.current_focus {
+            ImportKeyFocus::SourcePath => {
+                self.source_path = strings::del_last_word(self.source_path.to_owned())
+            }
+            ImportKeyFocus::Name => self.name = strings::del_last_word(self.name.to_owned()),
+        }
+    }
+
+    pub fn validate_name(&self) -> Result<(), String> {
+        validate_key_file_name(&self.name)
+    }
+
+    pub fn clean(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -1,14 +1,98 @@
 use serde::Serialize;
 
-use crate::utils;
+use crate::utils::{
+    self,
+    known_hosts::{KnownHostsEntry, KnownHostsMarker},
+    strings,
+};
 
 type ListItems = Vec<String>;
 
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum NewCertAuthorityFocus {
+    #[default]
+    PubKeyPath,
+    HostPattern,
+}
+
+/// State of the form used to trust a new host CA (@cert-authority)
+#[derive(Clone, Default)]
+pub struct NewCertAuthorityState {
+    pub_key_path: String,
+    host_pattern: String,
+    current_focus: NewCertAuthorityFocus,
+}
+
+impl NewCertAuthorityState {
+    pub fn get_pub_key_path(&self) -> &str {
+        self.pub_key_path.as_str()
+    }
+
+    pub fn get_host_pattern(&self) -> &str {
+        self.host_pattern.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: NewCertAuthorityFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        match self.current_focus {
+            NewCertAuthorityFocus::PubKeyPath => {
+                self.current_focus = NewCertAuthorityFocus::HostPattern
+            }
+            NewCertAuthorityFocus::HostPattern => {
+                self.current_focus = NewCertAuthorityFocus::PubKeyPath
+            }
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.next_focus();
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        match self.current_focus {
+            NewCertAuthorityFocus::PubKeyPath => self.pub_key_path.push(ch),
+            NewCertAuthorityFocus::HostPattern => self.host_pattern.push(ch),
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        match self.current_focus {
+            NewCertAuthorityFocus::PubKeyPath => self.pub_key_path.pop(),
+            NewCertAuthorityFocus::HostPattern => self.host_pattern.pop(),
+        };
+    }
+
+    pub fn del_word(&mut self) {
+        match self.current_focus {
+            NewCertAuthorityFocus::PubKeyPath => {
+                self.pub_key_path = strings::del_last_word(self.pub_key_path.to_owned())
+            }
+            NewCertAuthorityFocus::HostPattern => {
+                self.host_pattern = strings::del_last_word(self.host_pattern.to_owned())
+            }
+        }
+    }
+
+    pub fn clean(&mut self) {
+        *self = Self::default();
+    }
+}
+
 #[derive(Clone)]
 pub struct KnownHostsListState {
     items: ListItems,
     selected_item_idx: Option<usize>,
     has_focus: bool,
+    /// @cert-authority and @revoked entries of the user's known_hosts file
+    markers: Vec<KnownHostsEntry>,
+    selected_marker_idx: Option<usize>,
+    /// Keys of the selected host, one of which can be revoked
+    host_keys: Vec<KnownHostsEntry>,
+    selected_host_key_idx: Option<usize>,
+    new_cert_authority_state: NewCertAuthorityState,
 }
 
 impl KnownHostsListState {
@@ -18,6 +102,13 @@ impl KnownHostsListState {
         if !self.items.is_empty() {
             self.selected_item_idx = Some(0);
         }
+
+        self.markers = utils::files::get_known_hosts_markers().unwrap_or_default();
+        self.selected_marker_idx = if self.markers.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
     }
 
     pub fn focus(&mut self) {
@@ -44,6 +135,93 @@ impl KnownHostsListState {
         self.selected_item_idx
     }
 
+    pub fn get_selected_host(&self) -> Option<String> {
+        self.selected_item_idx
+            .and_then(|idx| self.items.get(idx))
+            .cloned()
+    }
+
+    pub fn get_markers(&self) -> &[KnownHostsEntry] {
+        &self.markers
+    }
+
+    pub fn get_selected_marker_idx(&self) -> Option<usize> {
+        self.selected_marker_idx
+    }
+
+    pub fn next_marker(&mut self) {
+        if let Some(idx) = self.selected_marker_idx {
+            if idx + 1 < self.markers.len() {
+                self.selected_marker_idx = Some(idx + 1);
+            }
+        }
+    }
+
+    pub fn prev_marker(&mut self) {
+        if let Some(idx) = self.selected_marker_idx {
+            if idx > 0 {
+                self.selected_marker_idx = Some(idx - 1);
+            }
+        }
+    }
+
+    /// Loads the keys of the selected host, to choose the one to revoke
+    pub fn start_host_key_revocation(&mut self) {
+        self.host_keys = self
+            .get_selected_host()
+            .and_then(|host| utils::files::get_known_host_keys(&host).ok())
+            .unwrap_or_default();
+        self.selected_host_key_idx = if self.host_keys.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+
+    pub fn get_host_keys(&self) -> &[KnownHostsEntry] {
+        &self.host_keys
+    }
+
+    pub fn get_selected_host_key_idx(&self) -> Option<usize> {
+        self.selected_host_key_idx
+    }
+
+    pub fn get_selected_host_key(&self) -> Option<&KnownHostsEntry> {
+        self.selected_host_key_idx
+            .and_then(|idx| self.host_keys.get(idx))
+    }
+
+    /// Returns true if the key already has a @revoked line
+    pub fn is_revoked(&self, host_key: &KnownHostsEntry) -> bool {
+        self.markers.iter().any(|marker| {
+            marker.marker == Some(KnownHostsMarker::Revoked) && marker.same_key(host_key)
+        })
+    }
+
+    pub fn next_host_key(&mut self) {
+        if let Some(idx) = self.selected_host_key_idx {
+            if idx + 1 < self.host_keys.len() {
+                self.selected_host_key_idx = Some(idx + 1);
+            }
+        }
+    }
+
+    pub fn prev_host_key(&mut self) {
+        if let Some(idx) = self.selected_host_key_idx {
+            if idx > 0 {
+                self.selected_host_key_idx = Some(idx - 1);
+            }
+        }
+    }
+
+    pub fn get_new_cert_authority_state(&self) -> &NewCertAuthorityState {
+        &self.new_cert_authority_state
+    }
+
+    pub fn get_new_cert_authority_state_mut(&mut self) -> &mut NewCertAuthorityState {
+        &mut self.new_cert_authority_state
+    }
+
     pub fn next_item(&mut self) {
         match self.selected_item_idx {
             None => {
@@ -81,6 +259,11 @@ impl Default for KnownHostsListState {
             items: vec![],
             selected_item_idx: None,
             has_focus: true,
+            markers: vec![],
+            selected_marker_idx: None,
+            host_keys: vec![],
+            selected_host_key_idx: None,
+            new_cert_authority_state: NewCertAuthorityState::default(),
         };
 
         state.load_known_hosts();
@@ -3,22 +3,42 @@ use serde::Serialize;
 use crate::ui::components::sections::Section;
 
 use self::{
+    agent_state::AgentState,
+    authorized_keys_state::AuthorizedKeysState,
     known_hosts_list_state::KnownHostsListState,
     public_keys_list_state::PublicKeysListState,
     client_config_state::ClientConfigState
 };
 
+pub mod agent_state;
+pub mod allowed_signers_state;
+pub mod authorized_keys_state;
+pub mod deploy_key_state;
+pub mod file_signature_state;
+pub mod import_key_state;
 pub mod known_hosts_list_state;
 pub mod public_keys_list_state;
+pub mod rename_key_state;
+pub mod rotate_key_state;
+pub mod sign_certificate_state;
+pub mod trash_state;
 pub mod client_config_state;
 
-const SECTIONS_ORDER: [Section; 3] = [Section::KnownHostsList, Section::PublicKeysList, Section::ClientConfig];
+const SECTIONS_ORDER: [Section; 5] = [
+    Section::KnownHostsList,
+    Section::PublicKeysList,
+    Section::Agent,
+    Section::ClientConfig,
+    Section::AuthorizedKeys,
+];
 
 #[derive(Default, Serialize)]
 pub struct SectionsStates {
     known_hosts_list_state: KnownHostsListState,
     public_keys_list_state: PublicKeysListState,
-    client_config_state: ClientConfigState
+    agent_state: AgentState,
+    client_config_state: ClientConfigState,
+    authorized_keys_state: AuthorizedKeysState,
 }
 
 impl SectionsStates {
@@ -63,19 +83,39 @@ impl SectionsStates {
         match section {
             Section::KnownHostsList => {
                 self.public_keys_list_state.unfocus();
+                self.agent_state.unfocus();
                 self.client_config_state.unfocus();
+                self.authorized_keys_state.unfocus();
                 self.known_hosts_list_state.focus();
             }
             Section::PublicKeysList => {
                 self.known_hosts_list_state.unfocus();
+                self.agent_state.unfocus();
                 self.client_config_state.unfocus();
+                self.authorized_keys_state.unfocus();
                 self.public_keys_list_state.focus();
             }
+            Section::Agent => {
+                self.known_hosts_list_state.unfocus();
+                self.public_keys_list_state.unfocus();
+                self.client_config_state.unfocus();
+                self.authorized_keys_state.unfocus();
+                self.agent_state.focus();
+            }
             Section::ClientConfig => {
                 self.known_hosts_list_state.unfocus();
                 self.public_keys_list_state.unfocus();
+                self.agent_state.unfocus();
+                self.authorized_keys_state.unfocus();
                 self.client_config_state.focus();
             }
+            Section::AuthorizedKeys => {
+                self.known_hosts_list_state.unfocus();
+                self.public_keys_list_state.unfocus();
+                self.agent_state.unfocus();
+                self.client_config_state.unfocus();
+                self.authorized_keys_state.focus();
+            }
         }
     }
 
@@ -83,7 +123,9 @@ impl SectionsStates {
         match section {
             Section::KnownHostsList => self.known_hosts_list_state.has_focus(),
             Section::PublicKeysList => self.public_keys_list_state.has_focus(),
-            Section::ClientConfig => self.client_config_state.has_focus()
+            Section::Agent => self.agent_state.has_focus(),
+            Section::ClientConfig => self.client_config_state.has_focus(),
+            Section::AuthorizedKeys => self.authorized_keys_state.has_focus(),
         }
     }
 
@@ -95,10 +137,18 @@ impl SectionsStates {
         &self.public_keys_list_state
     }
 
+    pub fn get_agent_state(&self) -> &AgentState {
+        &self.agent_state
+    }
+
     pub fn get_client_config_state(&self) -> &ClientConfigState {
         &self.client_config_state
     }
 
+    pub fn get_authorized_keys_state(&self) -> &AuthorizedKeysState {
+        &self.authorized_keys_state
+    }
+
     pub fn get_known_hosts_list_state_mut(&mut self) -> &mut KnownHostsListState {
         &mut self.known_hosts_list_state
     }
@@ -107,7 +157,15 @@ impl SectionsStates {
         &mut self.public_keys_list_state
     }
 
+    pub fn get_agent_state_mut(&mut self) -> &mut AgentState {
+        &mut self.agent_state
+    }
+
     pub fn get_client_config_state_mut(&mut self) -> &mut ClientConfigState {
         &mut self.client_config_state
     }
+
+    pub fn get_authorized_keys_state_mut(&mut self) -> &mut AuthorizedKeysState {
+        &mut self.authorized_keys_state
+    }
 }
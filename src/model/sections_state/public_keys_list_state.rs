@@ -1,28 +1,101 @@
 use color_eyre::{eyre::eyre, Result};
+use qrcodegen::QrCode;
 use serde::Serialize;
 use std::path::PathBuf;
 
 use crate::{
     commands::ssh_keygen::PublicKeyType,
-    utils::{self, files, strings},
+    keys::{
+        audit::{self, KeyPolicy},
+        convert::KeyFormat,
+        info::{PrivateKeyStatus, PublicKeyInfo},
+        passphrase_policy::{self, PassphrasePolicy, PassphraseStrength},
+        KeygenBackend,
+    },
+    utils::{self, files, qr, secret::SecretString, strings},
 };
 
-type ListItems = Vec<String>;
+use super::{
+    allowed_signers_state::{AllowedSignersState, GitSigningState},
+    deploy_key_state::DeployKeyState,
+    file_signature_state::{SignFileState, VerifySignatureState},
+    import_key_state::ImportKeyState,
+    rename_key_state::RenameKeyState,
+    rotate_key_state::RotateKeyState,
+    sign_certificate_state::SignCertificateState,
+    trash_state::TrashState,
+};
+
+type ListItems = Vec<PublicKeyInfo>;
+
+/// Checks that the given name can be used for the private key of a key pair stored in the SSH
+/// directory. The public key and the certificate are named after it
+pub fn validate_key_file_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err(String::from("the name of the key can't be empty"));
+    }
+    if name.contains('/') {
+        return Err(String::from("the name of the key must be a file name"));
+    }
+    if name.ends_with(".pub") {
+        return Err(String::from(
+            "the name is the one of the private key, without .pub",
+        ));
+    }
+    if name == "config" {
+        return Err(String::from(
+            "the name 'config' is reserved to the config file",
+        ));
+    }
+
+    Ok(())
+}
 
 #[derive(Clone, Copy, Default, PartialEq)]
 pub enum NewPublicKeyFocus {
     #[default]
     Name,
+    KeyType,
     Comment,
-    // KeyType For now, only ED25519 is available
+    Backend,
 }
+/// What the passphrase prompts are currently being used for
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum PassphrasePurpose {
+    /// Setting the passphrase of a new key pair
+    #[default]
+    NewKey,
+    /// Changing (or removing) the passphrase of the selected key pair
+    ChangePassphrase,
+    /// Decrypting the selected private key to change its comment
+    EditComment,
+    /// Decrypting an encrypted PKCS#8 key being imported
+    ImportKey,
+    /// Decrypting the selected private key to regenerate its missing public key
+    DerivePublicKey,
+    /// Setting the passphrase of the key replacing the selected one
+    RotateKey,
+    /// Decrypting the selected private key to add it to the agent
+    AddToAgent,
+    /// Decrypting the CA key used to issue a certificate
+    SignCertificate,
+    /// Decrypting the selected private key to sign a file
+    SignFile,
+}
+
 pub struct NewPublicKeyState {
     name: String,
     key_type: PublicKeyType,
     comment: String,
+    backend: KeygenBackend,
     current_focus: NewPublicKeyFocus,
-    passphrase: Option<String>,
-    passphrase_check: Option<String>,
+    passphrase: SecretString,
+    passphrase_check: SecretString,
+    /// Current passphrase of the key whose passphrase is being changed
+    old_passphrase: SecretString,
+    passphrase_purpose: PassphrasePurpose,
+    /// Minimum the new passphrases must meet
+    passphrase_policy: PassphrasePolicy,
 }
 
 impl Default for NewPublicKeyState {
@@ -33,9 +106,13 @@ impl Default for NewPublicKeyState {
             name: format!("id_{}", name),
             key_type: def_keytype,
             comment: String::default(),
+            backend: KeygenBackend::default(),
             current_focus: NewPublicKeyFocus::Name,
-            passphrase: None,
-            passphrase_check: None,
+            passphrase: SecretString::default(),
+            passphrase_check: SecretString::default(),
+            old_passphrase: SecretString::default(),
+            passphrase_purpose: PassphrasePurpose::default(),
+            passphrase_policy: PassphrasePolicy::from_env(),
         }
     }
 }
@@ -53,18 +130,20 @@ impl NewPublicKeyState {
         self.comment.as_str()
     }
 
+    pub fn get_backend(&self) -> KeygenBackend {
+        self.backend
+    }
+
+    pub fn toggle_backend(&mut self) {
+        self.backend = self.backend.toggle();
+    }
+
     pub fn get_passphrase_len(&self) -> usize {
-        match &self.passphrase {
-            None => 0,
-            Some(pass) => pass.len(),
-        }
+        self.passphrase.len()
     }
 
-    pub fn get_passphrase_bytes(&self) -> Vec<u8> {
-        match &self.passphrase {
-            None => vec![],
-            Some(pass) => pass.clone().as_bytes().to_vec(),
-        }
+    pub fn get_passphrase(&self) -> &SecretString {
+        &self.passphrase
     }
 
     pub fn write_name(&mut self, ch: char) {
@@ -101,79 +180,134 @@ impl NewPublicKeyState {
 
     pub fn next_focus(&mut self) {
         match self.current_focus {
-            NewPublicKeyFocus::Comment => self.current_focus = NewPublicKeyFocus::Name,
-            NewPublicKeyFocus::Name => self.current_focus = NewPublicKeyFocus::Comment,
+            NewPublicKeyFocus::Name => self.current_focus = NewPublicKeyFocus::KeyType,
+            NewPublicKeyFocus::KeyType => self.current_focus = NewPublicKeyFocus::Comment,
+            NewPublicKeyFocus::Comment => self.current_focus = NewPublicKeyFocus::Backend,
+            NewPublicKeyFocus::Backend => self.current_focus = NewPublicKeyFocus::Name,
         }
     }
 
     pub fn prev_focus(&mut self) {
         match self.current_focus {
-            NewPublicKeyFocus::Comment => self.current_focus = NewPublicKeyFocus::Name,
-            NewPublicKeyFocus::Name => self.current_focus = NewPublicKeyFocus::Comment,
+            NewPublicKeyFocus::Name => self.current_focus = NewPublicKeyFocus::Backend,
+            NewPublicKeyFocus::KeyType => self.current_focus = NewPublicKeyFocus::Name,
+            NewPublicKeyFocus::Comment => self.current_focus = NewPublicKeyFocus::KeyType,
+            NewPublicKeyFocus::Backend => self.current_focus = NewPublicKeyFocus::Comment,
         }
     }
 
-    pub fn write_passphrase(&mut self, ch: char) {
-        match &mut self.passphrase {
-            None => self.passphrase = Some(String::from(ch)),
-            Some(pass) => pass.push(ch),
+    pub fn next_type(&mut self) {
+        self.set_type(self.key_type.next());
+    }
+
+    pub fn prev_type(&mut self) {
+        self.set_type(self.key_type.prev());
+    }
+
+    /// Sets the key type, updating the name if it's still the default one of the previous type
+    fn set_type(&mut self, key_type: PublicKeyType) {
+        let prev_type_name: &str = self.key_type.into();
+        let new_type_name: &str = key_type.into();
+        if self.name == format!("id_{}", prev_type_name) {
+            self.name = format!("id_{}", new_type_name);
         }
+
+        self.key_type = key_type;
+    }
+
+    pub fn write_passphrase(&mut self, ch: char) {
+        self.passphrase.push(ch);
     }
 
     pub fn del_passphare_char(&mut self) {
-        if let Some(pass) = &mut self.passphrase {
-            pass.pop();
-        }
+        self.passphrase.pop();
     }
 
     pub fn del_passphrase(&mut self) {
-        self.passphrase = None;
+        self.passphrase.clear();
     }
 
     pub fn write_passphrase_check(&mut self, ch: char) {
-        match &mut self.passphrase_check {
-            None => self.passphrase_check = Some(String::from(ch)),
-            Some(pass) => pass.push(ch),
-        }
+        self.passphrase_check.push(ch);
     }
 
     pub fn del_passphrase_check_char(&mut self) {
-        if let Some(pass) = &mut self.passphrase_check {
-            pass.pop();
-        }
+        self.passphrase_check.pop();
     }
 
     pub fn del_passphrase_check(&mut self) {
-        self.passphrase_check = None;
+        self.passphrase_check.clear();
     }
 
-    pub fn get_passphrase_check_bytes(&self) -> Vec<u8> {
-        match &self.passphrase_check {
-            None => vec![],
-            Some(pass) => pass.clone().as_bytes().to_vec(),
-        }
+    pub fn get_passphrase_check(&self) -> &SecretString {
+        &self.passphrase_check
     }
 
     pub fn get_passphrase_check_len(&self) -> usize {
-        match &self.passphrase_check {
-            None => 0,
-            Some(pass) => pass.len(),
-        }
+        self.passphrase_check.len()
+    }
+
+    pub fn write_old_passphrase(&mut self, ch: char) {
+        self.old_passphrase.push(ch);
+    }
+
+    pub fn del_old_passphrase_char(&mut self) {
+        self.old_passphrase.pop();
+    }
+
+    pub fn del_old_passphrase(&mut self) {
+        self.old_passphrase.clear();
+    }
+
+    pub fn get_old_passphrase(&self) -> &SecretString {
+        &self.old_passphrase
+    }
+
+    pub fn get_old_passphrase_len(&self) -> usize {
+        self.old_passphrase.len()
+    }
+
+    pub fn get_passphrase_purpose(&self) -> PassphrasePurpose {
+        self.passphrase_purpose
+    }
+
+    pub fn set_passphrase_purpose(&mut self, purpose: PassphrasePurpose) {
+        self.passphrase_purpose = purpose;
+    }
+
+    pub fn get_passphrase_policy(&self) -> &PassphrasePolicy {
+        &self.passphrase_policy
+    }
+
+    pub fn get_passphrase_strength(&self) -> PassphraseStrength {
+        PassphraseStrength::of(self.passphrase.expose())
+    }
+
+    pub fn get_passphrase_entropy_bits(&self) -> f64 {
+        passphrase_policy::estimate_entropy_bits(self.passphrase.expose())
+    }
+
+    /// Checks the new passphrase against the policy
+    ///
+    /// # Returns
+    /// Why the passphrase isn't accepted
+    pub fn check_passphrase_policy(&self) -> Result<(), String> {
+        self.passphrase_policy
+            .check(self.passphrase.expose())
+    }
+
+    pub fn passphrases_match(&self) -> bool {
+        self.passphrase.expose() == self.passphrase_check.expose()
     }
 
     pub fn clean_passphrases(&mut self) {
-        self.passphrase = None;
-        self.passphrase_check = None;
+        self.passphrase.clear();
+        self.passphrase_check.clear();
+        self.old_passphrase.clear();
     }
 
     pub fn validate_name(&self) -> Result<(), String> {
-        if self.name == "config" {
-            return Err(String::from(
-                "the name 'config' is reserved to the config file",
-            ));
-        }
-
-        Ok(())
+        validate_key_file_name(&self.name)
     }
 }
 
@@ -182,12 +316,54 @@ pub struct PublicKeysListState {
     selected_item_idx: Option<usize>,
     has_focus: bool,
     new_key_state: NewPublicKeyState,
+    /// New comment of the selected key pair, while it's being edited
+    edited_comment: String,
+    /// Format the selected public key will be exported to
+    export_format: KeyFormat,
+    /// QR codes of the selected public key, while it's shown as such. Long keys take more than one
+    qr_code_parts: Vec<QrCode>,
+    qr_part_idx: usize,
+    import_key_state: ImportKeyState,
+    rotate_key_state: RotateKeyState,
+    rename_key_state: RenameKeyState,
+    /// Host blocks of the client config that use the key pair about to be deleted
+    deletion_references: Vec<String>,
+    trash_state: TrashState,
+    sign_certificate_state: SignCertificateState,
+    deploy_key_state: DeployKeyState,
+    sign_file_state: SignFileState,
+    verify_signature_state: VerifySignatureState,
+    allowed_signers_state: AllowedSignersState,
+    git_signing_state: GitSigningState,
 }
 
 impl PublicKeysListState {
     pub fn load_public_keys(&mut self) {
+        let ssh_dir = files::get_user_ssh_dir().unwrap_or_default();
         let public_keys = utils::files::get_public_keys_names().unwrap_or_default();
-        self.items = public_keys;
+        let orphaned_private_keys =
+            utils::files::get_orphaned_private_keys_names().unwrap_or_default();
+        self.items = public_keys
+            .iter()
+            .map(|name| PublicKeyInfo::load(&ssh_dir, name))
+            .chain(
+                orphaned_private_keys
+                    .iter()
+                    .map(|name| PublicKeyInfo::load_orphan(&ssh_dir, name)),
+            )
+            .collect();
+        self.items.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+        let policy = KeyPolicy::from_env();
+        for item in self.items.iter_mut() {
+            item.issues = audit::audit_key(&ssh_dir, item, &policy);
+        }
+
+        if let Some(idx) = self.selected_item_idx {
+            if idx >= self.items.len() {
+                self.selected_item_idx = self.items.len().checked_sub(1);
+            }
+        }
     }
 
     pub fn focus(&mut self) {
@@ -206,8 +382,18 @@ impl PublicKeysListState {
         self.has_focus
     }
 
-    pub fn get_items(&self) -> ListItems {
-        self.items.clone()
+    pub fn get_items(&self) -> &[PublicKeyInfo] {
+        &self.items
+    }
+
+    pub fn get_selected_item(&self) -> Option<&PublicKeyInfo> {
+        self.selected_item_idx.and_then(|idx| self.items.get(idx))
+    }
+
+    /// Checks if the selected item is a private key whose public key file is missing
+    pub fn is_selected_item_orphaned(&self) -> bool {
+        self.get_selected_item()
+            .is_some_and(|item| item.public_key_missing)
     }
 
     pub fn get_selected_item_idx(&self) -> Option<usize> {
@@ -215,10 +401,7 @@ impl PublicKeysListState {
     }
 
     pub fn get_selected_key_name(&self) -> Option<String> {
-        let public_key_name = match self.get_selected_item_idx() {
-            Some(idx) => self.items.get(idx),
-            None => None,
-        };
+        let public_key_name = self.get_selected_item().map(|item| &item.file_name);
 
         // I want only the key name, a.k.a the file stem of the public key
         match public_key_name {
@@ -233,21 +416,22 @@ impl PublicKeysListState {
     }
 
     pub fn get_selected_key_path(&self) -> Option<PathBuf> {
-        let public_key_name = match self.get_selected_item_idx() {
-            Some(idx) => self.items.get(idx),
-            None => None,
-        };
+        let public_key_name = self.get_selected_item().map(|item| &item.file_name);
 
         let ssh_dir = files::get_user_ssh_dir().unwrap_or_default();
 
         public_key_name.map(|n| ssh_dir.join(n))
     }
 
+    /// Returns the path of the private key of the selected key pair
+    pub fn get_selected_private_key_path(&self) -> Option<PathBuf> {
+        let ssh_dir = files::get_user_ssh_dir().unwrap_or_default();
+
+        self.get_selected_key_name().map(|n| ssh_dir.join(n))
+    }
+
     pub fn get_selected_key_content(&self) -> Result<String> {
-        let public_key_name = match self.get_selected_item_idx() {
-            Some(idx) => self.items.get(idx),
-            None => None,
-        };
+        let public_key_name = self.get_selected_item().map(|item| &item.file_name);
         let public_key_name = public_key_name.ok_or(
             eyre!(
                 "An error ocurred while trying to read the key name. Use R to refresh the list and try again."
@@ -286,6 +470,259 @@ impl PublicKeysListState {
         }
     }
 
+    /// Starts editing the comment of the selected key pair, using its current comment as the
+    /// initial value
+    pub fn start_comment_edit(&mut self) {
+        self.edited_comment = self
+            .get_selected_item()
+            .map(|item| item.comment.clone())
+            .unwrap_or_default();
+    }
+
+    pub fn get_edited_comment(&self) -> &str {
+        self.edited_comment.as_str()
+    }
+
+    pub fn write_edited_comment(&mut self, ch: char) {
+        self.edited_comment.push(ch);
+    }
+
+    pub fn del_edited_comment_char(&mut self) {
+        self.edited_comment.pop();
+    }
+
+    pub fn del_edited_comment_word(&mut self) {
+        self.edited_comment = strings::del_last_word(self.edited_comment.to_owned());
+    }
+
+    pub fn get_export_format(&self) -> KeyFormat {
+        self.export_format
+    }
+
+    pub fn next_export_format(&mut self) {
+        self.export_format = self.export_format.next();
+    }
+
+    pub fn prev_export_format(&mut self) {
+        self.export_format = self.export_format.prev();
+    }
+
+    /// Switches the content of the selected public key between its text and its QR codes
+    pub fn toggle_qr_code(&mut self) {
+        self.qr_part_idx = 0;
+        if !self.qr_code_parts.is_empty() {
+            self.qr_code_parts.clear();
+            return;
+        }
+
+        if let Ok(content) = self.get_selected_key_content() {
+            self.qr_code_parts = qr::encode_in_parts(content.trim());
+        }
+    }
+
+    pub fn hide_qr_code(&mut self) {
+        self.qr_code_parts.clear();
+        self.qr_part_idx = 0;
+    }
+
+    pub fn get_qr_code_parts(&self) -> &[QrCode] {
+        &self.qr_code_parts
+    }
+
+    pub fn get_qr_part_idx(&self) -> usize {
+        self.qr_part_idx
+    }
+
+    pub fn next_qr_part(&mut self) {
+        if !self.qr_code_parts.is_empty() {
+            self.qr_part_idx = (self.qr_part_idx + 1) % self.qr_code_parts.len();
+        }
+    }
+
+    pub fn prev_qr_part(&mut self) {
+        if !self.qr_code_parts.is_empty() {
+            self.qr_part_idx =
+                (self.qr_part_idx + self.qr_code_parts.len() - 1) % self.qr_code_parts.len();
+        }
+    }
+
+    pub fn get_import_key_state(&self) -> &ImportKeyState {
+        &self.import_key_state
+    }
+
+    pub fn get_import_key_state_mut(&mut self) -> &mut ImportKeyState {
+        &mut self.import_key_state
+    }
+
+    /// Starts the rotation of the selected key pair
+    pub fn start_key_rotation(&mut self) {
+        let old_key_name = self.get_selected_key_name().unwrap_or_default();
+        let comment = self
+            .get_selected_item()
+            .map(|item| item.comment.clone())
+            .unwrap_or_default();
+
+        self.rotate_key_state.start(&old_key_name, &comment);
+    }
+
+    pub fn get_rotate_key_state(&self) -> &RotateKeyState {
+        &self.rotate_key_state
+    }
+
+    pub fn get_rotate_key_state_mut(&mut self) -> &mut RotateKeyState {
+        &mut self.rotate_key_state
+    }
+
+    pub fn start_key_rename(&mut self) {
+        let old_key_name = self.get_selected_key_name().unwrap_or_default();
+        self.rename_key_state.start(&old_key_name);
+    }
+
+    pub fn get_rename_key_state(&self) -> &RenameKeyState {
+        &self.rename_key_state
+    }
+
+    pub fn get_rename_key_state_mut(&mut self) -> &mut RenameKeyState {
+        &mut self.rename_key_state
+    }
+
+    /// Looks up the Host blocks of the client config that use the selected key pair, so they're
+    /// shown before deleting it
+    pub fn start_key_deletion(&mut self) {
+        let key_name = self.get_selected_key_name().unwrap_or_default();
+        let references = files::find_key_pair_references(&key_name).unwrap_or_default();
+
+        self.deletion_references = vec![];
+        for reference in references {
+            let host = reference
+                .host
+                .unwrap_or_else(|| String::from("(global, before any Host block)"));
+            if !self.deletion_references.contains(&host) {
+                self.deletion_references.push(host);
+            }
+        }
+    }
+
+    pub fn get_deletion_references(&self) -> &[String] {
+        &self.deletion_references
+    }
+
+    pub fn get_trash_state(&self) -> &TrashState {
+        &self.trash_state
+    }
+
+    pub fn get_trash_state_mut(&mut self) -> &mut TrashState {
+        &mut self.trash_state
+    }
+
+    /// Starts issuing a certificate for the selected public key. Any other key pair with a private
+    /// key can be used as the CA
+    pub fn start_certificate_signing(&mut self) {
+        let public_key_name = self
+            .get_selected_item()
+            .map(|item| item.file_name.clone())
+            .unwrap_or_default();
+        let ca_keys = self
+            .items
+            .iter()
+            .filter(|item| {
+                item.file_name != public_key_name
+                    && item.certificate.is_none()
+                    && item.private_key_status != PrivateKeyStatus::Missing
+            })
+            .map(|item| item.file_name.trim_end_matches(".pub").to_owned())
+            .collect();
+
+        self.sign_certificate_state.start(&public_key_name, ca_keys);
+    }
+
+    pub fn get_sign_certificate_state(&self) -> &SignCertificateState {
+        &self.sign_certificate_state
+    }
+
+    pub fn get_sign_certificate_state_mut(&mut self) -> &mut SignCertificateState {
+        &mut self.sign_certificate_state
+    }
+
+    /// Starts deploying the selected public key, offering the aliases of the client config as the
+    /// destination
+    pub fn start_key_deploy(&mut self) {
+        let public_key_name = self
+            .get_selected_item()
+            .map(|item| item.file_name.clone())
+            .unwrap_or_default();
+        let hosts = files::get_client_config_hosts().unwrap_or_default();
+
+        self.deploy_key_state.start(&public_key_name, hosts);
+    }
+
+    pub fn get_deploy_key_state(&self) -> &DeployKeyState {
+        &self.deploy_key_state
+    }
+
+    pub fn get_deploy_key_state_mut(&mut self) -> &mut DeployKeyState {
+        &mut self.deploy_key_state
+    }
+
+    /// Starts signing a file with the selected key pair
+    pub fn start_file_signing(&mut self) {
+        let private_key_name = self
+            .get_selected_item()
+            .map(|item| item.file_name.trim_end_matches(".pub").to_owned())
+            .unwrap_or_default();
+
+        self.sign_file_state.start(&private_key_name);
+    }
+
+    pub fn get_sign_file_state(&self) -> &SignFileState {
+        &self.sign_file_state
+    }
+
+    pub fn get_sign_file_state_mut(&mut self) -> &mut SignFileState {
+        &mut self.sign_file_state
+    }
+
+    /// Starts verifying a signature, against the allowed_signers file of the user by default
+    pub fn start_signature_verification(&mut self) {
+        let allowed_signers_path = files::get_allowed_signers_path().unwrap_or_default();
+
+        self.verify_signature_state.start(&allowed_signers_path.to_string_lossy());
+    }
+
+    pub fn get_verify_signature_state(&self) -> &VerifySignatureState {
+        &self.verify_signature_state
+    }
+
+    pub fn get_verify_signature_state_mut(&mut self) -> &mut VerifySignatureState {
+        &mut self.verify_signature_state
+    }
+
+    pub fn get_allowed_signers_state(&self) -> &AllowedSignersState {
+        &self.allowed_signers_state
+    }
+
+    pub fn get_allowed_signers_state_mut(&mut self) -> &mut AllowedSignersState {
+        &mut self.allowed_signers_state
+    }
+
+    /// Starts making git sign with the selected public key
+    pub fn start_git_signing(&mut self) {
+        let public_key_name = self
+            .get_selected_item()
+            .map(|item| item.file_name.clone())
+            .unwrap_or_default();
+
+        self.git_signing_state.start(&public_key_name);
+    }
+
+    pub fn get_git_signing_state(&self) -> &GitSigningState {
+        &self.git_signing_state
+    }
+
+    pub fn get_git_signing_state_mut(&mut self) -> &mut GitSigningState {
+        &mut self.git_signing_state
+    }
+
     pub fn get_new_key_state(&self) -> &NewPublicKeyState {
         &self.new_key_state
     }
@@ -302,6 +739,21 @@ impl Default for PublicKeysListState {
             selected_item_idx: None,
             has_focus: false,
             new_key_state: NewPublicKeyState::default(),
+            edited_comment: String::default(),
+            export_format: KeyFormat::default(),
+            qr_code_parts: vec![],
+            qr_part_idx: 0,
+            import_key_state: ImportKeyState::default(),
+            rotate_key_state: RotateKeyState::default(),
+            rename_key_state: RenameKeyState::default(),
+            deletion_references: vec![],
+            trash_state: TrashState::default(),
+            sign_certificate_state: SignCertificateState::default(),
+            deploy_key_state: DeployKeyState::default(),
+            sign_file_state: SignFileState::default(),
+            verify_signature_state: VerifySignatureState::default(),
+            allowed_signers_state: AllowedSignersState::default(),
+            git_signing_state: GitSigningState::default(),
         };
 
         state.load_public_keys();
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use crate::utils::{files, strings};
+
+use super::public_keys_list_state::validate_key_file_name;
+
+#[derive(Clone, Default)]
+pub struct RenameKeyState {
+    /// Name of the private key being renamed
+    old_key_name: String,
+    /// New name of the private key in the SSH directory, or its new path
+    new_name: String,
+}
+
+impl RenameKeyState {
+    pub fn start(&mut self, old_key_name: &str) {
+        *self = Self {
+            old_key_name: old_key_name.to_owned(),
+            new_name: old_key_name.to_owned(),
+        };
+    }
+
+    pub fn get_old_key_name(&self) -> &str {
+        self.old_key_name.as_str()
+    }
+
+    pub fn get_new_name(&self) -> &str {
+        self.new_name.as_str()
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        self.new_name.push(ch);
+    }
+
+    pub fn del_char(&mut self) {
+        self.new_name.pop();
+    }
+
+    pub fn del_word(&mut self) {
+        self.new_name = strings::del_last_word(self.new_name.to_owned());
+    }
+
+    /// Returns the path the private key is going to be moved to, or why the new name isn't valid.
+    /// Names and relative paths are taken as relative to the SSH directory
+    pub fn get_new_private_key_path(&self) -> Result<PathBuf, String> {
+        let new_name = self.new_name.trim();
+        if new_name.is_empty() {
+            return Err(String::from("the new name can't be empty"));
+        }
+        if new_name.ends_with('/') {
+            return Err(String::from(
+                "the new name must include the file name of the key",
+            ));
+        }
+
+        let ssh_dir = files::get_user_ssh_dir().map_err(|err| err.to_string())?;
+        let new_path = match new_name.starts_with("~/") || new_name.starts_with('/') {
+            true => files::expand_home(new_name),
+            false => ssh_dir.join(new_name),
+        };
+
+        let file_name = new_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        validate_key_file_name(&file_name)?;
+        if new_path == ssh_dir.join(&self.old_key_name) {
+            return Err(String::from("the key pair already has this name"));
+        }
+
+        Ok(new_path)
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use crate::{commands::ssh_keygen::PublicKeyType, utils::strings};
+
+use super::public_keys_list_state::validate_key_file_name;
+
+/// What to do with the old key once its replacement is generated
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum OldKeyAction {
+    /// Leave the old key in place until the rotation is confirmed
+    #[default]
+    KeepUntilConfirmed,
+    /// Move the old key to ~/.ssh/archive
+    Archive,
+    Delete,
+}
+
+impl OldKeyAction {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::KeepUntilConfirmed => Self::Archive,
+            Self::Archive => Self::Delete,
+            Self::Delete => Self::KeepUntilConfirmed,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            Self::KeepUntilConfirmed => Self::Delete,
+            Self::Archive => Self::KeepUntilConfirmed,
+            Self::Delete => Self::Archive,
+        }
+    }
+}
+
+impl Display for OldKeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepUntilConfirmed => write!(f, "keep until confirmed"),
+            Self::Archive => write!(f, "archive"),
+            Self::Delete => write!(f, "delete"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum RotateKeyFocus {
+    #[default]
+    Name,
+    KeyType,
+    Comment,
+    OldKeyAction,
+}
+
+/// State of the key rotation wizard, which replaces the selected key pair with a new one
+#[derive(Clone, Default)]
+pub struct RotateKeyState {
+    /// Name of the private key being replaced
+    old_key_name: String,
+    name: String,
+    key_type: PublicKeyType,
+    comment: String,
+    old_key_action: OldKeyAction,
+    current_focus: RotateKeyFocus,
+}
+
+impl RotateKeyState {
+    /// Starts the rotation of the given key pair, using its name and comment as the initial
+    /// values of the replacement key
+    pub fn start(&mut self, old_key_name: &str, comment: &str) {
+        *self = Self {
+            old_key_name: old_key_name.to_owned(),
+            name: format!("{}_new", old_key_name),
+            comment: comment.to_owned(),
+            ..Self::default()
+        };
+    }
+
+    pub fn get_old_key_name(&self) -> &str {
+        self.old_key_name.as_str()
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_type(&self) -> PublicKeyType {
+        self.key_type
+    }
+
+    pub fn get_comment(&self) -> &str {
+        self.comment.as_str()
+    }
+
+    pub fn get_old_key_action(&self) -> OldKeyAction {
+        self.old_key_action
+    }
+
+    pub fn has_focus_on(&self, possible_focus: RotateKeyFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            RotateKeyFocus::Name => RotateKeyFocus::KeyType,
+            RotateKeyFocus::KeyType => RotateKeyFocus::Comment,
+            RotateKeyFocus::Comment => RotateKeyFocus::OldKeyAction,
+            RotateKeyFocus::OldKeyAction => RotateKeyFocus::Name,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            RotateKeyFocus::Name => RotateKeyFocus::OldKeyAction,
+            RotateKeyFocus::KeyType => RotateKeyFocus::Name,
+            RotateKeyFocus::Comment => RotateKeyFocus::KeyType,
+            RotateKeyFocus::OldKeyAction => RotateKeyFocus::Comment,
+        }
+    }
+
+    pub fn next_option(&mut self) {
+        match self.current_focus {
+            RotateKeyFocus::KeyType => self.key_type = self.key_type.next(),
+            RotateKeyFocus::OldKeyAction => self.old_key_action = self.old_key_action.next(),
+            _ => {}
+        }
+    }
+
+    pub fn prev_option(&mut self) {
+        match self.current_focus {
+            RotateKeyFocus::KeyType => self.key_type = self.key_type.prev(),
+            RotateKeyFocus::OldKeyAction => self.old_key_action = self.old_key_action.prev(),
+            _ => {}
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        match self.current_focus {
+            RotateKeyFocus::Name => self.name.push(ch),
+            RotateKeyFocus::Comment => self.comment.push(ch),
+            _ => {}
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        match self.current_focus {
+            RotateKeyFocus::Name => self.name.pop(),
+            RotateKeyFocus::Comment => self.comment.pop(),
+            _ => None,
+        };
+    }
+
+    pub fn del_word(&mut self) {
+        match self.current_focus {
+            RotateKeyFocus::Name => self.name = strings::del_last_word(self.name.to_owned()),
+            RotateKeyFocus::Comment => {
+                self.comment = strings::del_last_word(self.comment.to_owned())
+            }
+            _ => {}
+        }
+    }
+
+    pub fn validate_name(&self) -> Result<(), String> {
+        validate_key_file_name(&self.name)?;
+        if self.name == self.old_key_name {
+            return Err(String::from(
+                "the new key must have a different name than the old one",
+            ));
+        }
+
+        Ok(())
+    }
+}
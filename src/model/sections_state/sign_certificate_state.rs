@@ -0,0 +1,183 @@
+use crate::{
+    keys::certificate::{self, UserCertRequest},
+    utils::{files, strings},
+};
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum SignCertificateFocus {
+    #[default]
+    CaKey,
+    KeyId,
+    Principals,
+    Validity,
+    Serial,
+    Options,
+}
+
+/// State of the form used to issue a user certificate for the selected public key
+#[derive(Clone, Default)]
+pub struct SignCertificateState {
+    /// Name of the public key file being certified
+    public_key_name: String,
+    /// Names of the key pairs that can be used as the CA
+    ca_keys: Vec<String>,
+    ca_key_idx: usize,
+    key_id: String,
+    /// Comma separated list of principals
+    principals: String,
+    validity: String,
+    serial: String,
+    options: String,
+    current_focus: SignCertificateFocus,
+}
+
+impl SignCertificateState {
+    /// Starts the form for the given public key, using its name as the initial key ID
+    pub fn start(&mut self, public_key_name: &str, ca_keys: Vec<String>) {
+        *self = Self {
+            public_key_name: public_key_name.to_owned(),
+            ca_keys,
+            key_id: public_key_name.trim_end_matches(".pub").to_owned(),
+            serial: String::from("0"),
+            ..Self::default()
+        };
+    }
+
+    pub fn get_public_key_name(&self) -> &str {
+        self.public_key_name.as_str()
+    }
+
+    pub fn get_ca_key_name(&self) -> Option<&str> {
+        self.ca_keys.get(self.ca_key_idx).map(String::as_str)
+    }
+
+    pub fn get_key_id(&self) -> &str {
+        self.key_id.as_str()
+    }
+
+    pub fn get_principals(&self) -> &str {
+        self.principals.as_str()
+    }
+
+    pub fn get_validity(&self) -> &str {
+        self.validity.as_str()
+    }
+
+    pub fn get_serial(&self) -> &str {
+        self.serial.as_str()
+    }
+
+    pub fn get_options(&self) -> &str {
+        self.options.as_str()
+    }
+
+    pub fn has_focus_on(&self, possible_focus: SignCertificateFocus) -> bool {
+        self.current_focus == possible_focus
+    }
+
+    pub fn next_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            SignCertificateFocus::CaKey => SignCertificateFocus::KeyId,
+            SignCertificateFocus::KeyId => SignCertificateFocus::Principals,
+            SignCertificateFocus::Principals => SignCertificateFocus::Validity,
+            SignCertificateFocus::Validity => SignCertificateFocus::Serial,
+            SignCertificateFocus::Serial => SignCertificateFocus::Options,
+            SignCertificateFocus::Options => SignCertificateFocus::CaKey,
+        }
+    }
+
+    pub fn prev_focus(&mut self) {
+        self.current_focus = match self.current_focus {
+            SignCertificateFocus::CaKey => SignCertificateFocus::Options,
+            SignCertificateFocus::KeyId => SignCertificateFocus::CaKey,
+            SignCertificateFocus::Principals => SignCertificateFocus::KeyId,
+            SignCertificateFocus::Validity => SignCertificateFocus::Principals,
+            SignCertificateFocus::Serial => SignCertificateFocus::Validity,
+            SignCertificateFocus::Options => SignCertificateFocus::Serial,
+        }
+    }
+
+    pub fn next_option(&mut self) {
+        if self.current_focus == SignCertificateFocus::CaKey && !self.ca_keys.is_empty() {
+            self.ca_key_idx = (self.ca_key_idx + 1) % self.ca_keys.len();
+        }
+    }
+
+    pub fn prev_option(&mut self) {
+        if self.current_focus == SignCertificateFocus::CaKey && !self.ca_keys.is_empty() {
+            self.ca_key_idx = (self.ca_key_idx + self.ca_keys.len() - 1) % self.ca_keys.len();
+        }
+    }
+
+    fn focused_input(&mut self) -> Option<&mut String> {
+        match self.current_focus {
+            SignCertificateFocus::CaKey => None,
+            SignCertificateFocus::KeyId => Some(&mut self.key_id),
+            SignCertificateFocus::Principals => Some(&mut self.principals),
+            SignCertificateFocus::Validity => Some(&mut self.validity),
+            SignCertificateFocus::Serial => Some(&mut self.serial),
+            SignCertificateFocus::Options => Some(&mut self.options),
+        }
+    }
+
+    pub fn write_char(&mut self, ch: char) {
+        if let Some(input) = self.focused_input() {
+            input.push(ch);
+        }
+    }
+
+    pub fn del_char(&mut self) {
+        if let Some(input) = self.focused_input() {
+            input.pop();
+        }
+    }
+
+    pub fn del_word(&mut self) {
+        if let Some(input) = self.focused_input() {
+            *input = strings::del_last_word(input.to_owned());
+        }
+    }
+
+    /// Builds the certificate request out of the form, or returns why the form is invalid
+    pub fn get_request(&self, now: u64) -> Result<UserCertRequest, String> {
+        let ssh_dir = files::get_user_ssh_dir().map_err(|e| e.to_string())?;
+        let ca_key_name = self
+            .get_ca_key_name()
+            .ok_or_else(|| String::from("there are no private keys to use as the CA"))?;
+
+        if self.key_id.trim().is_empty() {
+            return Err(String::from("the key ID can't be empty"));
+        }
+        let principals: Vec<String> = self
+            .principals
+            .split(',')
+            .map(str::trim)
+            .filter(|principal| !principal.is_empty())
+            .map(String::from)
+            .collect();
+        if principals.is_empty() {
+            return Err(String::from("at least one principal is required"));
+        }
+        let (valid_after, valid_before) =
+            certificate::parse_validity_interval(&self.validity, now).map_err(|e| e.to_string())?;
+        let serial = self
+            .serial
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid serial number: '{}'", self.serial))?;
+        let (critical_options, extensions) =
+            certificate::parse_cert_options(&self.options).map_err(|e| e.to_string())?;
+
+        Ok(UserCertRequest {
+            ca_key_path: ssh_dir.join(ca_key_name),
+            public_key_path: ssh_dir.join(&self.public_key_name),
+            key_id: self.key_id.trim().to_owned(),
+            principals,
+            valid_after,
+            valid_before,
+            serial,
+            critical_options,
+            extensions,
+        })
+    }
+}
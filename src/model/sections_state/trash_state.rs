@@ -0,0 +1,64 @@
+use crate::utils::{files, trash::TrashedKeyPair};
+
+/// State of the popup listing the key pairs in the trash, to restore or purge them
+#[derive(Default)]
+pub struct TrashState {
+    items: Vec<TrashedKeyPair>,
+    selected_item_idx: Option<usize>,
+    /// Why the trash directory couldn't be read
+    error: Option<String>,
+}
+
+impl TrashState {
+    pub fn load_items(&mut self) {
+        match files::get_trashed_key_pairs() {
+            Ok(items) => {
+                self.items = items;
+                self.error = None;
+            }
+            Err(err) => {
+                self.items = vec![];
+                self.error = Some(err.to_string());
+            }
+        }
+
+        self.selected_item_idx = match self.selected_item_idx {
+            _ if self.items.is_empty() => None,
+            Some(idx) if idx >= self.items.len() => Some(self.items.len() - 1),
+            Some(idx) => Some(idx),
+            None => Some(0),
+        };
+    }
+
+    pub fn get_items(&self) -> &[TrashedKeyPair] {
+        &self.items
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn get_selected_item_idx(&self) -> Option<usize> {
+        self.selected_item_idx
+    }
+
+    pub fn get_selected_item(&self) -> Option<&TrashedKeyPair> {
+        self.selected_item_idx.and_then(|idx| self.items.get(idx))
+    }
+
+    pub fn next_item(&mut self) {
+        if let Some(idx) = self.selected_item_idx {
+            if idx + 1 < self.items.len() {
+                self.selected_item_idx = Some(idx + 1);
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        if let Some(idx) = self.selected_item_idx {
+            if idx > 0 {
+                self.selected_item_idx = Some(idx - 1);
+            }
+        }
+    }
+}
@@ -6,7 +6,9 @@ use ratatui::{
 pub struct AppLayout {
     pub known_hosts_list: Rect,
     pub public_keys_list: Rect,
+    pub agent: Rect,
     pub client_config: Rect,
+    pub authorized_keys: Rect,
     pub main_footer: Rect,
 }
 
@@ -17,12 +19,16 @@ impl AppLayout {
         let main_footer_rect = AppLayout::calc_main_footer_rect(&frame_rect);
         let known_hosts_list_rect = AppLayout::calc_known_hosts_list_rect(&frame_rect);
         let public_keys_list_rect = AppLayout::calc_public_keys_list_rect(&frame_rect);
+        let agent_rect = AppLayout::calc_agent_rect(&frame_rect);
         let client_config_rect = AppLayout::calc_client_config_rect(&frame_rect);
+        let authorized_keys_rect = AppLayout::calc_authorized_keys_rect(&frame_rect);
 
         AppLayout {
             known_hosts_list: known_hosts_list_rect,
             public_keys_list: public_keys_list_rect,
+            agent: agent_rect,
             client_config: client_config_rect,
+            authorized_keys: authorized_keys_rect,
             main_footer: main_footer_rect,
         }
     }
@@ -37,36 +43,79 @@ impl AppLayout {
     fn calc_known_hosts_list_rect(rect: &Rect) -> Rect {
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(33)])
+            .constraints([Constraint::Percentage(50)])
             .split(*rect);
 
         Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50), Constraint::Length(3)])
+            .constraints(Self::left_column_constraints())
             .split(columns[0])[0]
     }
 
     fn calc_public_keys_list_rect(rect: &Rect) -> Rect {
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(33)])
+            .constraints([Constraint::Percentage(50)])
             .split(*rect);
 
         Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50), Constraint::Length(3)])
+            .constraints(Self::left_column_constraints())
             .split(columns[0])[1]
     }
 
+    fn calc_agent_rect(rect: &Rect) -> Rect {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50)])
+            .split(*rect);
+
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(Self::left_column_constraints())
+            .split(columns[0])[2]
+    }
+
+    /// Known hosts, public keys, agent and footer
+    fn left_column_constraints() -> [Constraint; 4] {
+        [
+            Constraint::Percentage(35),
+            Constraint::Percentage(40),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]
+    }
+
     fn calc_client_config_rect(rect: &Rect) -> Rect {
         let columns = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(33), Constraint::Fill(1)])
+            .constraints([Constraint::Percentage(50), Constraint::Fill(1)])
             .split(*rect);
 
         Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(100), Constraint::Length(3)])
+            .constraints(Self::right_column_constraints())
             .split(columns[1])[0]
     }
+
+    fn calc_authorized_keys_rect(rect: &Rect) -> Rect {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Fill(1)])
+            .split(*rect);
+
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(Self::right_column_constraints())
+            .split(columns[1])[1]
+    }
+
+    /// Client config, authorized keys and footer
+    fn right_column_constraints() -> [Constraint; 3] {
+        [
+            Constraint::Percentage(65),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]
+    }
 }
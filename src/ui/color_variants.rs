@@ -1,3 +1,4 @@
+use ratatui::style::Color;
 use serde::Serialize;
 
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
@@ -5,3 +6,12 @@ pub enum ColorVariant {
     Success,
     Danger
 }
+
+impl From<ColorVariant> for Color {
+    fn from(variant: ColorVariant) -> Self {
+        match variant {
+            ColorVariant::Success => Color::Green,
+            ColorVariant::Danger => Color::Red,
+        }
+    }
+}
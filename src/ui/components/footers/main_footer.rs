@@ -5,13 +5,15 @@ use crate::{
     ui::{components::sections::Section, widgets::blocks},
 };
 
-const TOOLTIPS: [&str; 6] = [
-    "(q) quit | (→) next section | (←) previous section | (↑|↓) navigate section",
-    "(q) quit | (c) copy public key | (→) next section | (←) previous section | (↑|↓) navigate section",
+const TOOLTIPS: [&str; 8] = [
+    "(q) quit | (m) host CAs & revoked keys | (x) revoke a host key | (→) next section | (←) previous section | (↑|↓) navigate section",
+    "(q) quit | (c) copy public key | (n) new key pair | (p) change passphrase | (e) edit comment | (r) rotate | (m) rename/move | (A) add to agent | (s) sign certificate | (u) deploy to host | (W) weak key audit | (a) audit permissions | (f) sign file | (v) verify signature | (S) allowed signers | (G) git signing | (x) export | (i) import | (g) regenerate .pub | (d) delete | (T) trash | (→) next section | (←) previous section | (↑|↓) navigate section",
     "(q) quit | (Enter) interactive mode",
     "(q) quit interactive mode | (ctrl+s) write to file | (h|j|k|l) navigate | (i) insert mode | (v) visual mode | (y) yank",
     "(Esc) normal mode | (h|j|k|l) navigate | (y) yank",
     "(Esc) normal mode",
+    "(q) quit | (d) remove identity | (D) remove all identities | (R) refresh | (→) next section | (←) previous section | (↑|↓) navigate section",
+    "(q) quit | (n) authorize key | (e) edit options | (d) remove | (R) refresh | (←) previous section | (↑|↓) navigate section",
 ];
 
 pub fn draw_footer(f: &mut Frame, rect: &Rect, model: &Model) {
@@ -25,6 +27,8 @@ pub fn draw_footer(f: &mut Frame, rect: &Rect, model: &Model) {
             _ => TOOLTIPS[5],
         },
         Section::PublicKeysList => TOOLTIPS[1],
+        Section::Agent => TOOLTIPS[6],
+        Section::AuthorizedKeys => TOOLTIPS[7],
         _ => TOOLTIPS[0],
     };
 
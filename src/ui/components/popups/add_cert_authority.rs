@@ -0,0 +1,48 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{sections_state::known_hosts_list_state::NewCertAuthorityFocus, Model},
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_add_cert_authority_popup(f: &mut Frame, model: &Model) {
+    let new_ca_state = model
+        .get_sections_state()
+        .get_known_hosts_list_state()
+        .get_new_cert_authority_state();
+
+    let popup_block = popups::basic_popup_block("Trust host CA (@cert-authority)").title_bottom(
+        Line::from("Press ⏎ to confirm || It'll be added to ~/.ssh/known_hosts").right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .margin(2)
+        .split(area);
+
+    let pub_key_input = text_input::text_input(
+        "CA public key file",
+        new_ca_state.get_pub_key_path(),
+        new_ca_state.has_focus_on(NewCertAuthorityFocus::PubKeyPath),
+    );
+    f.render_widget(pub_key_input, inputs_areas[0]);
+
+    let host_pattern_input = text_input::text_input(
+        "Host pattern (e.g. *.corp.example)",
+        new_ca_state.get_host_pattern(),
+        new_ca_state.has_focus_on(NewCertAuthorityFocus::HostPattern),
+    );
+    f.render_widget(host_pattern_input, inputs_areas[1]);
+}
@@ -0,0 +1,102 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::{
+        sections_state::agent_state::{AddToAgentFocus, AddToAgentState},
+        Model,
+    },
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+const BOTTOM_TITLE: &str = "Press ⏎ to confirm || Lifetime examples: 600, 30m, 1h30m";
+
+pub fn draw_add_to_agent_popup(f: &mut Frame, model: &Model) {
+    let key_name = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_selected_key_name()
+        .unwrap_or_default();
+    let add_to_agent_state = model
+        .get_sections_state()
+        .get_agent_state()
+        .get_add_to_agent_state();
+
+    let title = format!("Add '{}' to the agent", key_name);
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from(BOTTOM_TITLE).right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let form_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6)])
+        .margin(2)
+        .split(area)[0];
+
+    draw_add_to_agent_form(f, form_area, add_to_agent_state);
+}
+
+/// Draws the output of the key generation, followed by the form used to add the new key to the
+/// agent
+pub fn draw_add_new_key_to_agent_popup(f: &mut Frame, model: &Model, keygen_output: &str) {
+    let add_to_agent_state = model
+        .get_sections_state()
+        .get_agent_state()
+        .get_add_to_agent_state();
+
+    let popup_block = popups::basic_popup_block("Add the new key to the agent?").title_bottom(
+        Line::from(format!("{} || (Esc) skip", BOTTOM_TITLE)).right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, f_area.height.min(30), f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(6)])
+        .margin(2)
+        .split(area);
+
+    let output = Paragraph::new(keygen_output.trim()).wrap(Wrap { trim: false });
+    f.render_widget(output, areas[0]);
+
+    draw_add_to_agent_form(f, areas[1], add_to_agent_state);
+}
+
+fn draw_add_to_agent_form(f: &mut Frame, area: Rect, add_to_agent_state: &AddToAgentState) {
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .split(area);
+
+    let lifetime_input = text_input::text_input(
+        "Lifetime (empty for no limit)",
+        add_to_agent_state.get_lifetime(),
+        add_to_agent_state.has_focus_on(AddToAgentFocus::Lifetime),
+    );
+    f.render_widget(lifetime_input, inputs_areas[0]);
+
+    let confirm = if add_to_agent_state.get_confirm() {
+        "◀ Yes ▶"
+    } else {
+        "◀ No ▶"
+    };
+    let confirm_input = text_input::text_input(
+        "Confirm each use (←|→)",
+        confirm,
+        add_to_agent_state.has_focus_on(AddToAgentFocus::Confirm),
+    );
+    f.render_widget(confirm_input, inputs_areas[1]);
+}
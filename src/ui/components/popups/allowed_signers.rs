@@ -0,0 +1,230 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Clear, List, ListDirection, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::{
+        sections_state::allowed_signers_state::{AllowedSignerFocus, GitSigningFocus},
+        Model,
+    },
+    ui::ui_utils::{centered_rect_for_paragraph, centered_rect_px, popups, styles, text_input},
+    utils::dates,
+};
+
+/// Draws the entries of the allowed_signers file. The ones that are no longer valid are shown in
+/// the danger color
+pub fn draw_allowed_signers(f: &mut Frame, model: &Model) {
+    let allowed_signers_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_allowed_signers_state();
+
+    let title = format!(
+        "Allowed signers ({})",
+        allowed_signers_state.get_path().to_string_lossy()
+    );
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("(a) add | (e) edit | (d) remove | (Esc) close").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    if let Some(error) = allowed_signers_state.get_error() {
+        let paragraph = Paragraph::new(error.to_owned())
+            .style(styles::fg_danger())
+            .block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let entries = allowed_signers_state.get_items();
+    if entries.is_empty() {
+        let paragraph = Paragraph::new("No allowed signers yet").block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let now = dates::now();
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            let principals_span = if entry.is_valid_at(now) {
+                Span::raw(entry.principals.clone())
+            } else {
+                Span::styled(entry.principals.clone(), styles::fg_danger())
+            };
+
+            ListItem::new(Line::from(vec![
+                principals_span,
+                Span::raw(format!(
+                    " {} {} {}",
+                    entry.options_string(),
+                    entry.fingerprint().unwrap_or(entry.key_type.clone()),
+                    entry.comment.clone().unwrap_or_default()
+                )),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .direction(ListDirection::TopToBottom)
+        .highlight_style(styles::highlighted_item())
+        .block(popup_block);
+
+    let mut list_state = ListState::default();
+    list_state.select(allowed_signers_state.get_selected_item_idx());
+
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+pub fn draw_allowed_signer_popup(f: &mut Frame, model: &Model) {
+    let form_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_allowed_signers_state()
+        .get_form_state();
+
+    let (title, key_label) = match form_state.get_editing() {
+        Some(entry) => (
+            format!("Edit the signer '{}'", entry.principals),
+            String::from("Public key"),
+        ),
+        None => match form_state.get_local_key_name() {
+            Some(local_key_name) => (
+                String::from("Allow a signer"),
+                format!(
+                    "Public key: {} (←|→ local keys, or paste one)",
+                    local_key_name
+                ),
+            ),
+            None => (
+                String::from("Allow a signer"),
+                String::from("Public key (←|→ local keys, or paste one)"),
+            ),
+        },
+    };
+
+    let popup_block = popups::basic_popup_block(&title).title_bottom(
+        Line::from("Press ⏎ to confirm || Dates are YYYYMMDD[HHMM[SS]], in UTC").right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, 19, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3); 5])
+        .margin(2)
+        .split(area);
+
+    let inputs = [
+        (
+            "Principals (e.g. user@example.com,*@example.org)",
+            form_state.get_principals(),
+            AllowedSignerFocus::Principals,
+        ),
+        (
+            key_label.as_str(),
+            form_state.get_key(),
+            AllowedSignerFocus::Key,
+        ),
+        (
+            "Namespaces (e.g. git,file. Empty for any)",
+            form_state.get_namespaces(),
+            AllowedSignerFocus::Namespaces,
+        ),
+        (
+            "Valid after (optional)",
+            form_state.get_valid_after(),
+            AllowedSignerFocus::ValidAfter,
+        ),
+        (
+            "Valid before (optional)",
+            form_state.get_valid_before(),
+            AllowedSignerFocus::ValidBefore,
+        ),
+    ];
+    for (idx, (label, value, focus)) in inputs.into_iter().enumerate() {
+        let input = text_input::text_input(label, value, form_state.has_focus_on(focus));
+        f.render_widget(input, inputs_areas[idx]);
+    }
+}
+
+pub fn draw_prompt_remove_allowed_signer_confirmation(f: &mut Frame, model: &Model) {
+    let Some(entry) = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_allowed_signers_state()
+        .get_selected_entry()
+    else {
+        return;
+    };
+
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let paragraph_content = format!(
+        "Remove this signer from the allowed_signers file?\n{} {}\nIts signatures won't be trusted anymore",
+        entry.principals,
+        entry.fingerprint().unwrap_or(entry.key_type.clone()),
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn draw_git_signing_popup(f: &mut Frame, model: &Model) {
+    let git_signing_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_git_signing_state();
+
+    let title = format!(
+        "Sign git commits and tags with '{}'",
+        git_signing_state.get_public_key_name()
+    );
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("Press ⏎ to write it into the git config").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .margin(2)
+        .split(area);
+
+    let principal_input = text_input::text_input(
+        "Principal (the e-mail of the commits)",
+        git_signing_state.get_principal(),
+        git_signing_state.has_focus_on(GitSigningFocus::Principal),
+    );
+    f.render_widget(principal_input, inputs_areas[0]);
+
+    let add_signer = if git_signing_state.get_add_signer() {
+        "◀ Yes ▶"
+    } else {
+        "◀ No ▶"
+    };
+    let add_signer_input = text_input::text_input(
+        "Add to the allowed signers, so git can verify the signatures",
+        add_signer,
+        git_signing_state.has_focus_on(GitSigningFocus::AddSigner),
+    );
+    f.render_widget(add_signer_input, inputs_areas[1]);
+}
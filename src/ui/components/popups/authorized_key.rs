@@ -0,0 +1,70 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{sections_state::authorized_keys_state::AuthorizedKeyFocus, Model},
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+const BOTTOM_TITLE: &str =
+    "Press ⏎ to confirm || Options example: from=\"10.0.0.*\",no-pty,expiry-time=\"20301231\"";
+
+pub fn draw_authorized_key_popup(f: &mut Frame, model: &Model) {
+    let form_state = model
+        .get_sections_state()
+        .get_authorized_keys_state()
+        .get_form_state();
+
+    let (title, key_label) = match form_state.get_editing() {
+        Some(entry) => (
+            format!(
+                "Edit the options of '{}'",
+                entry.comment.as_deref().unwrap_or(entry.key_type.as_str())
+            ),
+            String::from("Public key"),
+        ),
+        None => match form_state.get_local_key_name() {
+            Some(local_key_name) => (
+                String::from("Authorize a key"),
+                format!("Public key: {} (←|→ local keys, or paste a line)", local_key_name),
+            ),
+            None => (
+                String::from("Authorize a key"),
+                String::from("Public key (←|→ local keys, or paste a line)"),
+            ),
+        },
+    };
+
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from(BOTTOM_TITLE).right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .margin(2)
+        .split(area);
+
+    let key_input = text_input::text_input(
+        &key_label,
+        form_state.get_key(),
+        form_state.has_focus_on(AuthorizedKeyFocus::Key),
+    );
+    f.render_widget(key_input, inputs_areas[0]);
+
+    let options_input = text_input::text_input(
+        "Options (comma separated)",
+        form_state.get_options(),
+        form_state.has_focus_on(AuthorizedKeyFocus::Options),
+    );
+    f.render_widget(options_input, inputs_areas[1]);
+}
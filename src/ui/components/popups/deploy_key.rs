@@ -0,0 +1,89 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_deploy_key_popup(f: &mut Frame, model: &Model) {
+    let deploy_key_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_deploy_key_state();
+
+    let title = format!(
+        "Deploy '{}' to a host",
+        deploy_key_state.get_public_key_name()
+    );
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 7, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let input_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3)])
+        .margin(2)
+        .split(area)[0];
+
+    let host = if deploy_key_state.is_host_picked() {
+        format!("◀ {} ▶", deploy_key_state.get_host())
+    } else {
+        deploy_key_state.get_host().to_owned()
+    };
+    let input_paragraph = text_input::text_input("Host (←|→ for the config aliases)", &host, true);
+    f.render_widget(input_paragraph, input_area);
+}
+
+/// Draws the password (or passphrase) prompt of ssh, hiding the answer
+pub fn draw_prompt_remote_secret(f: &mut Frame, model: &Model, prompt: &str) {
+    let secret_len = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_deploy_key_state()
+        .get_remote_secret_len();
+
+    let popup_block = popups::basic_popup_block(prompt)
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 7, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let input_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3)])
+        .margin(2)
+        .split(area)[0];
+
+    let input_value = "*".repeat(secret_len);
+    let input_paragraph = text_input::text_input("Password", &input_value, true);
+    f.render_widget(input_paragraph, input_area);
+}
+
+/// Draws the prompt ssh shows when the host key isn't known yet, with its fingerprint
+pub fn draw_prompt_deploy_host_key(f: &mut Frame, prompt: &str) {
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to trust the host || (Esc) cancel").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, f_area.height.min(12), f_area);
+
+    let paragraph = Paragraph::new(prompt.trim())
+        .wrap(Wrap { trim: false })
+        .block(popup_block);
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
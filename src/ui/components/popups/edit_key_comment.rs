@@ -0,0 +1,40 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_edit_key_comment(f: &mut Frame, model: &Model) {
+    let keys_state = model.get_sections_state().get_public_keys_list_state();
+    // This should panic if None because this draw MUST NOT be called if the conditions are not
+    // met
+    let key_name = keys_state
+        .get_selected_key_name()
+        .expect("must provide a key pair to edit its comment");
+
+    let title = format!("Edit comment of '{}'", key_name);
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 7, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let input_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3)])
+        .margin(2)
+        .split(area)[0];
+
+    let input_paragraph =
+        text_input::text_input("Comment (-C)", keys_state.get_edited_comment(), true);
+    f.render_widget(input_paragraph, input_area);
+}
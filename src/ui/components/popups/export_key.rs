@@ -0,0 +1,46 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_export_key_popup(f: &mut Frame, model: &Model) {
+    let keys_state = model.get_sections_state().get_public_keys_list_state();
+    let key_name = keys_state
+        .get_selected_item()
+        .map(|item| item.file_name.as_str())
+        .unwrap_or_default();
+    let format = keys_state.get_export_format();
+
+    let title = format!("Export '{}'", key_name);
+    let popup_block = popups::basic_popup_block(&title).title_bottom(
+        Line::from(format!(
+            "Press ⏎ to confirm || It'll be stored at ~/.ssh/{}.{}",
+            key_name,
+            format.extension()
+        ))
+        .right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 7, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let input_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3)])
+        .margin(2)
+        .split(area)[0];
+
+    let format_value = format!("◀ {} ▶", format);
+    let format_input = text_input::text_input("Format (←|→)", &format_value, true);
+    f.render_widget(format_input, input_area);
+}
@@ -0,0 +1,111 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{
+        sections_state::file_signature_state::{SignFileFocus, VerifySignatureFocus},
+        Model,
+    },
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_sign_file_popup(f: &mut Frame, model: &Model) {
+    let sign_file_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_sign_file_state();
+
+    let title = format!(
+        "Sign a file with '{}'",
+        sign_file_state.get_private_key_name()
+    );
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("Press ⏎ to sign").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3); 2])
+        .margin(2)
+        .split(area);
+
+    let file_input = text_input::text_input(
+        "File to sign",
+        sign_file_state.get_file_path(),
+        sign_file_state.has_focus_on(SignFileFocus::File),
+    );
+    f.render_widget(file_input, inputs_areas[0]);
+
+    let namespace_input = text_input::text_input(
+        "Namespace (e.g. file, git)",
+        sign_file_state.get_namespace(),
+        sign_file_state.has_focus_on(SignFileFocus::Namespace),
+    );
+    f.render_widget(namespace_input, inputs_areas[1]);
+}
+
+pub fn draw_verify_signature_popup(f: &mut Frame, model: &Model) {
+    let verify_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_verify_signature_state();
+
+    let popup_block = popups::basic_popup_block("Verify the signature of a file")
+        .title_bottom(Line::from("Press ⏎ to verify").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 19, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3); 5])
+        .margin(2)
+        .split(area);
+
+    let file_input = text_input::text_input(
+        "Signed file",
+        verify_state.get_file_path(),
+        verify_state.has_focus_on(VerifySignatureFocus::File),
+    );
+    f.render_widget(file_input, inputs_areas[0]);
+
+    let signature_input = text_input::text_input(
+        "Signature (empty for <file>.sig)",
+        verify_state.get_signature_path(),
+        verify_state.has_focus_on(VerifySignatureFocus::Signature),
+    );
+    f.render_widget(signature_input, inputs_areas[1]);
+
+    let allowed_signers_input = text_input::text_input(
+        "Allowed signers file",
+        verify_state.get_allowed_signers_path(),
+        verify_state.has_focus_on(VerifySignatureFocus::AllowedSigners),
+    );
+    f.render_widget(allowed_signers_input, inputs_areas[2]);
+
+    let identity_input = text_input::text_input(
+        "Signer identity (e.g. user@example.com)",
+        verify_state.get_identity(),
+        verify_state.has_focus_on(VerifySignatureFocus::Identity),
+    );
+    f.render_widget(identity_input, inputs_areas[3]);
+
+    let namespace_input = text_input::text_input(
+        "Namespace",
+        verify_state.get_namespace(),
+        verify_state.has_focus_on(VerifySignatureFocus::Namespace),
+    );
+    f.render_widget(namespace_input, inputs_areas[4]);
+}
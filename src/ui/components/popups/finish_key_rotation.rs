@@ -0,0 +1,31 @@
+use ratatui::{
+    text::Line,
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::model::Model;
+use crate::ui::ui_utils::{centered_rect_for_paragraph, popups};
+
+pub fn draw_finish_key_rotation(f: &mut Frame, model: &Model, summary: &str) {
+    let old_key_name = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_rotate_key_state()
+        .get_old_key_name();
+
+    let popup_block = popups::warning_popup_block().title_bottom(
+        Line::from("(a) archive old key | (d) trash old key | (Esc) keep it").right_aligned(),
+    );
+
+    let paragraph_content = format!(
+        "{}\n\nOnce the new key is deployed, '{}' can be archived or moved to the trash",
+        summary, old_key_name
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
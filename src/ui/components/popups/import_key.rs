@@ -0,0 +1,49 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{sections_state::import_key_state::ImportKeyFocus, Model},
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_import_key_popup(f: &mut Frame, model: &Model) {
+    let import_key_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_import_key_state();
+
+    let popup_block = popups::basic_popup_block("Import key (OpenSSH, PEM, PKCS#8, RFC4716)")
+        .title_bottom(
+            Line::from("Press ⏎ to confirm || It'll be stored at ~/.ssh").right_aligned(),
+        );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 10, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(3)])
+        .margin(2)
+        .split(area);
+
+    let source_path_input = text_input::text_input(
+        "Key file",
+        import_key_state.get_source_path(),
+        import_key_state.has_focus_on(ImportKeyFocus::SourcePath),
+    );
+    f.render_widget(source_path_input, inputs_areas[0]);
+
+    let name_input = text_input::text_input(
+        "Filename",
+        import_key_state.get_name(),
+        import_key_state.has_focus_on(ImportKeyFocus::Name),
+    );
+    f.render_widget(name_input, inputs_areas[1]);
+}
@@ -0,0 +1,66 @@
+use ratatui::{
+    layout::Constraint,
+    style::Stylize,
+    text::Line,
+    widgets::{Cell, Clear, Row, Table},
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, styles},
+};
+
+const HEADER: [&str; 4] = ["Key", "Type", "Bits", "Issues"];
+
+/// Draws the keys flagged by the weak and legacy keys audit, with every reason they were flagged
+/// for
+pub fn draw_key_audit(f: &mut Frame, model: &Model) {
+    let popup_block = popups::basic_popup_block("Weak and legacy keys")
+        .title_bottom(Line::from("(Esc) close").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    let rows: Vec<Row> = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_items()
+        .iter()
+        .filter(|key| !key.issues.is_empty())
+        .map(|key| {
+            Row::new(vec![
+                Cell::from(key.file_name.trim_end_matches(".pub")),
+                Cell::from(key.algorithm.clone().unwrap_or(String::from("?"))),
+                Cell::from(
+                    key.bits
+                        .map(|bits| bits.to_string())
+                        .unwrap_or(String::from("?")),
+                ),
+                Cell::from(
+                    key.issues
+                        .iter()
+                        .map(|issue| issue.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                )
+                .style(styles::fg_warning()),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Min(15),
+        Constraint::Length(10),
+        Constraint::Length(5),
+        Constraint::Fill(2),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(HEADER).bold())
+        .block(popup_block);
+
+    f.render_widget(table, area);
+}
@@ -0,0 +1,63 @@
+use ratatui::{
+    text::{Line, Span},
+    widgets::{Clear, List, ListDirection, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, styles},
+    utils::known_hosts::KnownHostsMarker,
+};
+
+pub fn draw_known_hosts_markers(f: &mut Frame, model: &Model) {
+    let known_hosts_state = model.get_sections_state().get_known_hosts_list_state();
+
+    let popup_block = popups::basic_popup_block("Trusted host CAs and revoked keys")
+        .title_bottom(Line::from("(a) add CA from .pub file | (Esc) close").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    let markers = known_hosts_state.get_markers();
+    if markers.is_empty() {
+        let paragraph =
+            Paragraph::new("No @cert-authority nor @revoked lines in ~/.ssh/known_hosts")
+                .block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = markers
+        .iter()
+        .map(|entry| {
+            let marker = entry.marker.map(|m| m.as_str()).unwrap_or_default();
+            let marker_span = match entry.marker {
+                Some(KnownHostsMarker::Revoked) => Span::styled(marker, styles::fg_danger()),
+                _ => Span::raw(marker),
+            };
+
+            ListItem::new(Line::from(vec![
+                marker_span,
+                Span::raw(format!(
+                    " {} {} {}",
+                    entry.host_patterns,
+                    entry.key_type,
+                    entry.comment.clone().unwrap_or_default()
+                )),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .direction(ListDirection::TopToBottom)
+        .highlight_style(styles::highlighted_item())
+        .block(popup_block);
+
+    let mut list_state = ListState::default();
+    list_state.select(known_hosts_state.get_selected_marker_idx());
+
+    f.render_stateful_widget(list, area, &mut list_state);
+}
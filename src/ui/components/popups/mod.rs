@@ -1,4 +1,5 @@
 pub mod add_pub_key;
+pub mod allowed_signers;
 pub mod exit_prompt;
 pub mod waiting_cmd;
 pub mod error_msg;
@@ -7,8 +8,27 @@ pub mod with_cfg;
 pub mod prompt_key_overwrite;
 pub mod prompt_delete_key_pair_confirmation;
 pub mod show_pub_key_content;
+pub mod known_hosts_markers;
+pub mod add_cert_authority;
+pub mod revoke_host_key;
+pub mod edit_key_comment;
+pub mod export_key;
+pub mod file_signature;
+pub mod import_key;
+pub mod permissions_audit;
+pub mod rotate_key;
+pub mod rename_key;
+pub mod finish_key_rotation;
+pub mod add_to_agent;
+pub mod prompt_remove_agent_identities_confirmation;
+pub mod sign_certificate;
+pub mod authorized_key;
+pub mod prompt_remove_authorized_key_confirmation;
+pub mod deploy_key;
+pub mod key_audit;
+pub mod trash;
 
-use crate::ui::color_variants::ColorVariant;
+use crate::{ui::color_variants::ColorVariant, utils::permissions::PermissionIssue};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Popup {
@@ -18,8 +38,50 @@ pub enum Popup {
     ErrorMsg,
     PromptPassphrase,
     PromptReenterPassphrase,
+    PromptOldPassphrase,
     PromptKeyOverwrite,
     WithCfg(String, ColorVariant),
     PromptDeleteKeyPairConfirmation,
-    ShowPubKeyContent
+    ShowPubKeyContent,
+    KnownHostsMarkers,
+    AddCertAuthority,
+    /// Lists the keys of the selected known host, to revoke one of them
+    RevokeHostKey,
+    EditKeyComment,
+    ExportKey,
+    ImportKey,
+    /// Shows the files whose permissions are too loose for ssh
+    PermissionsAudit(Vec<PermissionIssue>),
+    RotateKey,
+    RenameKey,
+    /// Asks what to do with the old key of a rotation, showing the given summary of the rotation
+    FinishKeyRotation(String),
+    AddToAgent,
+    /// Offers to add the key pair that was just generated to the agent, showing the given output
+    /// of the key generation
+    AddNewKeyToAgent(String),
+    PromptRemoveAgentIdentityConfirmation,
+    PromptRemoveAgentIdentitiesConfirmation,
+    SignCertificate,
+    AuthorizedKey,
+    PromptRemoveAuthorizedKeyConfirmation,
+    DeployKey,
+    SignFile,
+    VerifySignature,
+    /// Lists the keys flagged by the weak and legacy keys audit
+    KeyAudit,
+    /// Lists the entries of the allowed_signers file
+    AllowedSigners,
+    AllowedSigner,
+    PromptRemoveAllowedSignerConfirmation,
+    GitSigning,
+    /// Lists the deleted key pairs, which can be restored or purged
+    Trash,
+    PromptPurgeTrashedKeyConfirmation,
+    /// Asks for the answer to the given password (or passphrase) prompt of ssh
+    PromptRemoteSecret(String),
+    /// Asks if the key of an unknown host should be trusted, showing the given prompt of ssh
+    PromptDeployHostKey(String),
+    /// Shows the given text while a job that isn't a command runs in the background
+    Loading(String),
 }
@@ -0,0 +1,49 @@
+use ratatui::{
+    layout::Constraint,
+    style::Stylize,
+    text::Line,
+    widgets::{Cell, Clear, Row, Table},
+    Frame,
+};
+
+use crate::{
+    ui::ui_utils::{centered_rect_px, popups, styles},
+    utils::permissions::PermissionIssue,
+};
+
+const HEADER: [&str; 4] = ["File", "Kind", "Mode", "Expected"];
+
+pub fn draw_permissions_audit(f: &mut Frame, issues: &[PermissionIssue]) {
+    let popup_block = popups::basic_popup_block("Loose permissions, refused by ssh")
+        .title_bottom(Line::from("Press ⏎ to fix them all | (Esc) close").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    let rows: Vec<Row> = issues
+        .iter()
+        .map(|issue| {
+            Row::new(vec![
+                Cell::from(issue.path.to_string_lossy().into_owned()),
+                Cell::from(issue.kind.to_string()),
+                Cell::from(format!("{:04o}", issue.mode)).style(styles::fg_danger()),
+                Cell::from(format!("{:04o}", issue.fixed_mode())),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(16),
+        Constraint::Length(6),
+        Constraint::Length(8),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(HEADER).bold())
+        .block(popup_block);
+
+    f.render_widget(table, area);
+}
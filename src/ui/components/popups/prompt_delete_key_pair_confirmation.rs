@@ -5,22 +5,39 @@ use ratatui::{
 };
 
 use crate::model::Model;
-use crate::ui::ui_utils::{centered_rect_for_paragraph, popups};
+use crate::ui::ui_utils::{centered_rect_for_paragraph, popups, styles};
 
 pub fn draw_prompt_delete_key_pair_confirmation(f: &mut Frame, model: &Model) {
     // This should panic if None because this draw MUST NOT be called if the conditions are not
     // met
-    let target_key_pair = model
-        .get_sections_state()
-        .get_public_keys_list_state()
+    let public_keys_list_state = model.get_sections_state().get_public_keys_list_state();
+    let target_key_pair = public_keys_list_state
         .get_selected_key_name()
         .expect("must provide a key pair to delete");
 
     let popup_block = popups::warning_popup_block()
         .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
 
-    let paragraph_content = format!("Delete '{}' key pair?", target_key_pair);
-    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+    let mut lines = vec![
+        Line::from(format!("Delete '{}' key pair?", target_key_pair)),
+        Line::from("It's moved to the trash, press 'T' to restore it"),
+    ];
+
+    let references = public_keys_list_state.get_deletion_references();
+    if !references.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "Used by these Host blocks of ~/.ssh/config:",
+            styles::fg_warning(),
+        ));
+        lines.extend(
+            references
+                .iter()
+                .map(|host| Line::from(format!("  Host {}", host))),
+        );
+    }
+
+    let paragraph = Paragraph::new(lines).block(popup_block);
 
     let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
 
@@ -0,0 +1,53 @@
+use ratatui::{
+    text::Line,
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use ssh_key::HashAlg;
+
+use crate::model::Model;
+use crate::ui::ui_utils::{centered_rect_for_paragraph, popups};
+
+pub fn draw_prompt_remove_agent_identity_confirmation(f: &mut Frame, model: &Model) {
+    let Some(identity) = model
+        .get_sections_state()
+        .get_agent_state()
+        .get_selected_identity()
+    else {
+        return;
+    };
+
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let paragraph_content = format!(
+        "Remove this identity from the agent?\n{} {}",
+        identity.fingerprint(HashAlg::Sha256),
+        identity.comment()
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn draw_prompt_remove_agent_identities_confirmation(f: &mut Frame, model: &Model) {
+    let identities_count = model.get_sections_state().get_agent_state().get_items().len();
+
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let paragraph_content = format!(
+        "Remove all the identities ({}) from the agent?",
+        identities_count
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,33 @@
+use ratatui::{
+    text::Line,
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::model::Model;
+use crate::ui::ui_utils::{centered_rect_for_paragraph, popups};
+
+pub fn draw_prompt_remove_authorized_key_confirmation(f: &mut Frame, model: &Model) {
+    let Some(entry) = model
+        .get_sections_state()
+        .get_authorized_keys_state()
+        .get_selected_entry()
+    else {
+        return;
+    };
+
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let paragraph_content = format!(
+        "Remove this key from the authorized_keys file?\n{} ({})\nIt won't be able to log in anymore",
+        entry.fingerprint().unwrap_or(entry.key_type.clone()),
+        entry.comment.as_deref().unwrap_or("no comment")
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,42 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_rename_key(f: &mut Frame, model: &Model) {
+    let rename_key_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_rename_key_state();
+
+    let title = format!("Rename or move '{}'", rename_key_state.get_old_key_name());
+    let popup_block = popups::basic_popup_block(&title).title_bottom(
+        Line::from("Press ⏎ to move the key pair and update ~/.ssh/config").right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 7, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let input_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3)])
+        .margin(2)
+        .split(area)[0];
+
+    let input_paragraph = text_input::text_input(
+        "New name, or path relative to ~/.ssh",
+        rename_key_state.get_new_name(),
+        true,
+    );
+    f.render_widget(input_paragraph, input_area);
+}
@@ -0,0 +1,61 @@
+use ratatui::{
+    text::{Line, Span},
+    widgets::{Clear, List, ListDirection, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_px, popups, styles},
+};
+
+pub fn draw_revoke_host_key(f: &mut Frame, model: &Model) {
+    let known_hosts_state = model.get_sections_state().get_known_hosts_list_state();
+    let host = known_hosts_state.get_selected_host().unwrap_or_default();
+
+    let popup_block = popups::warning_popup_block()
+        .title(format!("Revoke a key of '{}'", host))
+        .title_bottom(
+            Line::from("(⏎) mark as @revoked | (j|k) navigate | (Esc) close").right_aligned(),
+        );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    let host_keys = known_hosts_state.get_host_keys();
+    if host_keys.is_empty() {
+        let paragraph =
+            Paragraph::new("No keys left for this host in ~/.ssh/known_hosts").block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = host_keys
+        .iter()
+        .map(|host_key| {
+            let mut spans = vec![Span::raw(format!(
+                "{} {} {}",
+                host_key.key_type,
+                host_key.fingerprint().unwrap_or_default(),
+                host_key.comment.clone().unwrap_or_default()
+            ))];
+            if known_hosts_state.is_revoked(host_key) {
+                spans.push(Span::styled(" (revoked)", styles::fg_danger()));
+            }
+
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .direction(ListDirection::TopToBottom)
+        .highlight_style(styles::highlighted_item())
+        .block(popup_block);
+
+    let mut list_state = ListState::default();
+    list_state.select(known_hosts_state.get_selected_host_key_idx());
+
+    f.render_stateful_widget(list, area, &mut list_state);
+}
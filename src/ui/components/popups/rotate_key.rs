@@ -0,0 +1,71 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{sections_state::rotate_key_state::RotateKeyFocus, Model},
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+pub fn draw_rotate_key_popup(f: &mut Frame, model: &Model) {
+    let rotate_key_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_rotate_key_state();
+
+    let title = format!("Rotate '{}'", rotate_key_state.get_old_key_name());
+    let popup_block = popups::basic_popup_block(&title).title_bottom(
+        Line::from("Press ⏎ to confirm || IdentityFile lines will point at the new key")
+            .right_aligned(),
+    );
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 16, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .margin(2)
+        .split(area);
+
+    let name_input = text_input::text_input(
+        "New key filename",
+        rotate_key_state.get_name(),
+        rotate_key_state.has_focus_on(RotateKeyFocus::Name),
+    );
+    f.render_widget(name_input, inputs_areas[0]);
+
+    let key_type = format!("◀ {} ▶", rotate_key_state.get_type());
+    let key_type_input = text_input::text_input(
+        "Type (←|→)",
+        &key_type,
+        rotate_key_state.has_focus_on(RotateKeyFocus::KeyType),
+    );
+    f.render_widget(key_type_input, inputs_areas[1]);
+
+    let comment_input = text_input::text_input(
+        "Comment",
+        rotate_key_state.get_comment(),
+        rotate_key_state.has_focus_on(RotateKeyFocus::Comment),
+    );
+    f.render_widget(comment_input, inputs_areas[2]);
+
+    let old_key_action = format!("◀ {} ▶", rotate_key_state.get_old_key_action());
+    let old_key_action_input = text_input::text_input(
+        "Old key (←|→)",
+        &old_key_action,
+        rotate_key_state.has_focus_on(RotateKeyFocus::OldKeyAction),
+    );
+    f.render_widget(old_key_action_input, inputs_areas[3]);
+}
@@ -1,25 +1,62 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
     text::Line,
-    widgets::Clear,
+    widgets::{Clear, LineGauge, Paragraph},
     Frame,
 };
 
 use crate::{
-    model::Model,
-    ui::ui_utils::{centered_rect_px, popups, text_input},
+    keys::passphrase_policy::{PassphraseStrength, FULL_GAUGE_BITS},
+    model::{
+        sections_state::public_keys_list_state::{NewPublicKeyState, PassphrasePurpose},
+        Model,
+    },
+    ui::{
+        color_variants::ColorVariant,
+        ui_utils::{centered_rect_px, popups, styles, text_input},
+    },
 };
 
-pub fn draw_set_pub_key_passphrase(f: &mut Frame, model: &Model, reenter: bool) {
+/// Which one of the passphrase prompts should be drawn
+#[derive(Clone, Copy)]
+pub enum PassphrasePrompt {
+    /// Current passphrase of an existing key
+    Old,
+    New,
+    Reenter,
+}
+
+pub fn draw_set_pub_key_passphrase(f: &mut Frame, model: &Model, prompt: PassphrasePrompt) {
     let new_key_state = model
         .get_sections_state()
         .get_public_keys_list_state()
         .get_new_key_state();
 
-    let block_content = if !reenter {
-        "Enter passphrase (empty for no passphrase)"
-    } else {
-        "Enter same passphrase again"
+    let passphrase_required = new_key_state.get_passphrase_policy().required;
+    let block_content = match (prompt, new_key_state.get_passphrase_purpose()) {
+        (PassphrasePrompt::Old, PassphrasePurpose::EditComment)
+        | (PassphrasePrompt::Old, PassphrasePurpose::DerivePublicKey)
+        | (PassphrasePrompt::Old, PassphrasePurpose::AddToAgent)
+        | (PassphrasePrompt::Old, PassphrasePurpose::SignFile) => "Enter passphrase",
+        (PassphrasePrompt::Old, PassphrasePurpose::ImportKey) => {
+            "Enter passphrase of the imported key"
+        }
+        (PassphrasePrompt::Old, PassphrasePurpose::SignCertificate) => {
+            "Enter passphrase of the CA key"
+        }
+        (PassphrasePrompt::Old, _) => "Enter old passphrase",
+        (PassphrasePrompt::New, PassphrasePurpose::NewKey) if passphrase_required => {
+            "Enter passphrase"
+        }
+        (PassphrasePrompt::New, PassphrasePurpose::NewKey) => {
+            "Enter passphrase (empty for no passphrase)"
+        }
+        (PassphrasePrompt::New, _) if passphrase_required => "Enter new passphrase",
+        (PassphrasePrompt::New, _) => {
+            "Enter new passphrase (empty for no passphrase)"
+        }
+        (PassphrasePrompt::Reenter, _) => "Enter same passphrase again",
     };
 
     let popup_block = popups::basic_popup_block(block_content)
@@ -31,13 +68,78 @@ pub fn draw_set_pub_key_passphrase(f: &mut Frame, model: &Model, reenter: bool)
     f.render_widget(Clear, area);
     f.render_widget(popup_block, area);
 
-    let passphrase_len = if !reenter {
-        new_key_state.get_passphrase_len()
-    } else {
-        new_key_state.get_passphrase_check_len()
+    let passphrase_len = match prompt {
+        PassphrasePrompt::Old => new_key_state.get_old_passphrase_len(),
+        PassphrasePrompt::New => new_key_state.get_passphrase_len(),
+        PassphrasePrompt::Reenter => new_key_state.get_passphrase_check_len(),
     };
 
     draw_passphrase_input(f, area, passphrase_len);
+
+    match prompt {
+        PassphrasePrompt::Old => {}
+        PassphrasePrompt::New => draw_strength_gauge(f, area, new_key_state),
+        PassphrasePrompt::Reenter => draw_match_status(f, area, new_key_state),
+    }
+}
+
+/// Areas of the strength gauge and the status line, under the input
+fn feedback_areas(popup_area: Rect) -> (Rect, Rect) {
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .margin(2)
+        .split(popup_area);
+
+    (areas[2], areas[3])
+}
+
+/// Draws the estimated strength of the new passphrase and, if it doesn't meet the policy, why
+fn draw_strength_gauge(f: &mut Frame, popup_area: Rect, new_key_state: &NewPublicKeyState) {
+    let (gauge_area, status_area) = feedback_areas(popup_area);
+
+    let strength = new_key_state.get_passphrase_strength();
+    let entropy_bits = new_key_state.get_passphrase_entropy_bits();
+    let gauge_style = match strength {
+        PassphraseStrength::Empty | PassphraseStrength::VeryWeak | PassphraseStrength::Weak => {
+            styles::fg_danger()
+        }
+        PassphraseStrength::Reasonable => styles::fg_warning(),
+        PassphraseStrength::Strong | PassphraseStrength::VeryStrong => {
+            Style::new().fg(Color::from(ColorVariant::Success))
+        }
+    };
+    let gauge = LineGauge::default()
+        .filled_style(gauge_style)
+        .ratio((entropy_bits / FULL_GAUGE_BITS).min(1.0))
+        .label(format!("Strength: {} (~{:.0} bits)", strength, entropy_bits));
+    f.render_widget(gauge, gauge_area);
+
+    if let Err(violation) = new_key_state.check_passphrase_policy() {
+        let status = Paragraph::new(violation).style(styles::fg_danger());
+        f.render_widget(status, status_area);
+    }
+}
+
+/// Tells whether the passphrase typed again matches the new one
+fn draw_match_status(f: &mut Frame, popup_area: Rect, new_key_state: &NewPublicKeyState) {
+    let (_, status_area) = feedback_areas(popup_area);
+
+    if new_key_state.get_passphrase_check_len() == 0 {
+        return;
+    }
+
+    let status = match new_key_state.passphrases_match() {
+        true => Paragraph::new("Passphrases match")
+            .style(Style::new().fg(Color::from(ColorVariant::Success))),
+        false => Paragraph::new("Passphrases do not match").style(styles::fg_danger()),
+    };
+    f.render_widget(status, status_area);
 }
 
 fn draw_passphrase_input(f: &mut Frame, popup_area: Rect, passphrase_len: usize) {
@@ -1,32 +1,72 @@
 use ratatui::{
-    widgets::{Clear, Paragraph},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Wrap},
     Frame,
 };
+use qrcodegen::QrCode;
+use ssh_key::{certificate::OptionsMap, Certificate, HashAlg};
 
 use crate::{
+    keys::{
+        certificate::{self, CertValidity},
+        info,
+    },
     model::Model,
-    ui::ui_utils::{centered_rect_for_paragraph, popups, width_percentage_to_px},
+    ui::{
+        color_variants::ColorVariant,
+        ui_utils::{centered_rect_for_paragraph, centered_rect_px, popups, width_percentage_to_px},
+    },
+    utils::{dates, qr},
 };
 
 pub fn draw_pub_key_content(f: &mut Frame, model: &Model) {
-    let bind = model
+    let Some(bind) = model
         .get_sections_state()
         .get_public_keys_list_state()
-        .get_selected_key_path().expect("failed to get key path");
+        .get_selected_key_path()
+    else {
+        return;
+    };
 
     let key_path = bind.to_str();
 
+    if let Some(certificate) = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_selected_item()
+        .and_then(|item| item.certificate.as_ref())
+    {
+        draw_certificate_details(f, key_path, certificate);
+        return;
+    }
+
+    let qr_code_parts = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_qr_code_parts();
+    if !qr_code_parts.is_empty() {
+        let part_idx = model
+            .get_sections_state()
+            .get_public_keys_list_state()
+            .get_qr_part_idx();
+        draw_qr_code(f, key_path, qr_code_parts, part_idx);
+        return;
+    }
+
     let pub_key_content = model
         .get_sections_state()
         .get_public_keys_list_state()
-        .get_selected_key_content().expect("failed to get key content");
+        .get_selected_key_content()
+        .unwrap_or_else(|err| err.to_string());
 
     let wrapped_content = textwrap::fill(
         &pub_key_content,
         width_percentage_to_px(f.area(), 50)
     );
 
-    let popup_block = popups::rounded_block(key_path, None);
+    let popup_block = popups::rounded_block(key_path, None)
+        .title_bottom(Line::from("(r) QR code").right_aligned());
 
     let paragraph = Paragraph::new(wrapped_content).block(popup_block);
 
@@ -35,3 +75,142 @@ pub fn draw_pub_key_content(f: &mut Frame, model: &Model) {
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
+
+/// Draws one of the QR codes of the key, dark on light so phones can scan it. Keys too long for a
+/// single code are split, and their parts have to be scanned in order
+fn draw_qr_code(f: &mut Frame, key_path: Option<&str>, parts: &[QrCode], part_idx: usize) {
+    let Some(code) = parts.get(part_idx) else {
+        return;
+    };
+    let lines = qr::to_half_blocks(code);
+
+    let title = match parts.len() {
+        1 => key_path.unwrap_or_default().to_owned(),
+        parts_count => format!(
+            "{} (QR, part {}/{})",
+            key_path.unwrap_or_default(),
+            part_idx + 1,
+            parts_count
+        ),
+    };
+    let help = match parts.len() {
+        1 => "(r) text",
+        _ => "(r) text | (←|→) parts",
+    };
+    let popup_block =
+        popups::rounded_block(Some(&title), None).title_bottom(Line::from(help).right_aligned());
+
+    // Borders around the code
+    let width = lines.first().map_or(0, |line| line.chars().count()) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+
+    let f_area = f.area();
+    if width > f_area.width || height > f_area.height {
+        let paragraph = Paragraph::new(format!(
+            "The terminal is too small for the QR code, it needs {}x{}",
+            width, height
+        ))
+        .wrap(Wrap { trim: true })
+        .block(popup_block);
+        let area = centered_rect_px(f_area.width, f_area.height.min(5), f_area);
+
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let area = centered_rect_px(width, height, f_area);
+    let code_area = popup_block.inner(area);
+    let paragraph =
+        Paragraph::new(lines.join("\n")).style(Style::default().fg(Color::Black).bg(Color::White));
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+    f.render_widget(paragraph, code_area);
+}
+
+fn field<'a>(name: &'a str, value: String) -> Line<'a> {
+    Line::from(vec![Span::from(format!("{}: ", name)).bold(), Span::from(value)])
+}
+
+/// Lines listing the given options, or a single `(none)` line
+fn options_lines(options: &OptionsMap) -> Vec<Line<'_>> {
+    if options.is_empty() {
+        return vec![Line::from("        (none)")];
+    }
+
+    options
+        .iter()
+        .map(|(name, data)| match data.is_empty() {
+            true => Line::from(format!("        {}", name)),
+            false => Line::from(format!("        {} {}", name, data)),
+        })
+        .collect()
+}
+
+/// Draws the decoded fields of a certificate, like `ssh-keygen -L` does. The validity is shown in
+/// the danger color if the certificate can't be used right now
+fn draw_certificate_details(f: &mut Frame, key_path: Option<&str>, cert: &Certificate) {
+    let validity = CertValidity::of(cert, dates::now());
+    let validity_style = match validity {
+        CertValidity::Valid => Style::default(),
+        CertValidity::NotYetValid | CertValidity::Expired => {
+            Style::default().fg(Color::from(ColorVariant::Danger))
+        }
+    };
+
+    let mut lines = vec![
+        field(
+            "Type",
+            format!(
+                "{} {} certificate",
+                cert.algorithm().to_certificate_type(),
+                if cert.cert_type().is_user() { "user" } else { "host" }
+            ),
+        ),
+        field(
+            "Public key",
+            format!(
+                "{}-CERT {}",
+                info::algorithm_name(cert.public_key()),
+                cert.public_key().fingerprint(HashAlg::Sha256)
+            ),
+        ),
+        field(
+            "Signing CA",
+            format!(
+                "{} {}",
+                info::algorithm_name(cert.signature_key()),
+                cert.signature_key().fingerprint(HashAlg::Sha256)
+            ),
+        ),
+        field("Key ID", format!("\"{}\"", cert.key_id())),
+        field("Serial", cert.serial().to_string()),
+        field("Valid after", certificate::format_valid_after(cert)).style(validity_style),
+        field("Valid before", certificate::format_valid_before(cert)).style(validity_style),
+        field("Status", validity.to_string()).style(validity_style),
+        field("Principals", String::new()),
+    ];
+
+    if cert.valid_principals().is_empty() {
+        lines.push(Line::from("        (none)"));
+    }
+    lines.extend(
+        cert.valid_principals()
+            .iter()
+            .map(|principal| Line::from(format!("        {}", principal))),
+    );
+    lines.push(field("Critical options", String::new()));
+    lines.extend(options_lines(cert.critical_options()));
+    lines.push(field("Extensions", String::new()));
+    lines.extend(options_lines(cert.extensions()));
+
+    let popup_block = popups::rounded_block(key_path, None);
+
+    let paragraph = Paragraph::new(lines).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 75, 75, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,85 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::Clear,
+    Frame,
+};
+
+use crate::{
+    model::{sections_state::sign_certificate_state::SignCertificateFocus, Model},
+    ui::ui_utils::{centered_rect_px, popups, text_input},
+};
+
+const BOTTOM_TITLE: &str = "Press ⏎ to sign || Validity examples: +52w, -1d:+4w, 20250101:forever";
+
+pub fn draw_sign_certificate_popup(f: &mut Frame, model: &Model) {
+    let sign_certificate_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_sign_certificate_state();
+
+    let title = format!(
+        "Issue a certificate for '{}'",
+        sign_certificate_state.get_public_key_name()
+    );
+    let popup_block =
+        popups::basic_popup_block(&title).title_bottom(Line::from(BOTTOM_TITLE).right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width / 2, 22, f_area);
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup_block, area);
+
+    let inputs_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3); 6])
+        .margin(2)
+        .split(area);
+
+    let ca_key = match sign_certificate_state.get_ca_key_name() {
+        Some(ca_key_name) => format!("◀ {} ▶", ca_key_name),
+        None => String::from("No private keys available"),
+    };
+    let ca_key_input = text_input::text_input(
+        "CA key (←|→)",
+        &ca_key,
+        sign_certificate_state.has_focus_on(SignCertificateFocus::CaKey),
+    );
+    f.render_widget(ca_key_input, inputs_areas[0]);
+
+    let key_id_input = text_input::text_input(
+        "Key ID",
+        sign_certificate_state.get_key_id(),
+        sign_certificate_state.has_focus_on(SignCertificateFocus::KeyId),
+    );
+    f.render_widget(key_id_input, inputs_areas[1]);
+
+    let principals_input = text_input::text_input(
+        "Principals (comma separated)",
+        sign_certificate_state.get_principals(),
+        sign_certificate_state.has_focus_on(SignCertificateFocus::Principals),
+    );
+    f.render_widget(principals_input, inputs_areas[2]);
+
+    let validity_input = text_input::text_input(
+        "Validity interval (empty for forever)",
+        sign_certificate_state.get_validity(),
+        sign_certificate_state.has_focus_on(SignCertificateFocus::Validity),
+    );
+    f.render_widget(validity_input, inputs_areas[3]);
+
+    let serial_input = text_input::text_input(
+        "Serial number",
+        sign_certificate_state.get_serial(),
+        sign_certificate_state.has_focus_on(SignCertificateFocus::Serial),
+    );
+    f.render_widget(serial_input, inputs_areas[4]);
+
+    let options_input = text_input::text_input(
+        "Options (e.g. no-pty force-command=\"uptime\" source-address=10.0.0.0/8)",
+        sign_certificate_state.get_options(),
+        sign_certificate_state.has_focus_on(SignCertificateFocus::Options),
+    );
+    f.render_widget(options_input, inputs_areas[5]);
+}
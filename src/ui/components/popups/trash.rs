@@ -0,0 +1,99 @@
+use ratatui::{
+    text::{Line, Span},
+    widgets::{Clear, List, ListDirection, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::Model,
+    ui::ui_utils::{centered_rect_for_paragraph, centered_rect_px, popups, styles},
+    utils::{dates, files},
+};
+
+/// Draws the key pairs in the trash, the most recently deleted first
+pub fn draw_trash(f: &mut Frame, model: &Model) {
+    let trash_state = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_trash_state();
+
+    let title = format!(
+        "Trash ({})",
+        files::get_trash_dir().unwrap_or_default().to_string_lossy()
+    );
+    let popup_block = popups::basic_popup_block(&title)
+        .title_bottom(Line::from("(r) restore | (d) delete forever | (Esc) close").right_aligned());
+
+    let f_area = f.area();
+    let area = centered_rect_px(f_area.width * 3 / 4, f_area.height / 2, f_area);
+
+    f.render_widget(Clear, area);
+
+    if let Some(error) = trash_state.get_error() {
+        let paragraph = Paragraph::new(error.to_owned())
+            .style(styles::fg_danger())
+            .block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let trashed_key_pairs = trash_state.get_items();
+    if trashed_key_pairs.is_empty() {
+        let paragraph = Paragraph::new("The trash is empty").block(popup_block);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = trashed_key_pairs
+        .iter()
+        .map(|trashed_key_pair| {
+            ListItem::new(Line::from(vec![
+                Span::raw(trashed_key_pair.key_name.clone()),
+                Span::styled(
+                    format!(
+                        " deleted {} UTC ({})",
+                        dates::format_timestamp(trashed_key_pair.deleted_at),
+                        trashed_key_pair.file_names.join(", ")
+                    ),
+                    styles::fg_warning(),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .direction(ListDirection::TopToBottom)
+        .highlight_style(styles::highlighted_item())
+        .block(popup_block);
+
+    let mut list_state = ListState::default();
+    list_state.select(trash_state.get_selected_item_idx());
+
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+pub fn draw_prompt_purge_trashed_key_confirmation(f: &mut Frame, model: &Model) {
+    let Some(trashed_key_pair) = model
+        .get_sections_state()
+        .get_public_keys_list_state()
+        .get_trash_state()
+        .get_selected_item()
+    else {
+        return;
+    };
+
+    let popup_block = popups::warning_popup_block()
+        .title_bottom(Line::from("Press ⏎ to confirm").right_aligned());
+
+    let paragraph_content = format!(
+        "Permanently delete '{}' from the trash?\n{}\nThis can't be undone",
+        trashed_key_pair.key_name,
+        trashed_key_pair.file_names.join(", ")
+    );
+    let paragraph = Paragraph::new(paragraph_content).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
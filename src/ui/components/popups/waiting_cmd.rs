@@ -5,18 +5,19 @@ use ratatui::{
     Frame,
 };
 
-use crate::{commands::CmdTask, model::Model, ui::ui_utils::{centered_rect_for_paragraph, popups}};
+use crate::{model::Model, ui::ui_utils::{centered_rect_for_paragraph, popups}};
 
 pub fn draw_waiting_cmd(f: &mut Frame, model: &Model) {
-    let cmd_task = model.get_current_command().unwrap();
+    let Some(running_cmd) = model.get_popup_command() else {
+        return;
+    };
 
     let popup_block = popups::loading_popup_block();
 
-    // TODO
-    // Make it available for other commands xd
-    let text = match cmd_task {
-        CmdTask::SshKeygen => "Generating key..."
-    };
+    let text = format!(
+        "{}\n\n<Esc> to stop it, any other key to leave it running in the background",
+        running_cmd.waiting_text
+    );
     let styled_text = Text::styled(
         text,
         Style::default().fg(Color::Blue),
@@ -29,3 +30,16 @@ pub fn draw_waiting_cmd(f: &mut Frame, model: &Model) {
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
+
+pub fn draw_loading(f: &mut Frame, text: String) {
+    let popup_block = popups::loading_popup_block();
+
+    let styled_text = Text::styled(text, Style::default().fg(Color::Blue));
+
+    let paragraph = Paragraph::new(styled_text).block(popup_block);
+
+    let area = centered_rect_for_paragraph(&paragraph, 50, 50, f.area());
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
@@ -13,12 +13,7 @@ use crate::ui::{
 pub fn draw_popup_with_cfg(f: &mut Frame, content: String, variant: ColorVariant) {
     let popup_block = popups::loading_popup_block();
 
-    let color = match variant {
-        ColorVariant::Success => Color::Green,
-        ColorVariant::Danger => Color::Red
-    };
-
-    let styled_text = Text::styled(content, Style::default().fg(color));
+    let styled_text = Text::styled(content, Style::default().fg(Color::from(variant)));
 
     let paragraph = Paragraph::new(styled_text).block(popup_block);
 
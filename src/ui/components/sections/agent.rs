@@ -0,0 +1,85 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+use ssh_key::{HashAlg, PublicKey};
+
+use crate::{
+    keys::info::{self, PublicKeyInfo},
+    model::sections_state::agent_state::AgentState,
+    ui::ui_utils::styles,
+};
+
+const HEADER: [&str; 5] = ["Type", "Bits", "Fingerprint", "Comment", "Key file"];
+
+fn identity_row<'a>(identity: &'a PublicKey, keys: &[PublicKeyInfo]) -> Row<'a> {
+    let fingerprint = identity.fingerprint(HashAlg::Sha256).to_string();
+
+    // Identities loaded from a key pair that isn't in the ssh directory have no key file
+    let key_file = match keys
+        .iter()
+        .find(|key| key.certificate.is_none() && key.fingerprint.as_ref() == Some(&fingerprint))
+    {
+        Some(key) => Cell::from(key.file_name.trim_end_matches(".pub").to_owned()),
+        None => Cell::from("-"),
+    };
+
+    Row::new(vec![
+        Cell::from(info::algorithm_name(identity.key_data())),
+        Cell::from(
+            info::key_bits(identity.key_data())
+                .map(|b| b.to_string())
+                .unwrap_or(String::from("?")),
+        ),
+        Cell::from(fingerprint),
+        Cell::from(identity.comment()),
+        key_file,
+    ])
+}
+
+pub fn draw(f: &mut Frame, rect: &Rect, section_state: &AgentState, keys: &[PublicKeyInfo]) {
+    let mut block = Block::default()
+        .title("ssh-agent")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(Style::default());
+
+    if section_state.has_focus() {
+        block = block.border_style(styles::focused_border());
+    }
+
+    if let Some(error) = section_state.get_error() {
+        let paragraph = Paragraph::new(error)
+            .style(styles::fg_warning())
+            .wrap(Wrap { trim: true })
+            .block(block);
+        f.render_widget(paragraph, *rect);
+        return;
+    }
+
+    let rows: Vec<Row> = section_state
+        .get_items()
+        .iter()
+        .map(|identity| identity_row(identity, keys))
+        .collect();
+
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(5),
+        Constraint::Length(51),
+        Constraint::Min(10),
+        Constraint::Min(10),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(HEADER).bold())
+        .row_highlight_style(styles::highlighted_item())
+        .block(block);
+
+    let mut table_state = TableState::default();
+    table_state.select(section_state.get_selected_item_idx());
+
+    f.render_stateful_widget(table, *rect, &mut table_state);
+}
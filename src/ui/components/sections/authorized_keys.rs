@@ -0,0 +1,70 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::sections_state::authorized_keys_state::AuthorizedKeysState,
+    ui::ui_utils::styles,
+    utils::{authorized_keys::AuthorizedKeysEntry, dates},
+};
+
+const HEADER: [&str; 4] = ["Options", "Type", "Fingerprint", "Comment"];
+
+fn entry_row(entry: &AuthorizedKeysEntry) -> Row<'_> {
+    // Keys past their expiry-time are still listed, but sshd refuses them
+    let options = if entry.is_expired(dates::now()) {
+        Cell::from(format!("{} (expired)", entry.options_string())).style(styles::fg_danger())
+    } else {
+        Cell::from(entry.options_string())
+    };
+
+    Row::new(vec![
+        options,
+        Cell::from(entry.key_type.as_str()),
+        Cell::from(entry.fingerprint().unwrap_or(String::from("?"))),
+        Cell::from(entry.comment.as_deref().unwrap_or_default()),
+    ])
+}
+
+pub fn draw(f: &mut Frame, rect: &Rect, section_state: &AuthorizedKeysState) {
+    let mut block = Block::default()
+        .title("authorized_keys")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(Style::default());
+
+    if section_state.has_focus() {
+        block = block.border_style(styles::focused_border());
+    }
+
+    if let Some(error) = section_state.get_error() {
+        let paragraph = Paragraph::new(error)
+            .style(styles::fg_warning())
+            .wrap(Wrap { trim: true })
+            .block(block);
+        f.render_widget(paragraph, *rect);
+        return;
+    }
+
+    let rows: Vec<Row> = section_state.get_items().iter().map(entry_row).collect();
+
+    let widths = [
+        Constraint::Min(10),
+        Constraint::Length(20),
+        Constraint::Length(51),
+        Constraint::Min(10),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(HEADER).bold())
+        .row_highlight_style(styles::highlighted_item())
+        .block(block);
+
+    let mut table_state = TableState::default();
+    table_state.select(section_state.get_selected_item_idx());
+
+    f.render_stateful_widget(table, *rect, &mut table_state);
+}
@@ -1,3 +1,5 @@
+pub mod agent;
+pub mod authorized_keys;
 pub mod known_hosts_list;
 pub mod public_keys_list;
 pub mod client_config;
@@ -15,7 +17,9 @@ pub enum Section {
     #[default]
     KnownHostsList,
     PublicKeysList,
-    ClientConfig
+    Agent,
+    ClientConfig,
+    AuthorizedKeys,
 }
 
 impl Display for Section {
@@ -23,7 +27,9 @@ impl Display for Section {
         match self {
             Section::KnownHostsList => write!(f, "Known Hosts List"),
             Section::PublicKeysList => write!(f, "Public Keys List"),
-            Section::ClientConfig => write!(f, "SSH Client Config")
+            Section::Agent => write!(f, "SSH Agent"),
+            Section::ClientConfig => write!(f, "SSH Client Config"),
+            Section::AuthorizedKeys => write!(f, "Authorized Keys"),
         }
     }
 }
@@ -1,14 +1,97 @@
 use ratatui::{
-    layout::Rect,
-    style::Style,
-    widgets::{Block, BorderType, Borders, List, ListDirection, ListState},
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, BorderType, Borders, Cell, Row, Table, TableState},
     Frame,
 };
 
 use crate::{
-    model::sections_state::public_keys_list_state::PublicKeysListState, ui::ui_utils::styles,
+    keys::{
+        certificate::{self, CertValidity},
+        info::{PrivateKeyStatus, PublicKeyInfo},
+    },
+    model::sections_state::public_keys_list_state::PublicKeysListState,
+    ui::ui_utils::styles,
+    utils::dates,
 };
 
+const HEADER: [&str; 10] = [
+    "Name",
+    "Type",
+    "Bits",
+    "Fingerprint",
+    "Comment",
+    "Public",
+    "Private",
+    "Passphrase",
+    "Certificate",
+    "Audit",
+];
+
+fn key_row(key: &PublicKeyInfo) -> Row<'_> {
+    let unknown = String::from("?");
+
+    let public_key = if key.public_key_missing {
+        Cell::from("missing").style(styles::fg_warning())
+    } else {
+        Cell::from("yes")
+    };
+    let private_key = match key.private_key_status {
+        PrivateKeyStatus::Missing => Cell::from("missing").style(styles::fg_warning()),
+        _ => Cell::from("yes"),
+    };
+    let passphrase = match key.private_key_status {
+        PrivateKeyStatus::Encrypted => Cell::from("yes"),
+        PrivateKeyStatus::Unencrypted => Cell::from("no").style(styles::fg_warning()),
+        PrivateKeyStatus::Missing => Cell::from("-"),
+        PrivateKeyStatus::Unknown => Cell::from("?"),
+    };
+
+    // Principals and validity of certificates, as they are what decides who can use them and when
+    let certificate = match &key.certificate {
+        Some(cert) => {
+            let cell = Cell::from(format!(
+                "{} | {}",
+                cert.valid_principals().join(","),
+                certificate::format_validity(cert)
+            ));
+            match CertValidity::of(cert, dates::now()) {
+                CertValidity::Valid => cell,
+                CertValidity::NotYetValid | CertValidity::Expired => {
+                    cell.style(styles::fg_danger())
+                }
+            }
+        }
+        None => Cell::from(""),
+    };
+
+    let audit = if key.issues.is_empty() {
+        Cell::from("")
+    } else {
+        Cell::from(
+            key.issues
+                .iter()
+                .map(|issue| format!("[{}]", issue.badge()))
+                .collect::<Vec<String>>()
+                .join(" "),
+        )
+        .style(styles::fg_warning())
+    };
+
+    Row::new(vec![
+        Cell::from(key.file_name.trim_end_matches(".pub")),
+        Cell::from(key.algorithm.clone().unwrap_or(unknown.clone())),
+        Cell::from(key.bits.map(|b| b.to_string()).unwrap_or(unknown.clone())),
+        Cell::from(key.fingerprint.clone().unwrap_or(unknown)),
+        Cell::from(key.comment.as_str()),
+        public_key,
+        private_key,
+        passphrase,
+        certificate,
+        audit,
+    ])
+}
+
 pub fn draw(f: &mut Frame, rect: &Rect, section_state: &PublicKeysListState) {
     let mut block = Block::default()
         .title("Public keys")
@@ -20,14 +103,28 @@ pub fn draw(f: &mut Frame, rect: &Rect, section_state: &PublicKeysListState) {
         block = block.border_style(styles::focused_border());
     }
 
-    let list = List::default()
-        .items(section_state.get_items())
-        .direction(ListDirection::TopToBottom)
-        .highlight_style(styles::highlighted_item())
+    let rows: Vec<Row> = section_state.get_items().iter().map(key_row).collect();
+
+    let widths = [
+        Constraint::Min(10),
+        Constraint::Length(13),
+        Constraint::Length(5),
+        Constraint::Length(51),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Min(10),
+        Constraint::Min(10),
+    ];
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(HEADER).bold())
+        .row_highlight_style(styles::highlighted_item())
         .block(block);
 
-    let mut list_state = ListState::default();
-    list_state.select(section_state.get_selected_item_idx());
+    let mut table_state = TableState::default();
+    table_state.select(section_state.get_selected_item_idx());
 
-    f.render_stateful_widget(list, *rect, &mut list_state);
+    f.render_stateful_widget(table, *rect, &mut table_state);
 }
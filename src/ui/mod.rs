@@ -12,11 +12,19 @@ use crate::{model::Model, terminal::SshyTerminal};
 
 use self::components::{
     popups::{
-        add_pub_key, error_msg, exit_prompt, prompt_delete_key_pair_confirmation,
-        prompt_key_overwrite, set_pub_key_passphrase, with_cfg, show_pub_key_content, Popup,
+        add_cert_authority, add_pub_key, add_to_agent, allowed_signers, authorized_key, deploy_key,
+        edit_key_comment,
+        error_msg, exit_prompt, export_key, file_signature, finish_key_rotation, import_key, key_audit,
+        known_hosts_markers,
+        permissions_audit, prompt_delete_key_pair_confirmation, prompt_key_overwrite,
+        prompt_remove_agent_identities_confirmation, prompt_remove_authorized_key_confirmation,
+        revoke_host_key,
+        rename_key, rotate_key, set_pub_key_passphrase, show_pub_key_content, sign_certificate, trash, with_cfg,
+        Popup,
     },
-    sections::{known_hosts_list, public_keys_list, client_config, Section},
+    sections::{agent, authorized_keys, known_hosts_list, public_keys_list, client_config, Section},
 };
+use self::components::popups::set_pub_key_passphrase::PassphrasePrompt;
 
 #[derive(Clone)]
 pub enum Focus {
@@ -41,18 +49,91 @@ pub fn draw(terminal: &mut SshyTerminal, model: &Model) -> Result<()> {
             footers::main_footer::draw_footer(f, &app_layout.main_footer, model);
             known_hosts_list::draw(f, &app_layout.known_hosts_list, model.get_sections_state().get_known_hosts_list_state());
             public_keys_list::draw(f, &app_layout.public_keys_list, model.get_sections_state().get_public_keys_list_state());
+            agent::draw(
+                f,
+                &app_layout.agent,
+                model.get_sections_state().get_agent_state(),
+                model.get_sections_state().get_public_keys_list_state().get_items(),
+            );
             client_config::draw(f, &app_layout.client_config, model.get_sections_state().get_client_config_state());
+            authorized_keys::draw(f, &app_layout.authorized_keys, model.get_sections_state().get_authorized_keys_state());
             if let Some(popup) = model.get_popup() {
                 match popup {
                     Popup::ExitPrompt => exit_prompt::draw_exit_popup(f),
                     Popup::AddPubKey => add_pub_key::draw_add_pub_key_popup(f, model),
                     Popup::WaitingCmd => waiting_cmd::draw_waiting_cmd(f, model),
-                    Popup::ErrorMsg => error_msg::draw_error_msg(f, model),
-                    Popup::PromptPassphrase => {
-                        set_pub_key_passphrase::draw_set_pub_key_passphrase(f, model, false)
+                    Popup::EditKeyComment => edit_key_comment::draw_edit_key_comment(f, model),
+                    Popup::ExportKey => export_key::draw_export_key_popup(f, model),
+                    Popup::ImportKey => import_key::draw_import_key_popup(f, model),
+                    Popup::RotateKey => rotate_key::draw_rotate_key_popup(f, model),
+                    Popup::RenameKey => rename_key::draw_rename_key(f, model),
+                    Popup::FinishKeyRotation(summary) => {
+                        finish_key_rotation::draw_finish_key_rotation(f, model, &summary)
+                    }
+                    Popup::AddToAgent => add_to_agent::draw_add_to_agent_popup(f, model),
+                    Popup::AddNewKeyToAgent(keygen_output) => {
+                        add_to_agent::draw_add_new_key_to_agent_popup(f, model, &keygen_output)
+                    }
+                    Popup::PromptRemoveAgentIdentityConfirmation => {
+                        prompt_remove_agent_identities_confirmation::draw_prompt_remove_agent_identity_confirmation(f, model)
+                    }
+                    Popup::PromptRemoveAgentIdentitiesConfirmation => {
+                        prompt_remove_agent_identities_confirmation::draw_prompt_remove_agent_identities_confirmation(f, model)
+                    }
+                    Popup::AuthorizedKey => authorized_key::draw_authorized_key_popup(f, model),
+                    Popup::PromptRemoveAuthorizedKeyConfirmation => {
+                        prompt_remove_authorized_key_confirmation::draw_prompt_remove_authorized_key_confirmation(f, model)
+                    }
+                    Popup::SignCertificate => {
+                        sign_certificate::draw_sign_certificate_popup(f, model)
+                    }
+                    Popup::DeployKey => deploy_key::draw_deploy_key_popup(f, model),
+                    Popup::KeyAudit => key_audit::draw_key_audit(f, model),
+                    Popup::AllowedSigners => allowed_signers::draw_allowed_signers(f, model),
+                    Popup::AllowedSigner => {
+                        allowed_signers::draw_allowed_signer_popup(f, model)
+                    }
+                    Popup::PromptRemoveAllowedSignerConfirmation => {
+                        allowed_signers::draw_prompt_remove_allowed_signer_confirmation(f, model)
+                    }
+                    Popup::Trash => trash::draw_trash(f, model),
+                    Popup::PromptPurgeTrashedKeyConfirmation => {
+                        trash::draw_prompt_purge_trashed_key_confirmation(f, model)
                     }
+                    Popup::GitSigning => allowed_signers::draw_git_signing_popup(f, model),
+                    Popup::SignFile => file_signature::draw_sign_file_popup(f, model),
+                    Popup::VerifySignature => {
+                        file_signature::draw_verify_signature_popup(f, model)
+                    }
+                    Popup::PromptRemoteSecret(prompt) => {
+                        deploy_key::draw_prompt_remote_secret(f, model, &prompt)
+                    }
+                    Popup::PromptDeployHostKey(prompt) => {
+                        deploy_key::draw_prompt_deploy_host_key(f, &prompt)
+                    }
+                    Popup::PermissionsAudit(issues) => {
+                        permissions_audit::draw_permissions_audit(f, &issues)
+                    }
+                    Popup::Loading(text) => waiting_cmd::draw_loading(f, text),
+                    Popup::ErrorMsg => error_msg::draw_error_msg(f, model),
+                    Popup::PromptPassphrase => set_pub_key_passphrase::draw_set_pub_key_passphrase(
+                        f,
+                        model,
+                        PassphrasePrompt::New,
+                    ),
                     Popup::PromptReenterPassphrase => {
-                        set_pub_key_passphrase::draw_set_pub_key_passphrase(f, model, true)
+                        set_pub_key_passphrase::draw_set_pub_key_passphrase(
+                            f,
+                            model,
+                            PassphrasePrompt::Reenter,
+                        )
+                    }
+                    Popup::PromptOldPassphrase => {
+                        set_pub_key_passphrase::draw_set_pub_key_passphrase(
+                            f,
+                            model,
+                            PassphrasePrompt::Old,
+                        )
                     }
                     Popup::PromptKeyOverwrite => {
                         prompt_key_overwrite::draw_prompt_key_overwrite(f, model)
@@ -67,6 +148,13 @@ pub fn draw(terminal: &mut SshyTerminal, model: &Model) -> Result<()> {
                     Popup::ShowPubKeyContent => {
                         show_pub_key_content::draw_pub_key_content(f, model)
                     }
+                    Popup::KnownHostsMarkers => {
+                        known_hosts_markers::draw_known_hosts_markers(f, model)
+                    }
+                    Popup::AddCertAuthority => {
+                        add_cert_authority::draw_add_cert_authority_popup(f, model)
+                    }
+                    Popup::RevokeHostKey => revoke_host_key::draw_revoke_host_key(f, model),
                 }
             }
         })
@@ -3,7 +3,7 @@ use ratatui::widgets::{Block, BorderType, Borders};
 
 use super::styles;
 
-pub fn rounded_block(title: Option<&str>, border_style: Option<Style>) -> Block {
+pub fn rounded_block(title: Option<&str>, border_style: Option<Style>) -> Block<'_> {
     let mut block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
@@ -19,7 +19,7 @@ pub fn rounded_block(title: Option<&str>, border_style: Option<Style>) -> Block
 }
 
 /// Creates a popup block with the given title and rounded borders
-pub fn basic_popup_block(title: &str) -> Block {
+pub fn basic_popup_block(title: &str) -> Block<'_> {
     rounded_block(Some(title), Some(styles::focused_border()))
 }
 
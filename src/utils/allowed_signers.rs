@@ -0,0 +1,268 @@
+//! Parsing of the allowed_signers files used to verify signatures made with SSH keys
+//!
+//! See the ALLOWED SIGNERS section of ssh-keygen(1)
+
+use std::fmt::Display;
+
+use ssh_key::{HashAlg, PublicKey};
+
+use super::{
+    authorized_keys::{AuthorizedKeysEntry, KeyOption},
+    dates, strings,
+};
+
+/// Parses the value of a `valid-after` or `valid-before` option, which can end with `Z` (UTC, as
+/// every date here)
+fn parse_option_time(value: &str) -> Option<u64> {
+    dates::parse_timestamp(value.trim_end_matches(['Z', 'z'])).ok()
+}
+
+/// Returns true if the value can be given to the `valid-after` and `valid-before` options
+pub fn is_valid_option_time(value: &str) -> bool {
+    parse_option_time(value).is_some()
+}
+
+/// A single, non-comment line of an allowed_signers file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AllowedSignersEntry {
+    /// Comma separated list of principal patterns
+    pub principals: String,
+    pub options: Vec<KeyOption>,
+    pub key_type: String,
+    /// Base64 encoded key
+    pub key: String,
+    pub comment: Option<String>,
+}
+
+impl AllowedSignersEntry {
+    /// Parses an allowed_signers line. Returns None for comments, empty lines and lines that can't
+    /// be parsed
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (principals, rest) = match line.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => line.split_once(char::is_whitespace)?,
+        };
+
+        // Past the principals, the line has the same format as the one of authorized_keys
+        let entry = AuthorizedKeysEntry::parse(rest)?;
+
+        Some(Self {
+            principals: principals.to_owned(),
+            options: entry.options,
+            key_type: entry.key_type,
+            key: entry.key,
+            comment: entry.comment,
+        })
+    }
+
+    /// Builds an entry out of the principals, the options and the content of a public key file
+    pub fn from_public_key(
+        principals: &str,
+        options: Vec<KeyOption>,
+        public_key: &str,
+    ) -> Option<Self> {
+        let entry = AuthorizedKeysEntry::from_public_key(options, public_key)?;
+
+        Some(Self {
+            principals: principals.to_owned(),
+            options: entry.options,
+            key_type: entry.key_type,
+            key: entry.key,
+            comment: entry.comment,
+        })
+    }
+
+    /// Returns true if both entries refer to the same key, no matter the principals and options
+    pub fn same_key(&self, other: &AllowedSignersEntry) -> bool {
+        self.key_type == other.key_type && self.key == other.key
+    }
+
+    /// Options of the entry, as written in the file
+    pub fn options_string(&self) -> String {
+        self.options
+            .iter()
+            .map(|option| option.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Value of the given option, if the entry has it
+    pub fn get_option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|option| option.name.eq_ignore_ascii_case(name))
+            .and_then(|option| option.value.as_deref())
+    }
+
+    pub fn has_option(&self, name: &str) -> bool {
+        self.options
+            .iter()
+            .any(|option| option.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_cert_authority(&self) -> bool {
+        self.has_option("cert-authority")
+    }
+
+    pub fn matches_principal(&self, identity: &str) -> bool {
+        strings::match_pattern_list(&self.principals, identity)
+    }
+
+    /// Returns true if signatures made under the given namespace are accepted. Entries without the
+    /// `namespaces` option accept any namespace
+    pub fn allows_namespace(&self, namespace: &str) -> bool {
+        self.get_option("namespaces")
+            .map(|namespaces| strings::match_pattern_list(namespaces, namespace))
+            .unwrap_or(true)
+    }
+
+    /// Returns true if the entry is within its `valid-after` and `valid-before` dates at the given
+    /// time. Dates that can't be parsed make the entry invalid
+    pub fn is_valid_at(&self, now: u64) -> bool {
+        let after = self
+            .get_option("valid-after")
+            .map(|value| parse_option_time(value).is_some_and(|after| after <= now))
+            .unwrap_or(true);
+        let before = self
+            .get_option("valid-before")
+            .map(|value| parse_option_time(value).is_some_and(|before| now < before))
+            .unwrap_or(true);
+
+        after && before
+    }
+
+    pub fn public_key(&self) -> Option<PublicKey> {
+        PublicKey::from_openssh(&format!("{} {}", self.key_type, self.key)).ok()
+    }
+
+    pub fn fingerprint(&self) -> Option<String> {
+        self.public_key().map(|key| key.fingerprint(HashAlg::Sha256).to_string())
+    }
+}
+
+impl Display for AllowedSignersEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Principals with spaces have to be quoted, or they would be taken as the options
+        if self.principals.contains(char::is_whitespace) {
+            write!(f, "\"{}\" ", self.principals)?;
+        } else {
+            write!(f, "{} ", self.principals)?;
+        }
+        if !self.options.is_empty() {
+            write!(f, "{} ", self.options_string())?;
+        }
+        write!(f, "{} {}", self.key_type, self.key)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {}", comment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the content of an allowed_signers file, skipping the lines that can't be parsed
+pub fn parse_allowed_signers(content: &str) -> Vec<AllowedSignersEntry> {
+    content
+        .lines()
+        .filter_map(AllowedSignersEntry::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIN0ag5geYprTvDJEgpSZSx6XgH65iM8/otxvK8/Yzvz1";
+
+    #[test]
+    fn entries_are_parsed_and_written_back() {
+        let lines = [
+            format!("me@example.com ssh-ed25519 {}", KEY),
+            format!(
+                "*@example.com,!bot@example.com namespaces=\"git,file\" ssh-ed25519 {} me",
+                KEY
+            ),
+            format!(
+                "\"Jane Doe\" cert-authority,valid-after=\"20240101Z\" ssh-ed25519 {}",
+                KEY
+            ),
+        ];
+
+        for line in &lines {
+            let entry = AllowedSignersEntry::parse(line).unwrap();
+            assert_eq!(&entry.to_string(), line);
+        }
+
+        let entry = AllowedSignersEntry::parse(&lines[1]).unwrap();
+        assert_eq!(entry.principals, "*@example.com,!bot@example.com");
+        assert_eq!(entry.get_option("namespaces"), Some("git,file"));
+        assert_eq!(entry.comment.as_deref(), Some("me"));
+        assert!(entry.matches_principal("me@example.com"));
+        assert!(!entry.matches_principal("bot@example.com"));
+        assert!(entry.allows_namespace("git"));
+        assert!(!entry.allows_namespace("mail"));
+
+        let entry = AllowedSignersEntry::parse(&lines[2]).unwrap();
+        assert_eq!(entry.principals, "Jane Doe");
+        assert!(entry.is_cert_authority());
+    }
+
+    #[test]
+    fn comments_and_broken_lines_are_skipped() {
+        let content = format!(
+            "# team\n\nme@example.com ssh-ed25519 {}\nonly-principals\n\"unterminated ssh-ed25519 {}\n",
+            KEY, KEY
+        );
+
+        let entries = parse_allowed_signers(&content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].principals, "me@example.com");
+    }
+
+    #[test]
+    fn option_times_are_parsed() {
+        assert_eq!(parse_option_time("20240229"), Some(1709164800));
+        assert_eq!(parse_option_time("20240229Z"), Some(1709164800));
+        assert_eq!(parse_option_time("202402290130z"), Some(1709164800 + 5400));
+        assert!(is_valid_option_time("20240229000000Z"));
+        assert!(!is_valid_option_time("20230229"));
+        assert!(!is_valid_option_time("2024-02-29"));
+        assert!(!is_valid_option_time("20240229ZZ0"));
+    }
+
+    #[test]
+    fn validity_dates_bound_the_entry() {
+        let entry = AllowedSignersEntry::parse(&format!(
+            "me@example.com valid-after=20240101,valid-before=20240201Z ssh-ed25519 {}",
+            KEY
+        ))
+        .unwrap();
+        let after = parse_option_time("20240101").unwrap();
+        let before = parse_option_time("20240201").unwrap();
+
+        assert!(!entry.is_valid_at(after - 1));
+        assert!(entry.is_valid_at(after));
+        assert!(entry.is_valid_at(before - 1));
+        assert!(!entry.is_valid_at(before));
+
+        let unparseable = AllowedSignersEntry::parse(&format!(
+            "me@example.com valid-before=tomorrow ssh-ed25519 {}",
+            KEY
+        ))
+        .unwrap();
+        assert!(!unparseable.is_valid_at(0));
+
+        let unbounded =
+            AllowedSignersEntry::parse(&format!("me@example.com ssh-ed25519 {}", KEY)).unwrap();
+        assert!(unbounded.is_valid_at(0));
+        assert!(unbounded.is_valid_at(u64::MAX));
+    }
+}
@@ -0,0 +1,396 @@
+use std::fmt::Display;
+
+use color_eyre::eyre::{eyre, Result};
+use ssh_key::{HashAlg, PublicKey};
+
+use super::dates;
+
+/// Options that can prefix an authorized_keys line, and whether they take a value
+///
+/// See the AUTHORIZED_KEYS FILE FORMAT section of sshd(8)
+const KNOWN_OPTIONS: [(&str, bool); 22] = [
+    ("agent-forwarding", false),
+    ("cert-authority", false),
+    ("command", true),
+    ("environment", true),
+    ("expiry-time", true),
+    ("from", true),
+    ("no-agent-forwarding", false),
+    ("no-port-forwarding", false),
+    ("no-pty", false),
+    ("no-user-rc", false),
+    ("no-X11-forwarding", false),
+    ("no-touch-required", false),
+    ("permitlisten", true),
+    ("permitopen", true),
+    ("port-forwarding", false),
+    ("principals", true),
+    ("pty", false),
+    ("restrict", false),
+    ("tunnel", true),
+    ("user-rc", false),
+    ("verify-required", false),
+    ("X11-forwarding", false),
+];
+
+/// Key types that can start an authorized_keys line. Anything else is the start of the options
+const KEY_TYPES: [&str; 16] = [
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ssh-dss",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+    "sk-ssh-ed25519@openssh.com",
+    "ssh-ed25519-cert-v01@openssh.com",
+    "ssh-rsa-cert-v01@openssh.com",
+    "ssh-dss-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp256-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp384-cert-v01@openssh.com",
+    "ecdsa-sha2-nistp521-cert-v01@openssh.com",
+    "sk-ecdsa-sha2-nistp256-cert-v01@openssh.com",
+    "sk-ssh-ed25519-cert-v01@openssh.com",
+];
+
+/// Parses the value of an `expiry-time` option. sshd reads it in the system time zone unless it
+/// ends with `Z`, but it's always taken as UTC here
+fn parse_expiry_time(value: &str) -> Result<u64> {
+    dates::parse_timestamp(value.trim_end_matches(['Z', 'z']))
+}
+
+/// A single option of an authorized_keys line, such as `no-pty` or `from="10.0.0.0/8"`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyOption {
+    pub name: String,
+    /// Unquoted value of the option
+    pub value: Option<String>,
+}
+
+impl Display for KeyOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            None => write!(f, "{}", self.name),
+            Some(value) => write!(
+                f,
+                "{}=\"{}\"",
+                self.name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        }
+    }
+}
+
+/// Splits the options of an authorized_keys line, given without the key, into the options
+/// themselves. Values can be quoted, and quoted values can contain commas, spaces and escaped
+/// quotes
+fn split_options(value: &str) -> Result<Vec<KeyOption>> {
+    let mut options = vec![];
+    let mut chars = value.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut name = String::new();
+        while let Some(&ch) = chars.peek() {
+            if ch == ',' || ch == '=' {
+                break;
+            }
+            name.push(ch);
+            chars.next();
+        }
+
+        let value = if chars.peek() == Some(&'=') {
+            chars.next();
+            let mut value = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        None => return Err(eyre!("missing closing quote in option '{}'", name)),
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                            value.extend(chars.next())
+                        }
+                        Some(ch) => value.push(ch),
+                    }
+                }
+            } else {
+                while let Some(&ch) = chars.peek() {
+                    if ch == ',' {
+                        break;
+                    }
+                    value.push(ch);
+                    chars.next();
+                }
+            }
+            Some(value)
+        } else {
+            None
+        };
+
+        match chars.next() {
+            None | Some(',') => {}
+            Some(ch) => return Err(eyre!("unexpected '{}' after option '{}'", ch, name)),
+        }
+
+        let name = name.trim().to_owned();
+        if name.is_empty() {
+            return Err(eyre!("empty option"));
+        }
+        options.push(KeyOption { name, value });
+    }
+
+    Ok(options)
+}
+
+/// Parses the options typed by the user, checking that sshd knows them and that they have a
+/// value if, and only if, they need one
+pub fn parse_options(value: &str) -> Result<Vec<KeyOption>> {
+    let options = split_options(value)?;
+
+    for option in options.iter() {
+        let takes_value = KNOWN_OPTIONS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&option.name))
+            .map(|(_, takes_value)| *takes_value)
+            .ok_or_else(|| eyre!("unknown option '{}'", option.name))?;
+
+        match (&option.value, takes_value) {
+            (None, true) => return Err(eyre!("option '{}' needs a value", option.name)),
+            (Some(_), false) => return Err(eyre!("option '{}' doesn't take a value", option.name)),
+            (Some(value), true) if option.name.eq_ignore_ascii_case("expiry-time") => {
+                parse_expiry_time(value)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(options)
+}
+
+/// A single, non-comment line of an authorized_keys file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AuthorizedKeysEntry {
+    pub options: Vec<KeyOption>,
+    pub key_type: String,
+    /// Base64 encoded key
+    pub key: String,
+    pub comment: Option<String>,
+}
+
+impl AuthorizedKeysEntry {
+    /// Parses an authorized_keys line. Returns None for comments, empty lines and lines that
+    /// can't be parsed
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let first = line.split_whitespace().next()?;
+        if KEY_TYPES.contains(&first) {
+            return Self::from_public_key(vec![], line);
+        }
+
+        // The options end at the first whitespace that isn't quoted
+        let mut in_quotes = false;
+        let mut escaped = false;
+        let options_end = line.char_indices().find_map(|(idx, ch)| {
+            match ch {
+                '\\' if in_quotes && !escaped => {
+                    escaped = true;
+                    return None;
+                }
+                '"' if !escaped => in_quotes = !in_quotes,
+                ch if ch.is_whitespace() && !in_quotes => return Some(idx),
+                _ => {}
+            }
+            escaped = false;
+            None
+        })?;
+
+        let options = split_options(&line[..options_end]).ok()?;
+        Self::from_public_key(options, &line[options_end..])
+    }
+
+    /// Builds an entry from the content of a public key file (`<type> <base64> [comment]`)
+    pub fn from_public_key(options: Vec<KeyOption>, public_key: &str) -> Option<Self> {
+        let mut fields = public_key.split_whitespace();
+        let key_type = fields.next()?.to_owned();
+        let key = fields.next()?.to_owned();
+        let comment = fields.collect::<Vec<&str>>().join(" ");
+
+        if !KEY_TYPES.contains(&key_type.as_str()) {
+            return None;
+        }
+
+        Some(Self {
+            options,
+            key_type,
+            key,
+            comment: if comment.is_empty() {
+                None
+            } else {
+                Some(comment)
+            },
+        })
+    }
+
+    /// Returns true if both entries refer to the same key, no matter the options
+    pub fn same_key(&self, other: &AuthorizedKeysEntry) -> bool {
+        self.key_type == other.key_type && self.key == other.key
+    }
+
+    /// Options of the entry, as written in the file
+    pub fn options_string(&self) -> String {
+        self.options
+            .iter()
+            .map(|option| option.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Value of the given option, if the entry has it
+    pub fn get_option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|option| option.name.eq_ignore_ascii_case(name))
+            .and_then(|option| option.value.as_deref())
+    }
+
+    /// Returns true if the entry has an `expiry-time` that already passed
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.get_option("expiry-time")
+            .and_then(|expiry_time| parse_expiry_time(expiry_time).ok())
+            .is_some_and(|expiry_time| expiry_time <= now)
+    }
+
+    pub fn fingerprint(&self) -> Option<String> {
+        PublicKey::from_openssh(&format!("{} {}", self.key_type, self.key))
+            .ok()
+            .map(|key| key.fingerprint(HashAlg::Sha256).to_string())
+    }
+}
+
+impl Display for AuthorizedKeysEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.options.is_empty() {
+            write!(f, "{} ", self.options_string())?;
+        }
+        write!(f, "{} {}", self.key_type, self.key)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {}", comment)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+
+    fn option(name: &str, value: Option<&str>) -> KeyOption {
+        KeyOption {
+            name: name.to_owned(),
+            value: value.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn quoted_values_keep_commas_spaces_and_escaped_quotes() {
+        let options = split_options(
+            r#"no-pty,command="echo \"a, b\" \\ c",from="10.0.0.0/8,192.168.1.1",tunnel=0"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            options,
+            vec![
+                option("no-pty", None),
+                option("command", Some(r#"echo "a, b" \ c"#)),
+                option("from", Some("10.0.0.0/8,192.168.1.1")),
+                option("tunnel", Some("0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn options_are_written_back_quoted_and_escaped() {
+        let options = vec![
+            option("restrict", None),
+            option("command", Some(r#"echo "hi" \ there"#)),
+        ];
+        let written = options
+            .iter()
+            .map(|option| option.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        assert_eq!(written, r#"restrict,command="echo \"hi\" \\ there""#);
+        assert_eq!(split_options(&written).unwrap(), options);
+    }
+
+    #[test]
+    fn malformed_options_are_rejected() {
+        assert!(split_options(r#"command="echo hi"#).is_err());
+        assert!(split_options(r#"command="echo"hi"#).is_err());
+        assert!(split_options("no-pty,,no-user-rc").is_err());
+        assert!(split_options("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn options_are_checked_against_the_ones_sshd_knows() {
+        assert!(parse_options(r#"NO-PTY,Command="ls""#).is_ok());
+        assert!(parse_options("no-such-option").is_err());
+        assert!(parse_options("command").is_err());
+        assert!(parse_options("no-pty=yes").is_err());
+        assert!(parse_options("expiry-time=20300101").is_ok());
+        assert!(parse_options("expiry-time=20301301").is_err());
+    }
+
+    #[test]
+    fn lines_with_quoted_options_round_trip() {
+        let line = format!(
+            r#"from="*.example.com,!bad.example.com",command="echo \"a b\"" ssh-ed25519 {} me@host laptop"#,
+            KEY
+        );
+        let entry = AuthorizedKeysEntry::parse(&line).unwrap();
+
+        assert_eq!(
+            entry.get_option("from"),
+            Some("*.example.com,!bad.example.com")
+        );
+        assert_eq!(entry.get_option("command"), Some(r#"echo "a b""#));
+        assert_eq!(entry.key_type, "ssh-ed25519");
+        assert_eq!(entry.key, KEY);
+        assert_eq!(entry.comment.as_deref(), Some("me@host laptop"));
+        assert_eq!(entry.to_string(), line);
+    }
+
+    #[test]
+    fn comments_and_unknown_lines_are_skipped() {
+        assert_eq!(AuthorizedKeysEntry::parse("# ssh-ed25519 AAAA"), None);
+        assert_eq!(AuthorizedKeysEntry::parse("   "), None);
+        assert_eq!(AuthorizedKeysEntry::parse("no-pty not-a-key AAAA"), None);
+        assert_eq!(
+            AuthorizedKeysEntry::parse(r#"command="ls ssh-ed25519 AAAA"#),
+            None
+        );
+
+        let entry = AuthorizedKeysEntry::parse(&format!("ssh-ed25519 {}", KEY)).unwrap();
+        assert!(entry.options.is_empty());
+        assert_eq!(entry.comment, None);
+    }
+
+    #[test]
+    fn entries_expire_at_their_expiry_time() {
+        let entry =
+            AuthorizedKeysEntry::parse(&format!("expiry-time=\"19700102Z\" ssh-ed25519 {}", KEY))
+                .unwrap();
+
+        assert!(!entry.is_expired(86_399));
+        assert!(entry.is_expired(86_400));
+    }
+}
@@ -0,0 +1,207 @@
+//! Minimal parsing of the ssh client config, enough to find and rewrite the lines that reference
+//! key and certificate files
+
+use std::path::{Path, PathBuf};
+
+pub const IDENTITY_FILE: &str = "IdentityFile";
+pub const CERTIFICATE_FILE: &str = "CertificateFile";
+
+/// A line of the client config whose value is the path of a file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FileReference {
+    /// Index of the line in the config
+    pub line_idx: usize,
+    pub keyword: String,
+    /// Value of the line, as written in the config
+    pub value: String,
+    /// Patterns of the Host (or Match) block the line belongs to. None if the line is before the
+    /// first block
+    pub host: Option<String>,
+}
+
+/// Parts of a `Keyword value` or `Keyword=value` line
+struct ConfigLine<'a> {
+    keyword: &'a str,
+    /// Everything before the value, including the keyword and the separator
+    prefix: &'a str,
+    value: &'a str,
+    /// Everything after the value
+    suffix: &'a str,
+}
+
+impl<'a> ConfigLine<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            return None;
+        }
+
+        let keyword_end = trimmed.find(|c: char| c.is_whitespace() || c == '=')?;
+        let keyword = &trimmed[..keyword_end];
+
+        let after_keyword = &trimmed[keyword_end..];
+        let separator_len = after_keyword.len()
+            - after_keyword
+                .trim_start()
+                .strip_prefix('=')
+                .unwrap_or(after_keyword.trim_start())
+                .trim_start()
+                .len();
+
+        let value_start = line.len() - trimmed.len() + keyword_end + separator_len;
+        let rest = &line[value_start..];
+        let value_len = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.find('"').map(|end| end + 2).unwrap_or(rest.len()),
+            None => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+        };
+        if value_len == 0 {
+            return None;
+        }
+
+        Some(Self {
+            keyword,
+            prefix: &line[..value_start],
+            value: &rest[..value_len],
+            suffix: &rest[value_len..],
+        })
+    }
+
+    fn unquoted_value(&self) -> &str {
+        self.value.trim_matches('"')
+    }
+}
+
+/// Resolves a path written in the config. `~` and `%d` are expanded to the home directory, and
+/// relative paths are taken as relative to it
+pub fn resolve_path(value: &str) -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_default();
+    let value = value.trim_matches('"');
+
+    if value == "~" || value == "%d" {
+        return home_dir;
+    }
+    match value.strip_prefix("~/").or(value.strip_prefix("%d/")) {
+        Some(rest) => home_dir.join(rest),
+        None => home_dir.join(value),
+    }
+}
+
+/// Formats the new path following the style of the value it replaces: if the old value was
+/// relative to the home directory, the new one is too (as long as it's inside it)
+fn format_path(new_path: &Path, old_value: &str) -> String {
+    let home_dir = dirs::home_dir().unwrap_or_default();
+    let old_value = old_value.trim_matches('"');
+
+    let formatted = match new_path.strip_prefix(&home_dir) {
+        Ok(relative) if old_value.starts_with("~/") => {
+            format!("~/{}", relative.to_string_lossy())
+        }
+        Ok(relative) if old_value.starts_with("%d/") => {
+            format!("%d/{}", relative.to_string_lossy())
+        }
+        _ => new_path.to_string_lossy().into_owned(),
+    };
+
+    if formatted.contains(char::is_whitespace) {
+        format!("\"{}\"", formatted)
+    } else {
+        formatted
+    }
+}
+
+fn is_keyword(line: &ConfigLine, keywords: &[&str]) -> bool {
+    keywords.iter().any(|k| k.eq_ignore_ascii_case(line.keyword))
+}
+
+/// Returns the lines with one of the given keywords whose value points at the given path
+pub fn find_file_references(content: &str, keywords: &[&str], path: &Path) -> Vec<FileReference> {
+    let mut references = vec![];
+    let mut current_host: Option<String> = None;
+
+    for (line_idx, line) in content.lines().enumerate() {
+        let Some(config_line) = ConfigLine::parse(line) else {
+            continue;
+        };
+
+        if config_line.keyword.eq_ignore_ascii_case("Host")
+            || config_line.keyword.eq_ignore_ascii_case("Match")
+        {
+            let patterns = line.trim()[config_line.keyword.len()..]
+                .trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+            current_host = Some(patterns.trim().to_owned());
+            continue;
+        }
+
+        if is_keyword(&config_line, keywords) && resolve_path(config_line.unquoted_value()) == path
+        {
+            references.push(FileReference {
+                line_idx,
+                keyword: config_line.keyword.to_owned(),
+                value: config_line.unquoted_value().to_owned(),
+                host: current_host.clone(),
+            });
+        }
+    }
+
+    references
+}
+
+/// Replaces the value of the lines with one of the given keywords that point at the old path
+///
+/// # Returns
+/// The new content of the config and the number of replaced lines
+pub fn replace_file_references(
+    content: &str,
+    keywords: &[&str],
+    old_path: &Path,
+    new_path: &Path,
+) -> (String, usize) {
+    let references = find_file_references(content, keywords, old_path);
+
+    let mut new_content: Vec<String> = content.lines().map(String::from).collect();
+    for reference in &references {
+        let line = new_content[reference.line_idx].clone();
+        if let Some(config_line) = ConfigLine::parse(&line) {
+            new_content[reference.line_idx] = format!(
+                "{}{}{}",
+                config_line.prefix,
+                format_path(new_path, config_line.value),
+                config_line.suffix
+            );
+        }
+    }
+
+    let mut new_content = new_content.join("\n");
+    if content.ends_with('\n') {
+        new_content.push('\n');
+    }
+
+    (new_content, references.len())
+}
+
+/// Returns the aliases of the Host blocks, skipping the patterns (`*`, `?` and negated ones), as
+/// they don't name a single host that can be connected to
+pub fn find_host_aliases(content: &str) -> Vec<String> {
+    let mut aliases: Vec<String> = vec![];
+
+    for line in content.lines() {
+        let Some(config_line) = ConfigLine::parse(line) else {
+            continue;
+        };
+        if !config_line.keyword.eq_ignore_ascii_case("Host") {
+            continue;
+        }
+
+        let patterns = line.trim()[config_line.keyword.len()..]
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+        for pattern in patterns.split_whitespace() {
+            let pattern = pattern.trim_matches('"');
+            if pattern.contains(['*', '?', '!']) || aliases.iter().any(|alias| alias == pattern) {
+                continue;
+            }
+            aliases.push(pattern.to_owned());
+        }
+    }
+
+    aliases
+}
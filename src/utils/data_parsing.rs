@@ -1,5 +1,44 @@
-use color_eyre::eyre::{Context, Result};
+use color_eyre::eyre::{eyre, Context, Result};
 
 pub fn parse_usize_to_u16(value: usize) -> Result<u16> {
     u16::try_from(value).wrap_err("Value out of range")
 }
+
+/// Parses a time interval in the format used by ssh (e.g. `600`, `30m`, `1h30m`, `2d`) into seconds.
+/// Numbers without a unit are seconds
+pub fn parse_time_interval(value: &str) -> Result<u32> {
+    let invalid_interval = || eyre!("invalid time interval: '{}'", value);
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+    for ch in value.trim().chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+
+        let multiplier = match ch.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return Err(invalid_interval()),
+        };
+        let amount: u64 = number.parse().map_err(|_| invalid_interval())?;
+        total = amount
+            .checked_mul(multiplier)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid_interval)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        let seconds: u64 = number.parse().map_err(|_| invalid_interval())?;
+        total = total.checked_add(seconds).ok_or_else(invalid_interval)?;
+    }
+
+    match u32::try_from(total) {
+        Ok(0) | Err(_) => Err(invalid_interval()),
+        Ok(total) => Ok(total),
+    }
+}
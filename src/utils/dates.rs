@@ -0,0 +1,163 @@
+//! Conversions between Unix timestamps and calendar dates (always in UTC), without depending on a
+//! date crate
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{eyre, Result};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Returns the current Unix timestamp
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Days since the Unix epoch of the given date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Date (year, month, day) of the given number of days since the Unix epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Parses a date in the `YYYYMMDD[HHMM[SS]]` format used by ssh-keygen into a Unix timestamp
+pub fn parse_timestamp(value: &str) -> Result<u64> {
+    let invalid_date = || eyre!("invalid date: '{}'", value);
+
+    if !value.chars().all(|c| c.is_ascii_digit()) || ![8, 12, 14].contains(&value.len()) {
+        return Err(invalid_date());
+    }
+    let field = |range: std::ops::Range<usize>| -> i64 {
+        value.get(range).and_then(|f| f.parse().ok()).unwrap_or(0)
+    };
+
+    let (year, month, day) = (field(0..4), field(4..6), field(6..8));
+    let (hour, minute, second) = (field(8..10), field(10..12), field(12..14));
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(invalid_date());
+    }
+    // Rejects days that don't exist in the month (e.g. February 30th)
+    if civil_from_days(days_from_civil(year, month, day)) != (year, month, day) {
+        return Err(invalid_date());
+    }
+
+    let timestamp =
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+
+    u64::try_from(timestamp).map_err(|_| invalid_date())
+}
+
+/// Formats a Unix timestamp as `YYYY-MM-DD HH:MM:SS`
+pub fn format_timestamp(timestamp: u64) -> String {
+    let timestamp = i64::try_from(timestamp).unwrap_or(i64::MAX);
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    let seconds_of_day = timestamp.rem_euclid(SECONDS_PER_DAY);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_are_parsed_into_timestamps() {
+        assert_eq!(parse_timestamp("19700101").unwrap(), 0);
+        assert_eq!(parse_timestamp("19991231235959").unwrap(), 946684799);
+        assert_eq!(parse_timestamp("200001010000").unwrap(), 946684800);
+        assert_eq!(parse_timestamp("20240229").unwrap(), 1709164800);
+        assert_eq!(parse_timestamp("20240301").unwrap(), 1709164800 + 86400);
+    }
+
+    #[test]
+    fn only_leap_years_have_a_29th_of_february() {
+        assert!(parse_timestamp("20000229").is_ok());
+        assert!(parse_timestamp("20240229").is_ok());
+        assert!(parse_timestamp("19000229").is_err());
+        assert!(parse_timestamp("20230229").is_err());
+        assert!(parse_timestamp("21000229").is_err());
+        assert!(parse_timestamp("20240230").is_err());
+    }
+
+    #[test]
+    fn invalid_dates_are_rejected() {
+        for value in [
+            "",
+            "2024011",
+            "202401011",
+            "2024-01-01",
+            "20241301",
+            "20240001",
+            "20240100",
+            "20240431",
+            "202401012400",
+            "202401010060",
+            "20240101000060",
+            // Before the Unix epoch
+            "19691231",
+        ] {
+            assert!(parse_timestamp(value).is_err(), "{} was accepted", value);
+        }
+    }
+
+    #[test]
+    fn timestamps_are_formatted_across_boundaries() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(946684799), "1999-12-31 23:59:59");
+        assert_eq!(format_timestamp(946684800), "2000-01-01 00:00:00");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(1709251199), "2024-02-29 23:59:59");
+        assert_eq!(format_timestamp(1709251200), "2024-03-01 00:00:00");
+    }
+
+    #[test]
+    fn every_day_round_trips() {
+        // From 1970 to past 2100, which isn't a leap year
+        for days in 0..(131 * 366) {
+            let timestamp = days * SECONDS_PER_DAY as u64 + 3723;
+            let formatted = format_timestamp(timestamp);
+            let value: String = formatted.chars().filter(char::is_ascii_digit).collect();
+
+            assert_eq!(parse_timestamp(&value).unwrap(), timestamp, "{}", formatted);
+        }
+    }
+}
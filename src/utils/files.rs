@@ -4,7 +4,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use color_eyre::eyre::{Context, Result};
+use color_eyre::eyre::{eyre, Context, Result};
+
+use super::{
+    allowed_signers::{self, AllowedSignersEntry},
+    authorized_keys::AuthorizedKeysEntry,
+    client_config, dates,
+    known_hosts::{KnownHostsEntry, KnownHostsMarker},
+    permissions,
+    trash::TrashedKeyPair,
+};
 
 pub fn get_known_hosts() -> Result<Vec<String>> {
     let mut known_hosts: Vec<String> = vec![];
@@ -43,9 +52,60 @@ pub fn get_public_keys_names() -> Result<Vec<String>> {
         }
     }
 
+    pub_keys_names.sort();
+
     Ok(pub_keys_names)
 }
 
+/// Checks if the file at the given path is a private key, based on its header. Works for the
+/// OpenSSH, PEM (PKCS#1 / SEC1) and PKCS#8 formats
+pub fn is_private_key_file(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+
+    match get_file_lines(&file).next() {
+        Some(Ok(first_line)) => {
+            let first_line = first_line.trim();
+            first_line.starts_with("-----BEGIN ") && first_line.ends_with("PRIVATE KEY-----")
+        }
+        _ => false,
+    }
+}
+
+/// Returns the names of the private keys stored in the user's ssh directory whose public key
+/// (`<name>.pub`) doesn't exist
+pub fn get_orphaned_private_keys_names() -> Result<Vec<String>> {
+    let mut private_keys_names: Vec<String> = vec![];
+    let ssh_dir = dirs::home_dir().unwrap_or_default().join(".ssh/");
+
+    if ssh_dir.exists() && ssh_dir.is_dir() {
+        for entry in ssh_dir.read_dir().wrap_err("Error reading directory")?.flatten() {
+            let entry_path = entry.path();
+            if !entry_path.is_file() || !is_private_key_file(&entry_path) {
+                continue;
+            }
+
+            let entry_name = entry.file_name().to_string_lossy().to_string();
+            if !ssh_dir.join(format!("{}.pub", entry_name)).exists() {
+                private_keys_names.push(entry_name);
+            }
+        }
+    }
+
+    private_keys_names.sort();
+
+    Ok(private_keys_names)
+}
+
+/// Reads a text file, replacing the bytes that aren't valid UTF-8 so a single bad line doesn't hide
+/// the ones after it
+fn read_lossy(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 fn get_file_lines(file: &File) -> io::Lines<BufReader<&File>> {
     let reader = io::BufReader::new(file);
     reader.lines()
@@ -56,10 +116,13 @@ fn get_file_hostnames(known_hosts_file: &File) -> Vec<String> {
 
     for line in get_file_lines(known_hosts_file) {
         if let Ok(line) = line {
-            if let Some(hostname) = line.split_once(' ').map(|s| s.0.to_owned()) {
-                if !known_hosts.contains(&hostname) {
-                    known_hosts.push(hostname)
-                }
+            // Lines with markers (@cert-authority, @revoked) don't describe a known host
+            let entry = match KnownHostsEntry::parse(&line) {
+                Some(entry) if entry.marker.is_none() => entry,
+                _ => continue,
+            };
+            if !known_hosts.contains(&entry.host_patterns) {
+                known_hosts.push(entry.host_patterns)
             }
         } else {
             continue;
@@ -69,6 +132,421 @@ fn get_file_hostnames(known_hosts_file: &File) -> Vec<String> {
     known_hosts
 }
 
+fn get_user_known_hosts_path() -> Result<PathBuf> {
+    Ok(get_user_ssh_dir()?.join("known_hosts"))
+}
+
+fn get_user_known_hosts_entries() -> Result<Vec<KnownHostsEntry>> {
+    let known_hosts_path = get_user_known_hosts_path()?;
+    if !known_hosts_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = read_lossy(&known_hosts_path).wrap_err("Failed to read known_hosts file")?;
+    let entries = content.lines().filter_map(KnownHostsEntry::parse).collect();
+
+    Ok(entries)
+}
+
+/// Returns the @cert-authority and @revoked lines of the user's known_hosts file
+pub fn get_known_hosts_markers() -> Result<Vec<KnownHostsEntry>> {
+    let markers = get_user_known_hosts_entries()?
+        .into_iter()
+        .filter(|entry| entry.marker.is_some())
+        .collect();
+
+    Ok(markers)
+}
+
+fn append_known_hosts_entries(entries: &[KnownHostsEntry]) -> Result<()> {
+    let known_hosts_path = get_user_known_hosts_path()?;
+    create_file_if_non_existing(&known_hosts_path, permissions::PUBLIC_FILE_MODE)?;
+
+    let current_content =
+        fs::read_to_string(&known_hosts_path).wrap_err("Failed to read known_hosts file")?;
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&known_hosts_path)
+        .wrap_err("Failed to open known_hosts file")?;
+
+    if !current_content.is_empty() && !current_content.ends_with('\n') {
+        writeln!(file)?;
+    }
+    for entry in entries {
+        writeln!(file, "{}", entry)?;
+    }
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Appends a @cert-authority line to the user's known_hosts file, trusting the CA whose public
+/// key is stored at the given path to sign the certificates of the hosts matching the pattern
+pub fn add_cert_authority(ca_pub_key_path: &str, host_pattern: &str) -> Result<()> {
+    let host_pattern = host_pattern.trim();
+    if host_pattern.is_empty() || host_pattern.contains(char::is_whitespace) {
+        return Err(eyre!("the host pattern must be a single, non-empty, word"));
+    }
+
+    let ca_pub_key_path = expand_home(ca_pub_key_path.trim());
+    let ca_pub_key = fs::read_to_string(&ca_pub_key_path).wrap_err_with(|| {
+        format!(
+            "Failed to read CA public key '{}'",
+            ca_pub_key_path.to_string_lossy()
+        )
+    })?;
+
+    let entry = KnownHostsEntry::from_public_key(
+        Some(KnownHostsMarker::CertAuthority),
+        host_pattern,
+        &ca_pub_key,
+    )
+    .ok_or(eyre!("'{}' is not a valid public key", ca_pub_key_path.to_string_lossy()))?;
+
+    append_known_hosts_entries(&[entry])
+}
+
+/// Returns the keys of the given host in the user's known_hosts file
+pub fn get_known_host_keys(hostname: &str) -> Result<Vec<KnownHostsEntry>> {
+    let host_keys = get_user_known_hosts_entries()?
+        .into_iter()
+        .filter(|entry| entry.marker.is_none() && entry.host_patterns == hostname)
+        .collect();
+
+    Ok(host_keys)
+}
+
+/// Returns true if the user's known_hosts file has a @revoked line for the key of the entry
+pub fn is_known_host_key_revoked(host_key: &KnownHostsEntry) -> Result<bool> {
+    let revoked = get_user_known_hosts_entries()?
+        .iter()
+        .any(|e| e.marker == Some(KnownHostsMarker::Revoked) && e.same_key(host_key));
+
+    Ok(revoked)
+}
+
+/// Marks the key of the given known_hosts entry as revoked, appending a @revoked line for it to the
+/// user's known_hosts file. The key is revoked for every host, as ssh does with @revoked lines
+pub fn revoke_known_host_key(host_key: &KnownHostsEntry) -> Result<()> {
+    if is_known_host_key_revoked(host_key)? {
+        return Err(eyre!(
+            "the {} key of '{}' is already revoked",
+            host_key.key_type,
+            host_key.host_patterns
+        ));
+    }
+
+    append_known_hosts_entries(&[KnownHostsEntry {
+        marker: Some(KnownHostsMarker::Revoked),
+        host_patterns: String::from("*"),
+        key_type: host_key.key_type.clone(),
+        key: host_key.key.clone(),
+        comment: host_key.comment.clone(),
+    }])
+}
+
+pub fn get_authorized_keys_path() -> Result<PathBuf> {
+    Ok(get_user_ssh_dir()?.join("authorized_keys"))
+}
+
+/// Returns the entries of the user's authorized_keys file, skipping comments and the lines that
+/// can't be parsed
+pub fn get_authorized_keys_entries() -> Result<Vec<AuthorizedKeysEntry>> {
+    let authorized_keys_path = get_authorized_keys_path()?;
+    if !authorized_keys_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content =
+        read_lossy(&authorized_keys_path).wrap_err("Failed to read authorized_keys file")?;
+    let entries = content.lines().filter_map(AuthorizedKeysEntry::parse).collect();
+
+    Ok(entries)
+}
+
+/// Appends an entry to the user's authorized_keys file, unless its key is already authorized
+pub fn add_authorized_keys_entry(entry: &AuthorizedKeysEntry) -> Result<()> {
+    if get_authorized_keys_entries()?
+        .iter()
+        .any(|existing| existing.same_key(entry))
+    {
+        return Err(eyre!("the key is already in the authorized_keys file"));
+    }
+
+    let authorized_keys_path = get_authorized_keys_path()?;
+    create_file_if_non_existing(&authorized_keys_path, permissions::PRIVATE_FILE_MODE)?;
+
+    let current_content =
+        fs::read(&authorized_keys_path).wrap_err("Failed to read authorized_keys file")?;
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&authorized_keys_path)
+        .wrap_err("Failed to open authorized_keys file")?;
+
+    if !current_content.is_empty() && !current_content.ends_with(b"\n") {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", entry)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Replaces the line of the user's authorized_keys file holding the given entry with the new
+/// entry, or removes it if there's no new entry. Comments and the rest of the lines are kept as
+/// they are
+pub fn update_authorized_keys_entry(
+    old_entry: &AuthorizedKeysEntry,
+    new_entry: Option<&AuthorizedKeysEntry>,
+) -> Result<()> {
+    let authorized_keys_path = get_authorized_keys_path()?;
+    update_authorized_keys_file(&authorized_keys_path, old_entry, new_entry)
+}
+
+/// Does the job of update_authorized_keys_entry on the given file. Lines are parsed as read_lossy
+/// does, but the ones that aren't replaced are written back byte for byte, so a line that isn't
+/// valid UTF-8 is neither lost nor mangled
+fn update_authorized_keys_file(
+    authorized_keys_path: &Path,
+    old_entry: &AuthorizedKeysEntry,
+    new_entry: Option<&AuthorizedKeysEntry>,
+) -> Result<()> {
+    let content = fs::read(authorized_keys_path).wrap_err("Failed to read authorized_keys file")?;
+
+    let mut found = false;
+    let mut new_content: Vec<u8> = Vec::with_capacity(content.len());
+    let lines = content.strip_suffix(b"\n").unwrap_or(&content);
+    for line in lines.split(|byte| *byte == b'\n').filter(|_| !content.is_empty()) {
+        if !found
+            && AuthorizedKeysEntry::parse(&String::from_utf8_lossy(line)).as_ref()
+                == Some(old_entry)
+        {
+            found = true;
+            if let Some(new_entry) = new_entry {
+                new_content.extend_from_slice(format!("{}\n", new_entry).as_bytes());
+            }
+            continue;
+        }
+        new_content.extend_from_slice(line);
+        new_content.push(b'\n');
+    }
+
+    if !found {
+        return Err(eyre!(
+            "the entry is no longer in the authorized_keys file. Refresh the list and try again"
+        ));
+    }
+
+    fs::write(authorized_keys_path, new_content).wrap_err("Failed to write authorized_keys file")
+}
+
+/// Where git looks for the allowed_signers file, if it's configured
+const ALLOWED_SIGNERS_KEY: &str = "gpg.ssh.allowedSignersFile";
+
+/// Returns the global git config file of the user. As `git config --global` does, ~/.gitconfig is
+/// used unless it doesn't exist and the XDG one does
+pub fn get_git_config_path() -> Result<PathBuf> {
+    let home_dir = PathBuf::from(std::env::var("HOME").wrap_err("Couldn't find home directory")?);
+    let gitconfig_path = home_dir.join(".gitconfig");
+
+    let xdg_config_dir = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home_dir.join(".config"),
+    };
+    let xdg_gitconfig_path = xdg_config_dir.join("git").join("config");
+
+    if !gitconfig_path.exists() && xdg_gitconfig_path.exists() {
+        Ok(xdg_gitconfig_path)
+    } else {
+        Ok(gitconfig_path)
+    }
+}
+
+/// Runs `git config` on the given file, which git parses and rewrites itself (through a lock file,
+/// so the file is replaced at once)
+fn run_git_config(git_config_path: &Path, args: &[&str]) -> io::Result<std::process::Output> {
+    std::process::Command::new("git")
+        .arg("config")
+        .arg("--file")
+        .arg(git_config_path)
+        .args(args)
+        .output()
+}
+
+/// Returns a value of the given git config file, if it's set. As git does, the last one wins if the
+/// key is set more than once
+fn get_git_config_value(git_config_path: &Path, key: &str) -> Result<Option<String>> {
+    if !git_config_path.exists() {
+        return Ok(None);
+    }
+
+    let output = match run_git_config(git_config_path, &["--get", key]) {
+        // Without git, there's no config to follow
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        output => output.wrap_err("Failed to run git config")?,
+    };
+
+    // git config exits with 1 when the key isn't set
+    match output.status.code() {
+        Some(0) => Ok(Some(
+            String::from_utf8_lossy(&output.stdout)
+                .trim_end_matches('\n')
+                .to_owned(),
+        )),
+        Some(1) => Ok(None),
+        _ => Err(eyre!(
+            "Failed to read git config file: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Sets the given values in the given git config file, creating it if needed
+fn set_git_config_values(git_config_path: &Path, values: &[(&str, &str)]) -> Result<()> {
+    for (key, value) in values {
+        let output = run_git_config(git_config_path, &[key, value])
+            .wrap_err("Failed to run git config")?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "Failed to write git config file: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the allowed_signers file of the user: the one git is configured to use, or
+/// ~/.ssh/allowed_signers
+pub fn get_allowed_signers_path() -> Result<PathBuf> {
+    match get_git_config_value(&get_git_config_path()?, ALLOWED_SIGNERS_KEY)? {
+        Some(path) if !path.trim().is_empty() => Ok(expand_home(path.trim())),
+        _ => Ok(get_user_ssh_dir()?.join("allowed_signers")),
+    }
+}
+
+/// Makes git sign commits and tags with the given SSH public key (through ssh-keygen), and verify
+/// the signatures against the given allowed_signers file
+///
+/// # Returns
+/// The path of the git config file
+pub fn configure_git_ssh_signing(
+    signing_key_path: &Path,
+    allowed_signers_path: &Path,
+) -> Result<PathBuf> {
+    let signing_key_path = signing_key_path.to_string_lossy();
+    let allowed_signers_path = allowed_signers_path.to_string_lossy();
+
+    let git_config_path = get_git_config_path()?;
+    set_git_config_values(
+        &git_config_path,
+        &[
+            ("gpg.format", "ssh"),
+            ("user.signingkey", &signing_key_path),
+            (ALLOWED_SIGNERS_KEY, &allowed_signers_path),
+        ],
+    )?;
+
+    Ok(git_config_path)
+}
+
+/// E-mail address the user commits with, the usual principal of their signatures
+pub fn get_git_user_email() -> Result<Option<String>> {
+    get_git_config_value(&get_git_config_path()?, "user.email")
+}
+
+/// Returns the entries of the user's allowed_signers file, skipping comments and the lines that
+/// can't be parsed
+pub fn get_allowed_signers_entries() -> Result<Vec<AllowedSignersEntry>> {
+    let allowed_signers_path = get_allowed_signers_path()?;
+    if !allowed_signers_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(allowed_signers_path)
+        .wrap_err("Failed to read allowed_signers file")?;
+
+    Ok(allowed_signers::parse_allowed_signers(&content))
+}
+
+/// Appends an entry to the user's allowed_signers file, unless the key is already allowed for the
+/// same principals
+pub fn add_allowed_signers_entry(entry: &AllowedSignersEntry) -> Result<()> {
+    if get_allowed_signers_entries()?
+        .iter()
+        .any(|existing| existing.same_key(entry) && existing.principals == entry.principals)
+    {
+        return Err(eyre!(
+            "the key is already allowed to sign as '{}'",
+            entry.principals
+        ));
+    }
+
+    let allowed_signers_path = get_allowed_signers_path()?;
+    create_file_if_non_existing(&allowed_signers_path, permissions::PUBLIC_FILE_MODE)?;
+
+    let current_content = fs::read_to_string(&allowed_signers_path)
+        .wrap_err("Failed to read allowed_signers file")?;
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&allowed_signers_path)
+        .wrap_err("Failed to open allowed_signers file")?;
+
+    if !current_content.is_empty() && !current_content.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", entry)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Replaces the line of the user's allowed_signers file holding the given entry with the new
+/// entry, or removes it if there's no new entry. Comments and the rest of the lines are kept as
+/// they are
+pub fn update_allowed_signers_entry(
+    old_entry: &AllowedSignersEntry,
+    new_entry: Option<&AllowedSignersEntry>,
+) -> Result<()> {
+    let allowed_signers_path = get_allowed_signers_path()?;
+    let content = fs::read_to_string(&allowed_signers_path)
+        .wrap_err("Failed to read allowed_signers file")?;
+
+    let mut found = false;
+    let mut new_content = String::with_capacity(content.len());
+    for line in content.lines() {
+        if !found && AllowedSignersEntry::parse(line).as_ref() == Some(old_entry) {
+            found = true;
+            if let Some(new_entry) = new_entry {
+                new_content.push_str(&format!("{}\n", new_entry));
+            }
+            continue;
+        }
+        new_content.push_str(line);
+        new_content.push('\n');
+    }
+
+    if !found {
+        return Err(eyre!(
+            "the entry is no longer in the allowed_signers file. Refresh the list and try again"
+        ));
+    }
+
+    fs::write(allowed_signers_path, new_content).wrap_err("Failed to write allowed_signers file")
+}
+
+/// Replaces a leading `~` with the user's home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
 pub fn get_user_ssh_dir() -> Result<PathBuf> {
     let path = PathBuf::from(std::env::var("HOME").wrap_err("Couldn't find home directory")?)
         .join(".ssh/");
@@ -76,23 +554,159 @@ pub fn get_user_ssh_dir() -> Result<PathBuf> {
     Ok(path)
 }
 
-pub fn delete_key_pair(private_key_name: &str) -> Result<()> {
+/// Checks if there's already a private or public key with the given name in the user's SSH
+/// directory
+pub fn key_pair_exists(private_key_name: &str) -> Result<bool> {
     let ssh_dir = get_user_ssh_dir()?;
+    let public_key_name = format!("{}.pub", private_key_name);
 
-    let private_key_path = ssh_dir.clone().join(private_key_name);
+    Ok(ssh_dir.join(private_key_name).exists() || ssh_dir.join(public_key_name).exists())
+}
 
-    let public_key_name = format!("{}.pub", private_key_name);
-    let public_key_path = ssh_dir.join(public_key_name.clone());
+/// Returns the trash directory of sshy (~/.ssh/trash), where deleted key pairs are kept until
+/// they're restored or purged
+pub fn get_trash_dir() -> Result<PathBuf> {
+    Ok(get_user_ssh_dir()?.join("trash"))
+}
+
+/// Moves a key pair (and its certificate, if any) to its own directory in the trash, instead of
+/// deleting it
+///
+/// # Returns
+/// The directory of the trash the key pair was moved to
+pub fn trash_key_pair(private_key_name: &str) -> Result<PathBuf> {
+    move_key_pair_to_trash(
+        &get_user_ssh_dir()?,
+        &get_trash_dir()?,
+        private_key_name,
+        dates::now(),
+    )
+}
+
+/// Does the job of trash_key_pair with the given directories and deletion time
+fn move_key_pair_to_trash(
+    ssh_dir: &Path,
+    trash_dir: &Path,
+    private_key_name: &str,
+    deleted_at: u64,
+) -> Result<PathBuf> {
+    let file_names: Vec<String> = KEY_PAIR_FILE_SUFFIXES
+        .iter()
+        .map(|suffix| format!("{}{}", private_key_name, suffix))
+        .filter(|file_name| ssh_dir.join(file_name).exists())
+        .collect();
 
-    if private_key_path.exists() {
-        fs::remove_file(private_key_path).wrap_err(format!("Failed to remove private key '{}'", private_key_name.to_owned()))?;
+    if file_names.is_empty() {
+        return Err(eyre!("Key pair '{}' not found", private_key_name));
     }
-    if public_key_path.exists() {
-        fs::remove_file(public_key_path).wrap_err(format!("Failed to remove public key '{}'", public_key_name))?;
+
+    let dir_name = TrashedKeyPair::dir_name(private_key_name, deleted_at);
+    let mut key_trash_dir = trash_dir.join(&dir_name);
+    let mut counter = 1;
+    while key_trash_dir.exists() {
+        key_trash_dir = trash_dir.join(format!("{}-{}", dir_name, counter));
+        counter += 1;
     }
+    permissions::create_private_dir_all(&key_trash_dir)
+        .wrap_err("Failed to create the trash directory")?;
 
+    for (idx, file_name) in file_names.iter().enumerate() {
+        if let Err(err) = fs::rename(ssh_dir.join(file_name), key_trash_dir.join(file_name)) {
+            for moved_name in &file_names[..idx] {
+                let _ = fs::rename(key_trash_dir.join(moved_name), ssh_dir.join(moved_name));
+            }
+            let _ = fs::remove_dir(&key_trash_dir);
+            return Err(eyre!("Failed to move '{}' to the trash: {}", file_name, err));
+        }
+    }
 
-    Ok(())
+    Ok(key_trash_dir)
+}
+
+/// Returns the key pairs in the trash, the most recently deleted first
+pub fn get_trashed_key_pairs() -> Result<Vec<TrashedKeyPair>> {
+    read_trash_dir(&get_trash_dir()?)
+}
+
+/// Does the job of get_trashed_key_pairs on the given trash directory
+fn read_trash_dir(trash_dir: &Path) -> Result<Vec<TrashedKeyPair>> {
+    if !trash_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut trashed_key_pairs = vec![];
+    for entry in trash_dir.read_dir().wrap_err("Failed to read the trash directory")?.flatten() {
+        let dir_path = entry.path();
+        if !dir_path.is_dir() {
+            continue;
+        }
+
+        let mut file_names: Vec<String> = dir_path
+            .read_dir()
+            .wrap_err_with(|| format!("Failed to read '{}'", dir_path.to_string_lossy()))?
+            .flatten()
+            .map(|file| file.file_name().to_string_lossy().to_string())
+            .collect();
+        file_names.sort();
+
+        if let Some(trashed_key_pair) = TrashedKeyPair::from_dir(&dir_path, file_names) {
+            trashed_key_pairs.push(trashed_key_pair);
+        }
+    }
+
+    trashed_key_pairs.sort_by(|a, b| {
+        b.deleted_at
+            .cmp(&a.deleted_at)
+            .then_with(|| b.dir_path.cmp(&a.dir_path))
+    });
+
+    Ok(trashed_key_pairs)
+}
+
+/// Moves a key pair back from the trash to the ssh directory. Nothing is moved if a file with the
+/// same name is there already, and the restored files go back to the trash if one of them can't
+/// be moved
+pub fn restore_trashed_key_pair(trashed_key_pair: &TrashedKeyPair) -> Result<()> {
+    move_key_pair_from_trash(trashed_key_pair, &get_user_ssh_dir()?)
+}
+
+/// Does the job of restore_trashed_key_pair with the given ssh directory
+fn move_key_pair_from_trash(trashed_key_pair: &TrashedKeyPair, ssh_dir: &Path) -> Result<()> {
+    if let Some(taken) = trashed_key_pair
+        .file_names
+        .iter()
+        .find(|file_name| ssh_dir.join(file_name).exists())
+    {
+        return Err(eyre!(
+            "'{}' already exists, rename or delete it before restoring",
+            taken
+        ));
+    }
+
+    let file_names = &trashed_key_pair.file_names;
+    for (idx, file_name) in file_names.iter().enumerate() {
+        if let Err(err) = fs::rename(
+            trashed_key_pair.dir_path.join(file_name),
+            ssh_dir.join(file_name),
+        ) {
+            for moved_name in &file_names[..idx] {
+                let _ = fs::rename(
+                    ssh_dir.join(moved_name),
+                    trashed_key_pair.dir_path.join(moved_name),
+                );
+            }
+            return Err(eyre!("Failed to restore '{}': {}", file_name, err));
+        }
+    }
+
+    fs::remove_dir(&trashed_key_pair.dir_path)
+        .wrap_err("Key pair restored, but failed to remove its directory from the trash")
+}
+
+/// Permanently deletes a key pair from the trash
+pub fn purge_trashed_key_pair(trashed_key_pair: &TrashedKeyPair) -> Result<()> {
+    fs::remove_dir_all(&trashed_key_pair.dir_path)
+        .wrap_err_with(|| format!("Failed to purge '{}'", trashed_key_pair.key_name))
 }
 
 pub fn get_pub_key_content(key_name: &str) -> Result<String> {
@@ -113,16 +727,18 @@ pub fn get_pub_key_content(key_name: &str) -> Result<String> {
     fs::read_to_string(key_path).wrap_err("Failed to read public key content")
 }
 
-fn create_file_if_non_existing(path: &Path) -> Result<()> {
+/// Creates the file with the given mode, if it doesn't exist. Missing parent directories are
+/// created with the mode of the ssh directory
+fn create_file_if_non_existing(path: &Path, mode: u32) -> Result<()> {
     if path.exists() {
         return Ok(());
     }
 
     if let Some(parent_path) = path.parent() {
-        fs::create_dir_all(parent_path).wrap_err("Failed to create parent directories")?;
+        permissions::create_private_dir_all(parent_path).wrap_err("Failed to create parent directories")?;
     }
 
-    File::create(path).wrap_err_with(|| format!("Failed to create file: {}", path.to_string_lossy()))?;
+    permissions::create_file_with_mode(path, mode).wrap_err_with(|| format!("Failed to create file: {}", path.to_string_lossy()))?;
 
     Ok(())
 }
@@ -131,7 +747,7 @@ pub fn get_client_config_content() -> Result<String> {
     let ssh_dir = get_user_ssh_dir()?;
     let config_file_path = ssh_dir.join("config");
 
-    create_file_if_non_existing(&config_file_path)?;
+    create_file_if_non_existing(&config_file_path, permissions::PRIVATE_FILE_MODE)?;
 
     fs::read_to_string(config_file_path).wrap_err("Failed to read client config file")
 }
@@ -139,7 +755,7 @@ pub fn get_client_config_content() -> Result<String> {
 pub fn truncate_client_config_content(lines: &[String]) -> Result<()> {
     let ssh_dir = get_user_ssh_dir()?;
     let config_file_path = ssh_dir.join("config");
-    create_file_if_non_existing(&config_file_path)?;
+    create_file_if_non_existing(&config_file_path, permissions::PRIVATE_FILE_MODE)?;
 
     let mut file = File::create(config_file_path)?;
 
@@ -149,3 +765,355 @@ pub fn truncate_client_config_content(lines: &[String]) -> Result<()> {
     file.flush()?;
     Ok(())
 }
+
+/// Returns the aliases of the Host blocks of the client config
+pub fn get_client_config_hosts() -> Result<Vec<String>> {
+    let content = get_client_config_content()?;
+
+    Ok(client_config::find_host_aliases(&content))
+}
+
+/// Makes the lines of the client config with one of the given keywords that point at the old path,
+/// point at the new one
+///
+/// # Returns
+/// The number of rewritten lines
+pub fn rewrite_client_config_references(
+    keywords: &[&str],
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<usize> {
+    let content = get_client_config_content()?;
+
+    let (new_content, replaced) =
+        client_config::replace_file_references(&content, keywords, old_path, new_path);
+    if replaced > 0 {
+        let config_file_path = get_user_ssh_dir()?.join("config");
+        fs::write(config_file_path, new_content).wrap_err("Failed to write client config file")?;
+    }
+
+    Ok(replaced)
+}
+
+/// Suffixes of the files of a key pair: the private key, the public key and its certificate
+const KEY_PAIR_FILE_SUFFIXES: [&str; 3] = ["", ".pub", "-cert.pub"];
+
+/// Returns the IdentityFile and CertificateFile lines of the client config that point at one of
+/// the files of a key pair
+pub fn find_key_pair_references(private_key_name: &str) -> Result<Vec<client_config::FileReference>> {
+    let ssh_dir = get_user_ssh_dir()?;
+    let content = get_client_config_content()?;
+
+    let mut references: Vec<client_config::FileReference> = KEY_PAIR_FILE_SUFFIXES
+        .iter()
+        .flat_map(|suffix| {
+            client_config::find_file_references(
+                &content,
+                &[client_config::IDENTITY_FILE, client_config::CERTIFICATE_FILE],
+                &ssh_dir.join(format!("{}{}", private_key_name, suffix)),
+            )
+        })
+        .collect();
+    references.sort_by_key(|reference| reference.line_idx);
+
+    Ok(references)
+}
+
+/// Moves a key pair (and its certificate, if any) so its private key ends up at the given path.
+/// If one of the files can't be moved, the ones that were are moved back
+///
+/// # Returns
+/// The old and new paths of the moved files
+pub fn move_key_pair(
+    private_key_name: &str,
+    new_private_key_path: &Path,
+) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let ssh_dir = get_user_ssh_dir()?;
+
+    let moves: Vec<(PathBuf, PathBuf)> = KEY_PAIR_FILE_SUFFIXES
+        .iter()
+        .map(|suffix| {
+            let mut new_path = new_private_key_path.as_os_str().to_owned();
+            new_path.push(suffix);
+            (
+                ssh_dir.join(format!("{}{}", private_key_name, suffix)),
+                PathBuf::from(new_path),
+            )
+        })
+        .filter(|(old_path, _)| old_path.exists())
+        .collect();
+
+    if moves.is_empty() {
+        return Err(eyre!("Key pair '{}' not found", private_key_name));
+    }
+    if let Some((_, taken_path)) = moves.iter().find(|(_, new_path)| new_path.exists()) {
+        return Err(eyre!("'{}' already exists", taken_path.to_string_lossy()));
+    }
+
+    if let Some(parent_dir) = new_private_key_path.parent() {
+        permissions::create_private_dir_all(parent_dir).wrap_err_with(|| {
+            format!("Failed to create '{}'", parent_dir.to_string_lossy())
+        })?;
+    }
+
+    for (idx, (old_path, new_path)) in moves.iter().enumerate() {
+        if let Err(err) = fs::rename(old_path, new_path) {
+            for (moved_path, moved_to) in &moves[..idx] {
+                let _ = fs::rename(moved_to, moved_path);
+            }
+            return Err(eyre!(
+                "Failed to move '{}' to '{}': {}",
+                old_path.to_string_lossy(),
+                new_path.to_string_lossy(),
+                err
+            ));
+        }
+    }
+
+    Ok(moves)
+}
+
+/// Moves a key pair (and its certificate, if any) to the archive directory (~/.ssh/archive)
+///
+/// # Returns
+/// The path the private key was moved to
+pub fn archive_key_pair(private_key_name: &str) -> Result<PathBuf> {
+    let ssh_dir = get_user_ssh_dir()?;
+    let archive_dir = ssh_dir.join("archive");
+    permissions::create_private_dir_all(&archive_dir)
+        .wrap_err("Failed to create the archive directory")?;
+
+    let file_names: Vec<String> = KEY_PAIR_FILE_SUFFIXES
+        .iter()
+        .map(|suffix| format!("{}{}", private_key_name, suffix))
+        .filter(|file_name| ssh_dir.join(file_name).exists())
+        .collect();
+
+    if file_names.is_empty() {
+        return Err(eyre!("Key pair '{}' not found", private_key_name));
+    }
+    if let Some(archived) = file_names.iter().find(|f| archive_dir.join(f).exists()) {
+        return Err(eyre!("'{}' is already archived", archived));
+    }
+
+    for file_name in file_names {
+        fs::rename(ssh_dir.join(&file_name), archive_dir.join(&file_name))
+            .wrap_err_with(|| format!("Failed to archive '{}'", file_name))?;
+    }
+
+    Ok(archive_dir.join(private_key_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_config_values_are_set_without_touching_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_config_path = dir.path().join(".gitconfig");
+        fs::write(
+            &git_config_path,
+            "# kept\n[user] email = me@example.com\n[alias]\n\tlg = log \\\n\t\t--oneline\n[gpg \"ssh\"]\n\tprogram = ssh-keygen\n",
+        )
+        .unwrap();
+
+        set_git_config_values(
+            &git_config_path,
+            &[
+                ("gpg.format", "ssh"),
+                ("user.signingkey", "/home/me/.ssh/id ed25519.pub"),
+                (ALLOWED_SIGNERS_KEY, "~/.ssh/allowed_signers # shared"),
+            ],
+        )
+        .unwrap();
+
+        let get = |key| get_git_config_value(&git_config_path, key).unwrap();
+        assert_eq!(get("user.email").as_deref(), Some("me@example.com"));
+        assert_eq!(get("alias.lg").as_deref(), Some("log   --oneline"));
+        assert_eq!(get("gpg.ssh.program").as_deref(), Some("ssh-keygen"));
+        assert_eq!(get("gpg.format").as_deref(), Some("ssh"));
+        assert_eq!(
+            get("user.signingkey").as_deref(),
+            Some("/home/me/.ssh/id ed25519.pub")
+        );
+        assert_eq!(
+            get(ALLOWED_SIGNERS_KEY).as_deref(),
+            Some("~/.ssh/allowed_signers # shared")
+        );
+        assert_eq!(get("user.name"), None);
+        assert!(fs::read_to_string(&git_config_path)
+            .unwrap()
+            .starts_with("# kept\n"));
+    }
+
+    #[test]
+    fn authorized_keys_update_keeps_lines_that_arent_utf8() {
+        let dir = tempfile::tempdir().unwrap();
+        let authorized_keys_path = dir.path().join("authorized_keys");
+        let key =
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+        let mut content = b"# caf\xe9\n".to_vec();
+        content.extend_from_slice(format!("{} me@host\n", key).as_bytes());
+        content.extend_from_slice(b"ssh-rsa AAAA \xff\xfe\n");
+        fs::write(&authorized_keys_path, &content).unwrap();
+
+        let old_entry = AuthorizedKeysEntry::parse(&format!("{} me@host", key)).unwrap();
+        let new_entry = AuthorizedKeysEntry::parse(&format!("no-pty {} me@host", key)).unwrap();
+        update_authorized_keys_file(&authorized_keys_path, &old_entry, Some(&new_entry)).unwrap();
+
+        let mut expected = b"# caf\xe9\n".to_vec();
+        expected.extend_from_slice(format!("no-pty {} me@host\n", key).as_bytes());
+        expected.extend_from_slice(b"ssh-rsa AAAA \xff\xfe\n");
+        assert_eq!(fs::read(&authorized_keys_path).unwrap(), expected);
+
+        update_authorized_keys_file(&authorized_keys_path, &new_entry, None).unwrap();
+        assert_eq!(
+            fs::read(&authorized_keys_path).unwrap(),
+            b"# caf\xe9\nssh-rsa AAAA \xff\xfe\n"
+        );
+        assert!(update_authorized_keys_file(&authorized_keys_path, &new_entry, None).is_err());
+    }
+
+    /// Writes a key pair with the given files (e.g. `["", ".pub"]`) in the ssh directory
+    fn write_key_pair_files(ssh_dir: &Path, key_name: &str, suffixes: &[&str]) {
+        for suffix in suffixes {
+            let file_name = format!("{}{}", key_name, suffix);
+            fs::write(ssh_dir.join(&file_name), &file_name).unwrap();
+        }
+    }
+
+    fn dir_file_names(dir: &Path) -> Vec<String> {
+        let mut file_names: Vec<String> = dir
+            .read_dir()
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        file_names.sort();
+
+        file_names
+    }
+
+    #[test]
+    fn trashed_key_pairs_are_listed_and_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let ssh_dir = dir.path();
+        let trash_dir = ssh_dir.join("trash");
+        write_key_pair_files(ssh_dir, "id_ed25519", &["", ".pub", "-cert.pub"]);
+        write_key_pair_files(ssh_dir, "work.key", &["", ".pub"]);
+        write_key_pair_files(ssh_dir, "other", &[".pub"]);
+
+        assert!(read_trash_dir(&trash_dir).unwrap().is_empty());
+        assert!(move_key_pair_to_trash(ssh_dir, &trash_dir, "missing", 100).is_err());
+        assert!(!trash_dir.exists());
+
+        let first = move_key_pair_to_trash(ssh_dir, &trash_dir, "id_ed25519", 100).unwrap();
+        assert_eq!(first, trash_dir.join("id_ed25519.100"));
+        assert_eq!(
+            dir_file_names(&first),
+            ["id_ed25519", "id_ed25519-cert.pub", "id_ed25519.pub"]
+        );
+        move_key_pair_to_trash(ssh_dir, &trash_dir, "work.key", 200).unwrap();
+        // Deleting the same name again in the same second doesn't mix the key pairs
+        write_key_pair_files(ssh_dir, "id_ed25519", &[".pub"]);
+        let second = move_key_pair_to_trash(ssh_dir, &trash_dir, "id_ed25519", 100).unwrap();
+        assert_eq!(second, trash_dir.join("id_ed25519.100-1"));
+        assert_eq!(dir_file_names(ssh_dir), ["other.pub", "trash"]);
+
+        // Stray files and directories that aren't key pairs are ignored
+        fs::write(trash_dir.join("notes.txt"), "").unwrap();
+        fs::create_dir(trash_dir.join("empty.300")).unwrap();
+        let trashed_key_pairs = read_trash_dir(&trash_dir).unwrap();
+        assert_eq!(
+            trashed_key_pairs
+                .iter()
+                .map(|trashed| (trashed.key_name.as_str(), trashed.deleted_at))
+                .collect::<Vec<(&str, u64)>>(),
+            [("work.key", 200), ("id_ed25519", 100), ("id_ed25519", 100)]
+        );
+        assert_eq!(trashed_key_pairs[1].dir_path, second);
+        assert_eq!(trashed_key_pairs[2].dir_path, first);
+        assert_eq!(
+            trashed_key_pairs[0].file_names,
+            ["work.key", "work.key.pub"]
+        );
+
+        move_key_pair_from_trash(&trashed_key_pairs[0], ssh_dir).unwrap();
+        assert!(!trashed_key_pairs[0].dir_path.exists());
+        assert_eq!(
+            fs::read_to_string(ssh_dir.join("work.key.pub")).unwrap(),
+            "work.key.pub"
+        );
+    }
+
+    #[test]
+    fn restoring_over_an_existing_file_moves_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let ssh_dir = dir.path();
+        let trash_dir = ssh_dir.join("trash");
+        write_key_pair_files(ssh_dir, "id_rsa", &["", ".pub"]);
+        move_key_pair_to_trash(ssh_dir, &trash_dir, "id_rsa", 100).unwrap();
+        fs::write(ssh_dir.join("id_rsa.pub"), "new key").unwrap();
+
+        let trashed_key_pair = read_trash_dir(&trash_dir).unwrap().remove(0);
+        let err = move_key_pair_from_trash(&trashed_key_pair, ssh_dir).unwrap_err();
+        assert!(err.to_string().contains("'id_rsa.pub' already exists"));
+
+        assert_eq!(dir_file_names(ssh_dir), ["id_rsa.pub", "trash"]);
+        assert_eq!(
+            fs::read_to_string(ssh_dir.join("id_rsa.pub")).unwrap(),
+            "new key"
+        );
+        assert_eq!(
+            dir_file_names(&trashed_key_pair.dir_path),
+            ["id_rsa", "id_rsa.pub"]
+        );
+    }
+
+    #[test]
+    fn partial_moves_are_rolled_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let ssh_dir = dir.path();
+        write_key_pair_files(ssh_dir, "id_ecdsa", &["", ".pub"]);
+        // A directory can't be moved inside itself, so the certificate is the one that fails to
+        // go to the trash
+        fs::create_dir(ssh_dir.join("id_ecdsa-cert.pub")).unwrap();
+        let trash_dir = ssh_dir.join("id_ecdsa-cert.pub").join("trash");
+
+        let err = move_key_pair_to_trash(ssh_dir, &trash_dir, "id_ecdsa", 100).unwrap_err();
+        assert!(err.to_string().contains("'id_ecdsa-cert.pub'"));
+        assert_eq!(
+            dir_file_names(ssh_dir),
+            ["id_ecdsa", "id_ecdsa-cert.pub", "id_ecdsa.pub"]
+        );
+        assert!(dir_file_names(&trash_dir).is_empty());
+
+        // The restore of a key pair whose certificate went missing from the trash
+        let trash_dir = ssh_dir.join("trash");
+        fs::remove_dir_all(ssh_dir.join("id_ecdsa-cert.pub")).unwrap();
+        move_key_pair_to_trash(ssh_dir, &trash_dir, "id_ecdsa", 100).unwrap();
+        let mut trashed_key_pair = read_trash_dir(&trash_dir).unwrap().remove(0);
+        trashed_key_pair
+            .file_names
+            .push(String::from("id_ecdsa-cert.pub"));
+
+        let err = move_key_pair_from_trash(&trashed_key_pair, ssh_dir).unwrap_err();
+        assert!(err.to_string().contains("'id_ecdsa-cert.pub'"));
+        assert_eq!(dir_file_names(ssh_dir), ["trash"]);
+        assert_eq!(
+            dir_file_names(&trashed_key_pair.dir_path),
+            ["id_ecdsa", "id_ecdsa.pub"]
+        );
+    }
+
+    #[test]
+    fn missing_git_config_has_no_values() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(
+            get_git_config_value(&dir.path().join(".gitconfig"), "user.email").unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use ssh_key::{HashAlg, PublicKey};
+
+/// Marker that can prefix a known_hosts line
+///
+/// See the SSH_KNOWN_HOSTS FILE FORMAT section of sshd(8)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KnownHostsMarker {
+    /// The key is a CA key, trusted to sign the host certificates of the matching hosts
+    CertAuthority,
+    /// The key is revoked and must never be accepted
+    Revoked,
+}
+
+impl KnownHostsMarker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CertAuthority => "@cert-authority",
+            Self::Revoked => "@revoked",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "@cert-authority" => Some(Self::CertAuthority),
+            "@revoked" => Some(Self::Revoked),
+            _ => None,
+        }
+    }
+}
+
+impl Display for KnownHostsMarker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A single, non-comment line of a known_hosts file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KnownHostsEntry {
+    pub marker: Option<KnownHostsMarker>,
+    /// Comma separated list of host patterns (or a hashed hostname)
+    pub host_patterns: String,
+    pub key_type: String,
+    /// Base64 encoded key
+    pub key: String,
+    pub comment: Option<String>,
+}
+
+impl KnownHostsEntry {
+    /// Parses a known_hosts line. Returns None for comments, empty lines and lines that can't be
+    /// parsed
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut fields = line.split_whitespace();
+
+        let mut first = fields.next()?;
+        let marker = if first.starts_with('@') {
+            let marker = KnownHostsMarker::parse(first)?;
+            first = fields.next()?;
+            Some(marker)
+        } else {
+            None
+        };
+
+        let key_type = fields.next()?.to_owned();
+        let key = fields.next()?.to_owned();
+        let comment = fields.collect::<Vec<&str>>().join(" ");
+
+        Some(Self {
+            marker,
+            host_patterns: first.to_owned(),
+            key_type,
+            key,
+            comment: if comment.is_empty() {
+                None
+            } else {
+                Some(comment)
+            },
+        })
+    }
+
+    /// Builds an entry from the content of a public key file (`<type> <base64> [comment]`)
+    pub fn from_public_key(
+        marker: Option<KnownHostsMarker>,
+        host_patterns: &str,
+        public_key: &str,
+    ) -> Option<Self> {
+        let mut fields = public_key.split_whitespace();
+        let key_type = fields.next()?.to_owned();
+        let key = fields.next()?.to_owned();
+        let comment = fields.collect::<Vec<&str>>().join(" ");
+
+        Some(Self {
+            marker,
+            host_patterns: host_patterns.to_owned(),
+            key_type,
+            key,
+            comment: if comment.is_empty() {
+                None
+            } else {
+                Some(comment)
+            },
+        })
+    }
+
+    /// Returns true if both entries refer to the same key, no matter the hosts nor the marker
+    pub fn same_key(&self, other: &KnownHostsEntry) -> bool {
+        self.key_type == other.key_type && self.key == other.key
+    }
+
+    pub fn fingerprint(&self) -> Option<String> {
+        PublicKey::from_openssh(&format!("{} {}", self.key_type, self.key))
+            .ok()
+            .map(|key| key.fingerprint(HashAlg::Sha256).to_string())
+    }
+}
+
+impl Display for KnownHostsEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(marker) = self.marker {
+            write!(f, "{} ", marker)?;
+        }
+        write!(f, "{} {} {}", self.host_patterns, self.key_type, self.key)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {}", comment)?;
+        }
+
+        Ok(())
+    }
+}
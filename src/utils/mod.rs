@@ -1,5 +1,14 @@
+pub mod allowed_signers;
+pub mod authorized_keys;
+pub mod client_config;
 pub mod data_parsing;
+pub mod dates;
 pub mod files;
+pub mod known_hosts;
 pub mod math;
+pub mod permissions;
+pub mod qr;
+pub mod secret;
 pub mod strings;
+pub mod trash;
 pub mod constants;
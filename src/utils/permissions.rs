@@ -0,0 +1,209 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+
+use color_eyre::eyre::{eyre, Result};
+
+use super::files;
+
+/// Mode of the ssh directory
+pub const SSH_DIR_MODE: u32 = 0o700;
+/// Mode of the files that must only be readable by the user (private keys, config,
+/// authorized_keys)
+pub const PRIVATE_FILE_MODE: u32 = 0o600;
+/// Mode of the files that can be read by everyone (public keys, known_hosts)
+pub const PUBLIC_FILE_MODE: u32 = 0o644;
+
+/// Kind of the files and directories checked by the audit
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SshFileKind {
+    Home,
+    SshDir,
+    PrivateKey,
+    PublicKey,
+    Config,
+    AuthorizedKeys,
+    KnownHosts,
+}
+
+impl SshFileKind {
+    /// Bits allowed by ssh (with StrictModes) for this kind of file. Any bit outside of the mask
+    /// is a problem
+    fn allowed_mask(&self) -> u32 {
+        match self {
+            // Only writable by the user, the rest of the bits aren't ssh's business
+            Self::Home => 0o7755,
+            Self::SshDir => SSH_DIR_MODE,
+            Self::PrivateKey | Self::Config | Self::AuthorizedKeys => PRIVATE_FILE_MODE,
+            Self::PublicKey | Self::KnownHosts => PUBLIC_FILE_MODE,
+        }
+    }
+}
+
+impl Display for SshFileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Home => write!(f, "home directory"),
+            Self::SshDir => write!(f, "ssh directory"),
+            Self::PrivateKey => write!(f, "private key"),
+            Self::PublicKey => write!(f, "public key"),
+            Self::Config => write!(f, "client config"),
+            Self::AuthorizedKeys => write!(f, "authorized_keys"),
+            Self::KnownHosts => write!(f, "known_hosts"),
+        }
+    }
+}
+
+/// A file or directory whose mode is looser than what ssh expects
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PermissionIssue {
+    pub path: PathBuf,
+    pub kind: SshFileKind,
+    pub mode: u32,
+}
+
+impl PermissionIssue {
+    /// Mode the file will have once fixed. Only the offending bits are removed
+    pub fn fixed_mode(&self) -> u32 {
+        self.mode & self.kind.allowed_mask()
+    }
+}
+
+impl Display for PermissionIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {:04o} -> {:04o}",
+            self.path.to_string_lossy(),
+            self.kind,
+            self.mode,
+            self.fixed_mode()
+        )
+    }
+}
+
+#[cfg(unix)]
+fn get_mode(path: &Path) -> Option<u32> {
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn get_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// Creates the file at the given path (truncating it if it already exists) for writing. The file
+/// gets the given mode instead of the one given by the umask, even if it already existed with
+/// another one
+pub fn create_file_with_mode(path: &Path, mode: u32) -> io::Result<File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(mode);
+
+    let file = options.open(path)?;
+    // The mode given to open only applies to new files
+    set_mode(path, mode)?;
+
+    Ok(file)
+}
+
+/// Recursively creates a directory and all of its missing parents, with the mode of the ssh
+/// directory
+pub fn create_private_dir_all(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    builder.mode(SSH_DIR_MODE);
+
+    builder.create(path)
+}
+
+fn check(path: &Path, kind: SshFileKind) -> Option<PermissionIssue> {
+    let mode = get_mode(path)?;
+
+    if mode & !kind.allowed_mask() == 0 {
+        return None;
+    }
+
+    Some(PermissionIssue {
+        path: path.to_path_buf(),
+        kind,
+        mode,
+    })
+}
+
+/// Checks the modes of the user's home and ssh directories, and of the files in the latter, against
+/// the ones ssh expects
+pub fn audit_ssh_dir() -> Result<Vec<PermissionIssue>> {
+    let ssh_dir = files::get_user_ssh_dir()?;
+    let mut issues = vec![];
+
+    if let Some(home_dir) = dirs::home_dir() {
+        issues.extend(check(&home_dir, SshFileKind::Home));
+    }
+
+    if !ssh_dir.is_dir() {
+        return Ok(issues);
+    }
+    issues.extend(check(&ssh_dir, SshFileKind::SshDir));
+
+    let entries = ssh_dir
+        .read_dir()
+        .map_err(|e| eyre!("failed to read '{}': {}", ssh_dir.to_string_lossy(), e))?;
+
+    let mut files_issues: Vec<PermissionIssue> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let kind = match entry.file_name().to_string_lossy().as_ref() {
+                "config" => SshFileKind::Config,
+                "authorized_keys" | "authorized_keys2" => SshFileKind::AuthorizedKeys,
+                "known_hosts" => SshFileKind::KnownHosts,
+                name if name.ends_with(".pub") => SshFileKind::PublicKey,
+                _ if files::is_private_key_file(&path) => SshFileKind::PrivateKey,
+                _ => return None,
+            };
+
+            check(&path, kind)
+        })
+        .collect();
+    files_issues.sort_by(|a, b| a.path.cmp(&b.path));
+    issues.append(&mut files_issues);
+
+    Ok(issues)
+}
+
+/// Removes the offending bits of the given files and directories
+pub fn fix_permissions(issues: &[PermissionIssue]) -> Result<()> {
+    for issue in issues {
+        set_mode(&issue.path, issue.fixed_mode()).map_err(|e| {
+            eyre!(
+                "failed to change the mode of '{}': {}",
+                issue.path.to_string_lossy(),
+                e
+            )
+        })?;
+    }
+
+    Ok(())
+}
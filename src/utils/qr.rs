@@ -0,0 +1,67 @@
+//! Encoding of text into QR codes small enough to be drawn in a terminal and scanned from it
+
+use qrcodegen::{QrCode, QrCodeEcc, QrSegment, Version};
+
+/// Largest QR version used. Its codes are 57 modules wide, so with the quiet zone they take 61
+/// columns and 31 rows of the terminal
+const MAX_VERSION: u8 = 10;
+
+/// Light modules around the code, which scanners need to find it
+pub const QUIET_ZONE: i32 = 2;
+
+/// Encodes the text in the smallest QR version it fits in, if it fits in one up to MAX_VERSION
+fn encode_part(text: &str) -> Option<QrCode> {
+    QrCode::encode_segments_advanced(
+        &QrSegment::make_segments(text),
+        QrCodeEcc::Low,
+        Version::MIN,
+        Version::new(MAX_VERSION),
+        None,
+        true,
+    )
+    .ok()
+}
+
+/// Encodes the text in a QR code of the smallest version that fits it. Text that doesn't fit in
+/// the largest version used (e.g. long RSA keys) is split in as few parts of the same length as
+/// needed, one QR code each, which have to be joined in order once scanned
+pub fn encode_in_parts(text: &str) -> Vec<QrCode> {
+    let chars: Vec<char> = text.chars().collect();
+
+    for parts_count in 1..=chars.len() {
+        let part_len = chars.len().div_ceil(parts_count);
+        let parts: Option<Vec<QrCode>> = chars
+            .chunks(part_len)
+            .map(|part| encode_part(&part.iter().collect::<String>()))
+            .collect();
+
+        if let Some(parts) = parts {
+            return parts;
+        }
+    }
+
+    vec![]
+}
+
+/// Draws the code (and its quiet zone) with Unicode half blocks, two rows of modules per line, so
+/// the modules look square. Dark modules are drawn with the foreground color, so the code has to
+/// be drawn dark on light to be scanned
+pub fn to_half_blocks(code: &QrCode) -> Vec<String> {
+    let range = -QUIET_ZONE..code.size() + QUIET_ZONE;
+
+    range
+        .clone()
+        .step_by(2)
+        .map(|y| {
+            range
+                .clone()
+                .map(|x| match (code.get_module(x, y), code.get_module(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+//! Passphrases and passwords typed by the user, kept out of memory and logs once not needed
+
+use std::fmt::Debug;
+
+use zeroize::Zeroize;
+
+/// Bytes reserved up front, so typing a usual secret never moves it in memory
+const INITIAL_CAPACITY: usize = 128;
+
+/// Text that is zeroed in memory when it's cleared or dropped, and is never printed. It's typed
+/// one char at a time, so it's kept as UTF-8 bytes that grow without leaving copies behind
+#[derive(Default)]
+pub struct SecretString {
+    bytes: Vec<u8>,
+}
+
+impl SecretString {
+    pub fn push(&mut self, ch: char) {
+        let mut encoded = [0u8; 4];
+        let encoded_len = ch.encode_utf8(&mut encoded).len();
+
+        // Letting the vector reallocate would leave a copy of the secret in the freed memory
+        if self.bytes.len() + encoded_len > self.bytes.capacity() {
+            let mut grown = Vec::with_capacity((self.bytes.capacity() * 2).max(INITIAL_CAPACITY));
+            grown.extend_from_slice(&self.bytes);
+            self.bytes.zeroize();
+            self.bytes = grown;
+        }
+
+        self.bytes.extend_from_slice(&encoded[..encoded_len]);
+        encoded.zeroize();
+    }
+
+    pub fn pop(&mut self) {
+        let Some((last_char_idx, _)) = self.expose().char_indices().last() else {
+            return;
+        };
+
+        self.bytes[last_char_idx..].zeroize();
+        self.bytes.truncate(last_char_idx);
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.zeroize();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Number of chars, which is what's shown of the secret while typing it
+    pub fn len(&self) -> usize {
+        self.expose().chars().count()
+    }
+
+    /// The secret itself, to be handed to whatever needs it and never shown
+    pub fn expose(&self) -> &str {
+        std::str::from_utf8(&self.bytes).unwrap_or_default()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl Clone for SecretString {
+    fn clone(&self) -> Self {
+        let mut bytes = Vec::with_capacity(self.bytes.capacity());
+        bytes.extend_from_slice(&self.bytes);
+
+        Self { bytes }
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretString(***)")
+    }
+}
@@ -5,3 +5,54 @@ pub fn del_last_word(str: String) -> String {
         Some(idx) => str[..idx].to_string(),
     }
 }
+
+/// Matches the value against a pattern with the `*` (any sequence) and `?` (any character)
+/// wildcards, like the patterns of the ssh configuration files
+pub fn match_pattern(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    // Position of the last `*`, and of the value when it was reached, to backtrack to
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut v) = (0, 0);
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, v));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match star {
+                Some((star_p, star_v)) => {
+                    star = Some((star_p, star_v + 1));
+                    p = star_p + 1;
+                    v = star_v + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
+/// Matches the value against a comma separated list of patterns. Patterns starting with `!` are
+/// negated: if one of them matches, the whole list doesn't
+pub fn match_pattern_list(list: &str, value: &str) -> bool {
+    let mut matched = false;
+
+    for pattern in list.split(',').map(str::trim) {
+        match pattern.strip_prefix('!') {
+            Some(negated) if match_pattern(negated, value) => return false,
+            Some(_) => {}
+            None if match_pattern(pattern, value) => matched = true,
+            None => {}
+        }
+    }
+
+    matched
+}
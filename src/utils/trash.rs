@@ -0,0 +1,96 @@
+//! Key pairs moved to the trash directory of sshy instead of being deleted, so they can be
+//! restored
+
+use std::path::{Path, PathBuf};
+
+/// A key pair in the trash. Each one is kept in its own directory, named after the private key and
+/// the time it was deleted (`<name>.<timestamp>`), so the same name can be deleted more than once
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TrashedKeyPair {
+    pub key_name: String,
+    /// Unix timestamp of the deletion
+    pub deleted_at: u64,
+    pub dir_path: PathBuf,
+    /// Names of the files of the key pair (private key, public key and certificate)
+    pub file_names: Vec<String>,
+}
+
+impl TrashedKeyPair {
+    /// Name of the directory a key pair deleted at the given time is kept in
+    pub fn dir_name(key_name: &str, deleted_at: u64) -> String {
+        format!("{}.{}", key_name, deleted_at)
+    }
+
+    /// Reads the key pair kept in the given directory of the trash, if it's one
+    pub fn from_dir(dir_path: &Path, file_names: Vec<String>) -> Option<Self> {
+        let dir_name = dir_path.file_name()?.to_str()?;
+        let (key_name, deleted_at) = dir_name.rsplit_once('.')?;
+        // Directories created in the same second get a `-<n>` suffix
+        let deleted_at = deleted_at.split('-').next()?.parse().ok()?;
+
+        if key_name.is_empty() || file_names.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            key_name: key_name.to_owned(),
+            deleted_at,
+            dir_path: dir_path.to_path_buf(),
+            file_names,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_dir(dir_name: &str) -> Option<(String, u64)> {
+        TrashedKeyPair::from_dir(
+            &Path::new("/home/me/.ssh/trash").join(dir_name),
+            vec![String::from("key")],
+        )
+        .map(|trashed| (trashed.key_name, trashed.deleted_at))
+    }
+
+    #[test]
+    fn dir_names_are_split_at_the_last_dot() {
+        assert_eq!(
+            TrashedKeyPair::dir_name("id_ed25519", 1_700_000_000),
+            "id_ed25519.1700000000"
+        );
+        assert_eq!(
+            from_dir("id_ed25519.1700000000"),
+            Some((String::from("id_ed25519"), 1_700_000_000))
+        );
+        assert_eq!(
+            from_dir("work.github.key.42"),
+            Some((String::from("work.github.key"), 42))
+        );
+    }
+
+    #[test]
+    fn same_second_suffix_is_ignored() {
+        assert_eq!(
+            from_dir("id_rsa.1700000000-1"),
+            Some((String::from("id_rsa"), 1_700_000_000))
+        );
+        assert_eq!(
+            from_dir("id_rsa.1700000000-12"),
+            Some((String::from("id_rsa"), 1_700_000_000))
+        );
+    }
+
+    #[test]
+    fn dirs_that_arent_key_pairs_are_rejected() {
+        assert_eq!(from_dir("id_rsa"), None);
+        assert_eq!(from_dir("id_rsa.old"), None);
+        assert_eq!(from_dir("id_rsa.-1"), None);
+        assert_eq!(from_dir(".1700000000"), None);
+        assert_eq!(from_dir("id_rsa.1700000000.bak"), None);
+        assert_eq!(
+            TrashedKeyPair::from_dir(Path::new("/trash/id_rsa.1700000000"), vec![]),
+            None
+        );
+    }
+}